@@ -0,0 +1,1043 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, TimeZone, Utc};
+
+use crate::datetime::start_of_day;
+use crate::time_entry::TimeEntry;
+
+/// 期間の開始前から続くタイムエントリーを切り詰めて集計するため、取得の開始日時を早める時間。
+pub const CLIP_LOOKBACK_HOURS: i64 = 24;
+
+/// タグのないタイムエントリーを集計する場合のタグ名。
+pub const UNTAGGED_KEY: &str = "(no tag)";
+
+/// 集計単位の名前ごとの集計結果。
+pub type Durations = HashMap<String, GroupDurations>;
+
+/// 集計単位ごとの合計秒数と、タグごとの合計秒数。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupDurations {
+    /// 記録された時間の合計秒数。タグの集計方法によらず、実際の記録時間と一致する。
+    pub total: i64,
+    /// タグ名ごとの合計秒数。
+    pub tags: HashMap<String, i64>,
+    /// 実行中のタイムエントリーの経過時間を含むか。
+    pub in_progress: bool,
+}
+
+/// タイムエントリーの時間をタグへ割り当てる方法。
+#[derive(Debug, Clone, Copy, PartialEq, clap::ArgEnum)]
+pub enum TagMode {
+    /// タグごとに時間全体を加算する。複数のタグがある場合は、それぞれのタグに加算する。
+    PerTag,
+    /// タグごとに加算し、タグのない時間を`(no tag)`にまとめる。
+    Untagged,
+    /// 時間をタグの数で等分して加算し、タグのない時間を`(no tag)`にまとめる。
+    Split,
+}
+
+/// 日付と集計単位ごとの合計秒数を、表の形式にまとめた集計結果。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DurationGrid {
+    /// 列となる日付。
+    pub dates: Vec<NaiveDate>,
+    /// 集計単位ごとの行。名前順に並べる。
+    pub rows: Vec<DurationGridRow>,
+    /// 日付ごとの合計秒数。
+    pub date_totals: Vec<i64>,
+    /// 全体の合計秒数。
+    pub total: i64,
+    /// 実行中のタイムエントリーの経過時間を含むか。
+    pub in_progress: bool,
+}
+
+/// `DurationGrid`の集計単位ごとの行。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DurationGridRow {
+    /// 集計単位の名前。該当する名前がない場合は空文字とする。
+    pub name: String,
+    /// 列の日付ごとの合計秒数。
+    pub durations: Vec<i64>,
+    /// 行の合計秒数。
+    pub total: i64,
+    /// 実行中のタイムエントリーの経過時間を含むか。
+    pub in_progress: bool,
+}
+
+/// 月ごとの集計結果と、期間全体の集計結果。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonthlyDurations {
+    /// 月の最初の日と、その月の集計結果。月の順に並べる。
+    pub months: Vec<(NaiveDate, Durations)>,
+    /// 全ての月を合わせた集計結果。
+    pub total: Durations,
+    /// タイムエントリーを取得できる過去の期間より前のため、集計しなかった月の最初の日。
+    pub skipped: Vec<NaiveDate>,
+}
+
+impl MonthlyDurations {
+    /// 月ごとの集計結果から、全ての月を合わせた集計結果を計算して返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `months` - 月の最初の日と、その月の集計結果
+    pub fn new(months: Vec<(NaiveDate, Durations)>) -> Self {
+        let mut total = Durations::new();
+        for (_, durations) in &months {
+            for (name, group) in durations {
+                let sum: &mut GroupDurations = total.entry(name.clone()).or_default();
+                sum.total += group.total;
+                sum.in_progress |= group.in_progress;
+                for (tag, duration) in &group.tags {
+                    *sum.tags.entry(tag.clone()).or_insert(0) += duration;
+                }
+            }
+        }
+
+        Self {
+            months,
+            total,
+            skipped: vec![],
+        }
+    }
+
+    /// 集計しなかった月を設定する。
+    ///
+    /// 集計しなかった月は、集計結果が空の月と区別して表示する。
+    pub fn skipped(mut self, skipped: Vec<NaiveDate>) -> Self {
+        self.skipped = skipped;
+        self
+    }
+}
+
+/// 集計時にタグの上位でまとめる単位。
+#[derive(Debug, Clone, Copy, PartialEq, clap::ArgEnum)]
+pub enum GroupBy {
+    Project,
+    Client,
+    Workspace,
+    Task,
+}
+
+impl GroupBy {
+    /// タイムエントリーの集計キーを返す。
+    ///
+    /// 該当する名前がない場合は空文字とする。
+    pub fn key(&self, entry: &TimeEntry) -> String {
+        let name = match self {
+            GroupBy::Project => &entry.project,
+            GroupBy::Client => &entry.client,
+            GroupBy::Workspace => &entry.workspace,
+            GroupBy::Task => &entry.task,
+        };
+
+        name.clone().unwrap_or_default()
+    }
+}
+
+/// 集計結果を並べる基準。
+#[derive(Debug, Clone, Copy, PartialEq, clap::ArgEnum)]
+pub enum SortBy {
+    /// 名前順。
+    Name,
+    /// 合計時間の長い順。同じ時間の場合は名前順。
+    Duration,
+    /// 設定したプロジェクトの順。設定にない名前は後ろに名前順で並べる。
+    Custom,
+}
+
+/// 集計結果の並び順。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SortOrder {
+    /// 名前順。
+    #[default]
+    Name,
+    /// 合計時間の長い順。同じ時間の場合は名前順。
+    Duration,
+    /// 指定した名前の順。指定にない名前は後ろに名前順で並べ、タグは名前順とする。
+    Custom(Vec<String>),
+}
+
+impl SortOrder {
+    /// 並べる基準と、設定したプロジェクトの順から並び順を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `sort_by` - 並べる基準
+    /// * `project_order` - `SortBy::Custom`の場合に利用するプロジェクトの順
+    pub fn new(sort_by: SortBy, project_order: &[String]) -> Self {
+        match sort_by {
+            SortBy::Name => SortOrder::Name,
+            SortBy::Duration => SortOrder::Duration,
+            SortBy::Custom => SortOrder::Custom(project_order.to_vec()),
+        }
+    }
+
+    /// 集計単位を並び順に従って並べる。
+    pub fn sort_groups<'a>(
+        &self,
+        durations: &'a Durations,
+    ) -> Vec<(&'a String, &'a GroupDurations)> {
+        let mut groups: Vec<_> = durations.iter().collect();
+        match self {
+            SortOrder::Name => groups.sort_by(|a, b| a.0.cmp(b.0)),
+            SortOrder::Duration => {
+                groups.sort_by(|a, b| b.1.total.cmp(&a.1.total).then(a.0.cmp(b.0)))
+            }
+            SortOrder::Custom(order) => groups.sort_by_key(|(name, _)| {
+                let position = order.iter().position(|ordered| ordered == *name);
+                (position.unwrap_or(order.len()), *name)
+            }),
+        }
+        groups
+    }
+
+    /// タグを並び順に従って並べる。
+    pub fn sort_tags<'a>(&self, tags: &'a HashMap<String, i64>) -> Vec<(&'a String, &'a i64)> {
+        let mut tags: Vec<_> = tags.iter().collect();
+        match self {
+            SortOrder::Name | SortOrder::Custom(_) => tags.sort_by(|a, b| a.0.cmp(b.0)),
+            SortOrder::Duration => tags.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0))),
+        }
+        tags
+    }
+}
+
+/// 実行中のタイムエントリーを、指定した日時までの経過時間で集計できるようにする。
+///
+/// 実行中のタイムエントリーは終了日時を設定しないまま、経過時間を`duration`に設定する。
+/// Toggl APIでは実行中の`duration`は負の値となるため、経過時間を設定していないものは集計しない。
+///
+/// # Arguments
+///
+/// * `time_entries` - 対象のタイムエントリー
+/// * `now` - 経過時間の計算に利用する現在時刻
+pub fn count_running_until(time_entries: Vec<TimeEntry>, now: &DateTime<Utc>) -> Vec<TimeEntry> {
+    time_entries
+        .into_iter()
+        .map(|entry| match entry.stop {
+            Some(_) => entry,
+            None => TimeEntry {
+                duration: (*now - entry.start).num_seconds().max(0),
+                ..entry
+            },
+        })
+        .collect()
+}
+
+/// 集計に利用するタイムエントリーの終了日時を返す。
+///
+/// 実行中の場合は、経過時間を設定している場合のみ開始日時に経過時間を加えた日時とする。
+fn counted_stop(entry: &TimeEntry) -> Option<DateTime<Utc>> {
+    entry
+        .stop
+        .or_else(|| (entry.duration >= 0).then(|| entry.start + TimeDelta::seconds(entry.duration)))
+}
+
+/// 集計に含めるタイムエントリーの合計秒数と、実行中のタイムエントリーを含むかを返す。
+///
+/// 実行中のタイムエントリーは、`count_running_until`で経過時間を設定した場合のみ含める。
+pub fn calc_total_duration(time_entries: &[TimeEntry]) -> (i64, bool) {
+    time_entries
+        .iter()
+        .filter(|entry| counted_stop(entry).is_some())
+        .fold((0, false), |(total, in_progress), entry| {
+            (total + entry.duration, in_progress || entry.stop.is_none())
+        })
+}
+
+/// タイムエントリーのうち、指定した期間に含まれる部分を返す。
+///
+/// 期間と重ならない場合や、経過時間を設定していない実行中のタイムエントリーは`None`を返す。
+/// 実行中のタイムエントリーは、終了日時を設定しないまま経過時間を切り詰める。
+///
+/// # Arguments
+///
+/// * `entry` - 対象のタイムエントリー
+/// * `start_at` - 期間の開始日時
+/// * `end_at` - 期間の終了日時(この日時を含まない)
+pub fn clip_time_entry(
+    entry: &TimeEntry,
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+) -> Option<TimeEntry> {
+    let stop = counted_stop(entry)?;
+    let clipped_start = entry.start.max(*start_at);
+    let clipped_stop = stop.min(*end_at);
+    if clipped_start >= clipped_stop {
+        return None;
+    }
+    // 期間内に収まる場合は、記録された時間をそのまま利用する
+    if clipped_start == entry.start && clipped_stop == stop {
+        return Some(entry.clone());
+    }
+
+    Some(TimeEntry {
+        start: clipped_start,
+        stop: entry.stop.map(|_| clipped_stop),
+        duration: (clipped_stop - clipped_start).num_seconds(),
+        ..entry.clone()
+    })
+}
+
+/// タイムエントリーが指定した期間と重なるかを返す。
+///
+/// 実行中のタイムエントリーは、期間の終了前に開始していれば重なるとする。
+pub fn overlaps(entry: &TimeEntry, start_at: &DateTime<Utc>, end_at: &DateTime<Utc>) -> bool {
+    entry.start < *end_at && entry.stop.map_or(true, |stop| *start_at < stop)
+}
+
+/// タイムエントリーを、指定した期間に含まれる部分のみに切り詰める。
+///
+/// 期間と重ならないタイムエントリーは除く。
+/// 経過時間を設定していない実行中のタイムエントリーは切り詰められないため、期間と重なればそのまま含める。
+pub fn clip_time_entries(
+    time_entries: &[TimeEntry],
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+) -> Vec<TimeEntry> {
+    time_entries
+        .iter()
+        .filter(|entry| overlaps(entry, start_at, end_at))
+        .map(|entry| clip_time_entry(entry, start_at, end_at).unwrap_or_else(|| entry.clone()))
+        .collect()
+}
+
+/// タイムエントリーを、指定したタイムゾーンの日ごとに分割する。
+///
+/// 日付をまたぐタイムエントリーは、それぞれの日に含まれる部分に分割する。
+/// 経過時間を設定していない実行中のタイムエントリーは、開始日にそのまま含める。
+///
+/// # Arguments
+///
+/// * `time_entries` - 分割するタイムエントリー
+/// * `tz` - 日の区切りに利用するタイムゾーン
+pub fn split_by_day<Tz: TimeZone>(
+    time_entries: &[TimeEntry],
+    tz: &Tz,
+) -> Result<HashMap<NaiveDate, Vec<TimeEntry>>> {
+    let mut daily_time_entries: HashMap<NaiveDate, Vec<TimeEntry>> = HashMap::new();
+    for entry in time_entries {
+        let mut date = entry.start.with_timezone(tz).date_naive();
+        let Some(stop) = counted_stop(entry) else {
+            daily_time_entries
+                .entry(date)
+                .or_default()
+                .push(entry.clone());
+            continue;
+        };
+
+        let mut day_start = start_of_day(&date, tz)?;
+        loop {
+            let next_date = date
+                .succ_opt()
+                .with_context(|| format!("Failed to get the next day of {}", date))?;
+            let day_end = start_of_day(&next_date, tz)?;
+            if let Some(clipped) = clip_time_entry(entry, &day_start, &day_end) {
+                daily_time_entries.entry(date).or_default().push(clipped);
+            }
+            if stop <= day_end {
+                break;
+            }
+            (date, day_start) = (next_date, day_end);
+        }
+    }
+
+    Ok(daily_time_entries)
+}
+
+/// プロジェクトなどの指定した単位ごと、かつタグごとの集計結果を計算する。
+///
+/// 実行中のtime entryは、`count_running_until`で経過時間を設定した場合のみ集計する。
+///
+/// # Arguments
+///
+/// * `time_entries` - 集計するタイムエントリー
+/// * `group_by` - タグの上位でまとめる単位
+/// * `tag_mode` - 時間をタグへ割り当てる方法
+pub fn calc_project_tag_duration(
+    time_entries: &[TimeEntry],
+    group_by: GroupBy,
+    tag_mode: TagMode,
+) -> Durations {
+    time_entries.iter().fold(HashMap::new(), |mut acc, entry| {
+        if counted_stop(entry).is_none() {
+            return acc;
+        }
+
+        let group = acc.entry(group_by.key(entry)).or_default();
+        group.total += entry.duration;
+        group.in_progress |= entry.stop.is_none();
+        tag_durations(entry, tag_mode)
+            .into_iter()
+            .for_each(|(tag, duration)| {
+                *group.tags.entry(tag).or_insert(0) += duration;
+            });
+        acc
+    })
+}
+
+/// 日ごとのタイムエントリーを、日付と指定した単位ごとの表にまとめる。
+///
+/// 指定した日付にタイムエントリーがない場合は、その列を0とする。
+///
+/// # Arguments
+///
+/// * `daily_time_entries` - 日付ごとのタイムエントリー
+/// * `dates` - 列とする日付
+/// * `group_by` - 行としてまとめる単位
+pub fn calc_duration_grid(
+    daily_time_entries: &HashMap<NaiveDate, Vec<TimeEntry>>,
+    dates: &[NaiveDate],
+    group_by: GroupBy,
+) -> DurationGrid {
+    let mut rows: BTreeMap<String, (Vec<i64>, bool)> = BTreeMap::new();
+    for (index, date) in dates.iter().enumerate() {
+        let Some(time_entries) = daily_time_entries.get(date) else {
+            continue;
+        };
+        let durations = calc_project_tag_duration(time_entries, group_by, TagMode::PerTag);
+        for (name, group) in durations {
+            let (row, in_progress) = rows
+                .entry(name)
+                .or_insert_with(|| (vec![0; dates.len()], false));
+            row[index] += group.total;
+            *in_progress |= group.in_progress;
+        }
+    }
+
+    let rows: Vec<DurationGridRow> = rows
+        .into_iter()
+        .map(|(name, (durations, in_progress))| DurationGridRow {
+            name,
+            total: durations.iter().sum(),
+            durations,
+            in_progress,
+        })
+        .collect();
+    let date_totals: Vec<i64> = (0..dates.len())
+        .map(|index| rows.iter().map(|row| row.durations[index]).sum())
+        .collect();
+
+    DurationGrid {
+        dates: dates.to_vec(),
+        total: date_totals.iter().sum(),
+        in_progress: rows.iter().any(|row| row.in_progress),
+        rows,
+        date_totals,
+    }
+}
+
+/// タイムエントリーの時間を、集計方法に従ってタグへ割り当てる。
+fn tag_durations(entry: &TimeEntry, tag_mode: TagMode) -> Vec<(String, i64)> {
+    if entry.tags.is_empty() {
+        return match tag_mode {
+            TagMode::PerTag => vec![],
+            TagMode::Untagged | TagMode::Split => vec![(UNTAGGED_KEY.to_string(), entry.duration)],
+        };
+    }
+
+    match tag_mode {
+        TagMode::PerTag | TagMode::Untagged => entry
+            .tags
+            .iter()
+            .map(|tag| (tag.clone(), entry.duration))
+            .collect(),
+        TagMode::Split => {
+            // 割り切れない秒数は先頭のタグから1秒ずつ割り当て、合計を記録時間と一致させる
+            let count = entry.tags.len() as i64;
+            let (base, remainder) = (entry.duration / count, entry.duration % count);
+            entry
+                .tags
+                .iter()
+                .enumerate()
+                .map(|(index, tag)| {
+                    let extra = if (index as i64) < remainder { 1 } else { 0 };
+                    (tag.clone(), base + extra)
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use rstest::rstest;
+
+    use super::calc_duration_grid;
+    use super::calc_project_tag_duration;
+    use super::calc_total_duration;
+    use super::clip_time_entries;
+    use super::clip_time_entry;
+    use super::count_running_until;
+    use super::overlaps;
+    use super::split_by_day;
+    use super::DurationGrid;
+    use super::DurationGridRow;
+    use super::GroupBy;
+    use super::GroupDurations;
+    use super::MonthlyDurations;
+    use super::SortBy;
+    use super::SortOrder;
+    use super::TagMode;
+    use crate::time_entry::TimeEntry;
+
+    /// 指定した単位とタグごとに集計できることを確認する。
+    #[rstest]
+    #[case::project(GroupBy::Project, "project 1")]
+    #[case::client(GroupBy::Client, "client 1")]
+    #[case::workspace(GroupBy::Workspace, "workspace 1")]
+    #[case::task(GroupBy::Task, "task 1")]
+    fn test_calc_project_tag_duration(#[case] group_by: GroupBy, #[case] key: &str) {
+        let entries = [
+            dummy_entry(1, 3600, &["tag 1"]),
+            dummy_entry(2, 1800, &["tag 1", "tag 2"]),
+        ];
+        let expected = HashMap::from([(
+            key.to_string(),
+            group_durations(5400, &[("tag 1", 5400), ("tag 2", 1800)]),
+        )]);
+
+        let result = calc_project_tag_duration(&entries, group_by, TagMode::PerTag);
+
+        assert_eq!(expected, result);
+    }
+
+    /// 名前がない場合は空文字の単位として集計し、実行中のタイムエントリーは集計しないことを確認する。
+    #[test]
+    fn test_calc_project_tag_duration_no_name_and_running() {
+        let entries = [
+            TimeEntry {
+                project: None,
+                ..dummy_entry(1, 3600, &["tag 1"])
+            },
+            TimeEntry {
+                stop: None,
+                ..dummy_entry(2, -1, &["tag 1"])
+            },
+        ];
+        let expected = HashMap::from([("".to_string(), group_durations(3600, &[("tag 1", 3600)]))]);
+
+        let result = calc_project_tag_duration(&entries, GroupBy::Project, TagMode::PerTag);
+
+        assert_eq!(expected, result);
+    }
+
+    /// タグの集計方法ごとに時間を割り当て、合計は実際の記録時間となることを確認する。
+    #[rstest]
+    #[case::per_tag(TagMode::PerTag, &[("tag 1", 3601), ("tag 2", 3601)])]
+    #[case::untagged(
+        TagMode::Untagged,
+        &[("tag 1", 3601), ("tag 2", 3601), ("(no tag)", 600)],
+    )]
+    #[case::split(
+        TagMode::Split,
+        &[("tag 1", 1801), ("tag 2", 1800), ("(no tag)", 600)],
+    )]
+    fn test_calc_project_tag_duration_tag_mode(
+        #[case] tag_mode: TagMode,
+        #[case] expected_tags: &[(&str, i64)],
+    ) {
+        let entries = [
+            dummy_entry(1, 3601, &["tag 1", "tag 2"]),
+            dummy_entry(2, 600, &[]),
+        ];
+        let expected = HashMap::from([(
+            "project 1".to_string(),
+            group_durations(4201, expected_tags),
+        )]);
+
+        let result = calc_project_tag_duration(&entries, GroupBy::Project, tag_mode);
+
+        assert_eq!(expected, result);
+    }
+
+    /// 等分する場合に、タグごとの時間の合計が記録時間と一致することを確認する。
+    #[rstest]
+    #[case::divisible(3600, 3)]
+    #[case::remainder(3602, 3)]
+    #[case::shorter_than_tags(2, 3)]
+    fn test_calc_project_tag_duration_split_sum(#[case] duration: i64, #[case] tag_count: usize) {
+        let tags: Vec<String> = (0..tag_count).map(|i| format!("tag {}", i)).collect();
+        let tags: Vec<&str> = tags.iter().map(|tag| tag.as_str()).collect();
+        let entries = [dummy_entry(1, duration, &tags)];
+
+        let result = calc_project_tag_duration(&entries, GroupBy::Project, TagMode::Split);
+
+        let group = &result["project 1"];
+        assert_eq!(duration, group.total);
+        assert_eq!(duration, group.tags.values().sum::<i64>());
+    }
+
+    /// 集計単位とタグが並び順に従って並ぶことを確認する。
+    #[rstest]
+    #[case::name(
+        SortOrder::Name,
+        &["", "project a", "project b", "project c"],
+        &["tag a", "tag b", "tag c"],
+    )]
+    #[case::duration(
+        SortOrder::Duration,
+        &["project b", "", "project c", "project a"],
+        &["tag c", "tag a", "tag b"],
+    )]
+    #[case::custom(
+        SortOrder::Custom(vec!["project c".to_string(), "unknown".to_string(), "project a".to_string()]),
+        &["project c", "project a", "", "project b"],
+        &["tag a", "tag b", "tag c"],
+    )]
+    fn test_sort_order(
+        #[case] order: SortOrder,
+        #[case] expected_groups: &[&str],
+        #[case] expected_tags: &[&str],
+    ) {
+        // HashMapの反復順はインスタンスごとに変わるため、毎回作成して同じ順になることを確認する
+        for _ in 0..10 {
+            let durations = HashMap::from([
+                ("project a".to_string(), group_durations(600, &[])),
+                ("project b".to_string(), group_durations(3600, &[])),
+                ("project c".to_string(), group_durations(1800, &[])),
+                ("".to_string(), group_durations(1800, &[])),
+            ]);
+            let tags = HashMap::from([
+                ("tag b".to_string(), 600),
+                ("tag a".to_string(), 1800),
+                ("tag c".to_string(), 3600),
+            ]);
+
+            let groups: Vec<&str> = order
+                .sort_groups(&durations)
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            let sorted_tags: Vec<&str> = order
+                .sort_tags(&tags)
+                .into_iter()
+                .map(|(tag, _)| tag.as_str())
+                .collect();
+
+            assert_eq!(expected_groups, groups.as_slice());
+            assert_eq!(expected_tags, sorted_tags.as_slice());
+        }
+    }
+
+    /// 並べる基準から並び順を作成できることを確認する。
+    #[rstest]
+    #[case::name(SortBy::Name, SortOrder::Name)]
+    #[case::duration(SortBy::Duration, SortOrder::Duration)]
+    #[case::custom(SortBy::Custom, SortOrder::Custom(vec!["project 1".to_string()]))]
+    fn test_sort_order_new(#[case] sort_by: SortBy, #[case] expected: SortOrder) {
+        let result = SortOrder::new(sort_by, &["project 1".to_string()]);
+
+        assert_eq!(expected, result);
+    }
+
+    /// タイムエントリーのうち、期間に含まれる部分のみを返すことを確認する。
+    #[rstest]
+    #[case::inside("2024-01-01T10:00:00Z", 3600, Some(("2024-01-01T10:00:00Z", 3600)))]
+    #[case::cross_start("2023-12-31T22:00:00Z", 4 * 3600, Some(("2024-01-01T00:00:00Z", 2 * 3600)))]
+    #[case::cross_end("2024-01-31T23:00:00Z", 2 * 3600, Some(("2024-01-31T23:00:00Z", 3600)))]
+    #[case::cover("2023-12-31T00:00:00Z", 33 * 24 * 3600, Some(("2024-01-01T00:00:00Z", 31 * 24 * 3600)))]
+    #[case::before("2023-12-31T22:00:00Z", 2 * 3600, None)]
+    #[case::after("2024-02-01T00:00:00Z", 3600, None)]
+    fn test_clip_time_entry(
+        #[case] start: &str,
+        #[case] duration: i64,
+        #[case] expected: Option<(&str, i64)>,
+    ) {
+        let entry = TimeEntry {
+            start: datetime(start),
+            stop: Some(datetime(start) + chrono::Duration::seconds(duration)),
+            ..dummy_entry(1, duration, &["tag 1"])
+        };
+        let start_at = datetime("2024-01-01T00:00:00Z");
+        let end_at = datetime("2024-02-01T00:00:00Z");
+
+        let result = clip_time_entry(&entry, &start_at, &end_at);
+
+        let expected = expected.map(|(start, duration)| TimeEntry {
+            start: datetime(start),
+            stop: Some(datetime(start) + chrono::Duration::seconds(duration)),
+            duration,
+            ..entry.clone()
+        });
+        assert_eq!(expected, result);
+    }
+
+    /// 期間と重なるタイムエントリーを判定できることを確認する。
+    ///
+    /// 実行中のタイムエントリーは、期間の終了前に開始していれば重なるとする。
+    #[rstest]
+    #[case::inside("2024-01-01T10:00:00Z", Some(3600), true)]
+    #[case::cross_start("2023-12-31T22:00:00Z", Some(4 * 3600), true)]
+    #[case::stop_at_start("2023-12-31T22:00:00Z", Some(2 * 3600), false)]
+    #[case::start_at_end("2024-01-02T00:00:00Z", Some(3600), false)]
+    #[case::running_from_before("2023-12-31T22:00:00Z", None, true)]
+    #[case::running_after("2024-01-02T00:00:00Z", None, false)]
+    fn test_overlaps(#[case] start: &str, #[case] duration: Option<i64>, #[case] expected: bool) {
+        let entry = TimeEntry {
+            start: datetime(start),
+            stop: duration.map(|duration| datetime(start) + chrono::Duration::seconds(duration)),
+            ..dummy_entry(1, duration.unwrap_or(-1), &[])
+        };
+
+        let result = overlaps(
+            &entry,
+            &datetime("2024-01-01T00:00:00Z"),
+            &datetime("2024-01-02T00:00:00Z"),
+        );
+
+        assert_eq!(expected, result);
+    }
+
+    /// 経過時間を設定していない実行中のタイムエントリーは、切り詰めずにそのまま含めることを確認する。
+    ///
+    /// 期間の終了後に開始した実行中のタイムエントリーは除く。
+    #[test]
+    fn test_clip_time_entries_running() {
+        let entries = [
+            dummy_entry(1, 3600, &["tag 1"]),
+            TimeEntry {
+                stop: None,
+                ..dummy_entry(2, -1, &["tag 1"])
+            },
+            TimeEntry {
+                start: datetime("2024-01-02T00:00:00Z"),
+                stop: None,
+                ..dummy_entry(3, -1, &["tag 1"])
+            },
+        ];
+        let start_at = datetime("2024-01-01T00:00:00Z");
+        let end_at = datetime("2024-01-02T00:00:00Z");
+
+        let result = clip_time_entries(&entries, &start_at, &end_at);
+
+        assert_eq!(vec![entries[0].clone(), entries[1].clone()], result);
+    }
+
+    /// 日付をまたぐタイムエントリーを、タイムゾーンの日ごとに分割することを確認する。
+    ///
+    /// 夏時間の切り替え日は、1日の長さが23時間または25時間となる。
+    #[rstest]
+    #[case::midnight(
+        chrono_tz::Asia::Tokyo,
+        "2024-01-01T13:00:00Z",
+        4 * 3600,
+        &[("2024-01-01", 2 * 3600), ("2024-01-02", 2 * 3600)],
+    )]
+    #[case::within_day(
+        chrono_tz::Asia::Tokyo,
+        "2024-01-01T00:00:00Z",
+        3600,
+        &[("2024-01-01", 3600)],
+    )]
+    #[case::multiple_days(
+        chrono_tz::UTC,
+        "2024-01-01T12:00:00Z",
+        48 * 3600,
+        &[("2024-01-01", 12 * 3600), ("2024-01-02", 24 * 3600), ("2024-01-03", 12 * 3600)],
+    )]
+    #[case::dst_start(
+        chrono_tz::America::New_York,
+        "2024-03-10T04:00:00Z",
+        24 * 3600,
+        &[("2024-03-09", 3600), ("2024-03-10", 23 * 3600)],
+    )]
+    #[case::dst_end(
+        chrono_tz::America::New_York,
+        "2024-11-03T03:00:00Z",
+        26 * 3600,
+        &[("2024-11-02", 3600), ("2024-11-03", 25 * 3600)],
+    )]
+    fn test_split_by_day(
+        #[case] tz: chrono_tz::Tz,
+        #[case] start: &str,
+        #[case] duration: i64,
+        #[case] expected: &[(&str, i64)],
+    ) {
+        let entry = TimeEntry {
+            start: datetime(start),
+            stop: Some(datetime(start) + chrono::Duration::seconds(duration)),
+            ..dummy_entry(1, duration, &["tag 1"])
+        };
+
+        let result = split_by_day(&[entry], &tz).unwrap();
+
+        let mut durations: Vec<(NaiveDate, i64)> = result
+            .into_iter()
+            .map(|(date, entries)| (date, entries.iter().map(|entry| entry.duration).sum()))
+            .collect();
+        durations.sort();
+        let expected: Vec<(NaiveDate, i64)> = expected
+            .iter()
+            .map(|(date, duration)| (date.parse().unwrap(), *duration))
+            .collect();
+        assert_eq!(expected, durations);
+    }
+
+    /// 終了していないタイムエントリーは、開始日に含めることを確認する。
+    #[test]
+    fn test_split_by_day_running() {
+        let entry = TimeEntry {
+            stop: None,
+            ..dummy_entry(1, -1, &["tag 1"])
+        };
+
+        let result = split_by_day(&[entry.clone()], &chrono_tz::UTC).unwrap();
+
+        let expected = HashMap::from([(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), vec![entry])]);
+        assert_eq!(expected, result);
+    }
+
+    /// 実行中のタイムエントリーに、現在までの経過時間を設定することを確認する。
+    #[test]
+    fn test_count_running_until() {
+        let stopped = dummy_entry(1, 3600, &["tag 1"]);
+        let running = TimeEntry {
+            stop: None,
+            ..dummy_entry(2, -1704067200, &["tag 1"])
+        };
+        let now = datetime("2024-01-01T00:30:00Z");
+
+        let result = count_running_until(vec![stopped.clone(), running.clone()], &now);
+
+        let expected = vec![
+            stopped,
+            TimeEntry {
+                duration: 1800,
+                ..running
+            },
+        ];
+        assert_eq!(expected, result);
+    }
+
+    /// 経過時間を設定した実行中のタイムエントリーを集計し、実行中であることを示すことを確認する。
+    #[test]
+    fn test_calc_project_tag_duration_running() {
+        let entries = count_running_until(
+            vec![
+                dummy_entry(1, 3600, &["tag 1"]),
+                TimeEntry {
+                    stop: None,
+                    project: Some("project 2".to_string()),
+                    ..dummy_entry(2, -1704067200, &["tag 1"])
+                },
+            ],
+            &datetime("2024-01-01T00:30:00Z"),
+        );
+        let expected = HashMap::from([
+            (
+                "project 1".to_string(),
+                group_durations(3600, &[("tag 1", 3600)]),
+            ),
+            (
+                "project 2".to_string(),
+                GroupDurations {
+                    in_progress: true,
+                    ..group_durations(1800, &[("tag 1", 1800)])
+                },
+            ),
+        ]);
+
+        let result = calc_project_tag_duration(&entries, GroupBy::Project, TagMode::PerTag);
+
+        assert_eq!(expected, result);
+    }
+
+    /// 合計時間に、経過時間を設定した実行中のタイムエントリーのみを含めることを確認する。
+    #[rstest]
+    #[case::stopped(&[], 3600, false)]
+    #[case::running_not_counted(&[-1704067200], 3600, false)]
+    #[case::running_counted(&[1800], 5400, true)]
+    fn test_calc_total_duration(
+        #[case] running_durations: &[i64],
+        #[case] expected_total: i64,
+        #[case] expected_in_progress: bool,
+    ) {
+        let mut entries = vec![dummy_entry(1, 3600, &["tag 1"])];
+        entries.extend(running_durations.iter().map(|duration| TimeEntry {
+            stop: None,
+            ..dummy_entry(2, *duration, &["tag 1"])
+        }));
+
+        let result = calc_total_duration(&entries);
+
+        assert_eq!((expected_total, expected_in_progress), result);
+    }
+
+    /// 経過時間を設定した実行中のタイムエントリーは、終了日時を設定しないまま切り詰めることを確認する。
+    #[test]
+    fn test_clip_time_entry_running() {
+        let entry = TimeEntry {
+            start: datetime("2024-01-01T23:00:00Z"),
+            stop: None,
+            duration: 2 * 3600,
+            ..dummy_entry(1, 0, &["tag 1"])
+        };
+        let start_at = datetime("2024-01-01T00:00:00Z");
+        let end_at = datetime("2024-01-02T00:00:00Z");
+
+        let result = clip_time_entry(&entry, &start_at, &end_at);
+
+        let expected = TimeEntry {
+            duration: 3600,
+            ..entry
+        };
+        assert_eq!(Some(expected), result);
+    }
+
+    /// 日付と単位ごとの表にまとめ、行、列、全体の合計を計算することを確認する。
+    #[test]
+    fn test_calc_duration_grid() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let daily_time_entries = HashMap::from([
+            (
+                date(1),
+                vec![
+                    dummy_entry(1, 3600, &["tag 1"]),
+                    TimeEntry {
+                        project: Some("project 2".to_string()),
+                        ..dummy_entry(2, 1800, &[])
+                    },
+                ],
+            ),
+            (date(3), vec![dummy_entry(3, 900, &["tag 1", "tag 2"])]),
+            // 列に含まれない日付は集計しない
+            (date(4), vec![dummy_entry(4, 600, &["tag 1"])]),
+        ]);
+
+        let result = calc_duration_grid(
+            &daily_time_entries,
+            &[date(1), date(2), date(3)],
+            GroupBy::Project,
+        );
+
+        let expected = DurationGrid {
+            dates: vec![date(1), date(2), date(3)],
+            rows: vec![
+                DurationGridRow {
+                    name: "project 1".to_string(),
+                    durations: vec![3600, 0, 900],
+                    total: 4500,
+                    in_progress: false,
+                },
+                DurationGridRow {
+                    name: "project 2".to_string(),
+                    durations: vec![1800, 0, 0],
+                    total: 1800,
+                    in_progress: false,
+                },
+            ],
+            date_totals: vec![5400, 0, 900],
+            total: 6300,
+            in_progress: false,
+        };
+        assert_eq!(expected, result);
+    }
+
+    /// 経過時間を設定した実行中のタイムエントリーを含む行と全体に、実行中の印を付けることを確認する。
+    #[test]
+    fn test_calc_duration_grid_in_progress() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let daily_time_entries = HashMap::from([(
+            date,
+            vec![
+                dummy_entry(1, 3600, &[]),
+                TimeEntry {
+                    project: Some("project 2".to_string()),
+                    stop: None,
+                    ..dummy_entry(2, 1800, &[])
+                },
+            ],
+        )]);
+
+        let result = calc_duration_grid(&daily_time_entries, &[date], GroupBy::Project);
+
+        let in_progress: Vec<bool> = result.rows.iter().map(|row| row.in_progress).collect();
+        assert_eq!(vec![false, true], in_progress);
+        assert!(result.in_progress);
+        assert_eq!(5400, result.total);
+    }
+
+    /// 月ごとの集計結果を、集計単位とタグごとに合計することを確認する。
+    #[test]
+    fn test_monthly_durations_new() {
+        let month = |month| NaiveDate::from_ymd_opt(2024, month, 1).unwrap();
+        let months = vec![
+            (
+                month(1),
+                HashMap::from([
+                    (
+                        "project 1".to_string(),
+                        group_durations(3600, &[("tag 1", 3600)]),
+                    ),
+                    ("project 2".to_string(), group_durations(1800, &[])),
+                ]),
+            ),
+            (month(2), HashMap::new()),
+            (
+                month(3),
+                HashMap::from([(
+                    "project 1".to_string(),
+                    GroupDurations {
+                        in_progress: true,
+                        ..group_durations(900, &[("tag 1", 600), ("tag 2", 900)])
+                    },
+                )]),
+            ),
+        ];
+
+        let result = MonthlyDurations::new(months.clone());
+
+        let expected_total = HashMap::from([
+            (
+                "project 1".to_string(),
+                GroupDurations {
+                    in_progress: true,
+                    ..group_durations(4500, &[("tag 1", 4200), ("tag 2", 900)])
+                },
+            ),
+            ("project 2".to_string(), group_durations(1800, &[])),
+        ]);
+        assert_eq!(months, result.months);
+        assert_eq!(expected_total, result.total);
+    }
+
+    /// テスト用にRFC 3339形式の文字列から日時を作成する。
+    fn datetime(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().to_utc()
+    }
+
+    /// テスト用に集計結果を作成する。
+    fn group_durations(total: i64, tags: &[(&str, i64)]) -> GroupDurations {
+        GroupDurations {
+            total,
+            tags: tags
+                .iter()
+                .map(|(tag, duration)| (tag.to_string(), *duration))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(id: i64, duration: i64, tags: &[&str]) -> TimeEntry {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + chrono::Duration::seconds(duration)),
+            duration,
+            project: Some("project 1".to_string()),
+            client: Some("client 1".to_string()),
+            workspace: Some("workspace 1".to_string()),
+            task: Some("task 1".to_string()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::{env, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::datetime::Zone;
+
+/// Toggl APIのデフォルトのURL。
+pub const DEFAULT_API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+/// Toggl Reports APIのデフォルトのURL。
+pub const DEFAULT_REPORTS_API_URL: &str = "https://api.track.toggl.com/reports/api/v3";
+
+/// 設定ファイルのパスを指定する環境変数。
+const CONFIG_PATH_ENV: &str = "TOOGGLS_CONFIG";
+
+/// アプリケーションの設定。
+///
+/// 設定ファイル(TOML形式)から読み込み、環境変数で上書きする。
+/// 設定ファイルは環境変数`TOOGGLS_CONFIG`で指定でき、指定がない場合はユーザーの設定ディレクトリの`tooggls/config.toml`を利用する。
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub api: ApiConfig,
+    pub report: ReportConfig,
+}
+
+/// Toggl APIとの通信に関する設定。
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    /// Toggl APIのURL。環境変数`TOOGGLS_API_URL`で上書きできる。
+    pub base_url: String,
+    /// Toggl Reports APIのURL。環境変数`TOOGGLS_REPORTS_API_URL`で上書きできる。
+    pub reports_base_url: String,
+    /// リクエストのタイムアウト秒数。環境変数`TOOGGLS_TIMEOUT_SECS`で上書きできる。
+    pub timeout_secs: Option<u64>,
+    /// 通信に利用するプロキシのURL。環境変数`TOOGGLS_PROXY`で上書きできる。
+    pub proxy: Option<String>,
+    /// 追加で信頼するCA証明書(PEM形式)のパス。環境変数`TOOGGLS_CA_CERT`で上書きできる。
+    pub ca_cert: Option<PathBuf>,
+    /// 失敗したリクエストを再送する場合の、最初の送信を含めた最大試行回数。
+    pub max_attempts: u32,
+    /// 1回目の再送までの待機時間(ミリ秒)。再送のたびに2倍になる。
+    pub initial_backoff_ms: u64,
+    /// 再送までの待機時間の上限(ミリ秒)。
+    pub max_backoff_ms: u64,
+    /// 1秒あたりの最大リクエスト数。0の場合は制限しない。
+    pub requests_per_second: u32,
+    /// 解釈できないタイムエントリーがある場合にエラーとする。`false`の場合は警告を出力して読み飛ばす。
+    pub strict: bool,
+    /// タイムエントリーの一覧を1回のリクエストで取得する最大の日数。これより長い期間は分割して取得する。
+    pub max_range_days: u32,
+    /// Toggl APIでタイムエントリーの一覧を取得できる過去の日数。これより前の期間は取得しない。0の場合は制限しない。
+    pub history_days: u32,
+}
+
+/// 集計結果の表示に関する設定。
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReportConfig {
+    /// `--sort custom`で表示するプロジェクトの順。
+    pub project_order: Vec<String>,
+    /// タイムエントリーを集計する期間や日の範囲で切り詰め、範囲に含まれる部分のみを集計する。
+    pub clip_to_range: bool,
+    /// `yearly`と`quarterly`で利用する年度が始まる月(1から12)。
+    pub fiscal_year_start_month: u32,
+    /// 日や月の境界と時刻の表示に利用するタイムゾーン。`Asia/Tokyo`のようなIANAの名前か`local`を指定する。
+    pub timezone: Zone,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            project_order: vec![],
+            clip_to_range: true,
+            fiscal_year_start_month: 1,
+            timezone: Zone::Local,
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_API_URL.to_string(),
+            reports_base_url: DEFAULT_REPORTS_API_URL.to_string(),
+            timeout_secs: None,
+            proxy: None,
+            ca_cert: None,
+            max_attempts: 4,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            requests_per_second: 4,
+            strict: false,
+            max_range_days: 31,
+            history_days: 90,
+        }
+    }
+}
+
+impl Config {
+    /// 設定ファイルと環境変数から設定を読み込む。
+    ///
+    /// デフォルトの設定ファイルが存在しない場合は、デフォルト値に環境変数を反映した設定を返す。
+    /// 環境変数で指定された設定ファイルが存在しない場合はエラーを返す。
+    pub fn load() -> Result<Self> {
+        let mut config = match env::var(CONFIG_PATH_ENV).ok().map(PathBuf::from) {
+            Some(path) => Self::from_file(&path)?,
+            None => match default_config_path() {
+                Some(path) if path.exists() => Self::from_file(&path)?,
+                _ => Self::default(),
+            },
+        };
+        config.apply_env(|key| env::var(key).ok())?;
+
+        Ok(config)
+    }
+
+    /// 設定ファイルを読み込む。
+    fn from_file(path: &PathBuf) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.to_string_lossy()))?;
+
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.to_string_lossy()))
+    }
+
+    /// TOML形式の文字列から設定を読み込む。
+    fn parse(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Invalid config format")
+    }
+
+    /// 環境変数で設定を上書きする。
+    ///
+    /// # Arguments
+    ///
+    /// * `var` - 環境変数名から値を取得する関数
+    fn apply_env<F: Fn(&str) -> Option<String>>(&mut self, var: F) -> Result<()> {
+        if let Some(base_url) = var("TOOGGLS_API_URL") {
+            self.api.base_url = base_url;
+        }
+        if let Some(reports_base_url) = var("TOOGGLS_REPORTS_API_URL") {
+            self.api.reports_base_url = reports_base_url;
+        }
+        if let Some(timeout_secs) = var("TOOGGLS_TIMEOUT_SECS") {
+            let timeout_secs = timeout_secs
+                .parse()
+                .with_context(|| format!("Invalid TOOGGLS_TIMEOUT_SECS: {}", timeout_secs))?;
+            self.api.timeout_secs = Some(timeout_secs);
+        }
+        if let Some(proxy) = var("TOOGGLS_PROXY") {
+            self.api.proxy = Some(proxy);
+        }
+        if let Some(ca_cert) = var("TOOGGLS_CA_CERT") {
+            self.api.ca_cert = Some(PathBuf::from(ca_cert));
+        }
+
+        Ok(())
+    }
+}
+
+/// デフォルトの設定ファイルのパスを返す。
+fn default_config_path() -> Option<PathBuf> {
+    let app_name = env!("CARGO_PKG_NAME");
+    dirs::config_dir().map(|dir| dir.join(app_name).join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use rstest::rstest;
+
+    use super::ApiConfig;
+    use super::Config;
+    use super::ReportConfig;
+    use super::DEFAULT_API_URL;
+    use crate::datetime::Zone;
+
+    /// 設定ファイルの内容を読み込めることを確認する。
+    #[rstest]
+    #[case::empty("", Config::default())]
+    #[case::all(
+        r#"
+        [api]
+        base_url = "http://localhost:8080/api/v9"
+        reports_base_url = "http://localhost:8080/reports/api/v3"
+        timeout_secs = 30
+        proxy = "http://proxy.example.com:3128"
+        ca_cert = "/etc/ssl/certs/corporate.pem"
+        max_attempts = 5
+        initial_backoff_ms = 100
+        max_backoff_ms = 2000
+        requests_per_second = 0
+        strict = true
+        max_range_days = 7
+        history_days = 0
+
+        [report]
+        project_order = ["project 2", "project 1"]
+        clip_to_range = false
+        fiscal_year_start_month = 4
+        timezone = "Asia/Tokyo"
+        "#,
+        Config {
+            api: ApiConfig {
+                base_url: "http://localhost:8080/api/v9".to_string(),
+                reports_base_url: "http://localhost:8080/reports/api/v3".to_string(),
+                timeout_secs: Some(30),
+                proxy: Some("http://proxy.example.com:3128".to_string()),
+                ca_cert: Some(PathBuf::from("/etc/ssl/certs/corporate.pem")),
+                max_attempts: 5,
+                initial_backoff_ms: 100,
+                max_backoff_ms: 2000,
+                requests_per_second: 0,
+                strict: true,
+                max_range_days: 7,
+                history_days: 0,
+            },
+            report: ReportConfig {
+                project_order: vec!["project 2".to_string(), "project 1".to_string()],
+                clip_to_range: false,
+                fiscal_year_start_month: 4,
+                timezone: Zone::Named(chrono_tz::Asia::Tokyo),
+            },
+        },
+    )]
+    #[case::partial(
+        r#"
+        [api]
+        timeout_secs = 10
+        "#,
+        Config {
+            api: ApiConfig {
+                timeout_secs: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )]
+    fn test_parse(#[case] content: &str, #[case] expected: Config) {
+        let result = Config::parse(content);
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 不正な設定ファイルがエラーとなることを確認する。
+    #[rstest]
+    #[case::unknown_key("[api]\nurl = \"http://localhost\"")]
+    #[case::invalid_type("[api]\ntimeout_secs = \"ten\"")]
+    #[case::invalid_toml("[api")]
+    #[case::unknown_timezone("[report]\ntimezone = \"Asia/Unknown\"")]
+    fn test_parse_error(#[case] content: &str) {
+        let result = Config::parse(content);
+
+        assert!(result.is_err());
+    }
+
+    /// 環境変数で設定が上書きされることを確認する。
+    #[test]
+    fn test_apply_env() {
+        let vars = HashMap::from([
+            ("TOOGGLS_API_URL", "http://localhost:8080"),
+            ("TOOGGLS_REPORTS_API_URL", "http://localhost:8080/reports"),
+            ("TOOGGLS_TIMEOUT_SECS", "5"),
+            ("TOOGGLS_PROXY", "http://proxy:3128"),
+            ("TOOGGLS_CA_CERT", "ca.pem"),
+        ]);
+        let mut config = Config::parse("[api]\ntimeout_secs = 30").unwrap();
+
+        config
+            .apply_env(|key| vars.get(key).map(|value| value.to_string()))
+            .unwrap();
+
+        let expected = Config {
+            api: ApiConfig {
+                base_url: "http://localhost:8080".to_string(),
+                reports_base_url: "http://localhost:8080/reports".to_string(),
+                timeout_secs: Some(5),
+                proxy: Some("http://proxy:3128".to_string()),
+                ca_cert: Some(PathBuf::from("ca.pem")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(expected, config);
+    }
+
+    /// 環境変数が設定されていない場合は設定が変わらないことを確認する。
+    #[test]
+    fn test_apply_env_no_vars() {
+        let mut config = Config::default();
+
+        config.apply_env(|_| None).unwrap();
+
+        assert_eq!(DEFAULT_API_URL, config.api.base_url);
+        assert_eq!(Config::default(), config);
+    }
+
+    /// 不正な環境変数の値がエラーとなることを確認する。
+    #[test]
+    fn test_apply_env_invalid_timeout() {
+        let mut config = Config::default();
+
+        let result =
+            config.apply_env(|key| (key == "TOOGGLS_TIMEOUT_SECS").then(|| "ten".to_string()));
+
+        assert!(result.is_err());
+    }
+}
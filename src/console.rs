@@ -1,9 +1,48 @@
-use std::io::Write;
+use std::io::{BufRead, Write};
 
 use anyhow::{Context, Result};
-use chrono::Local;
+use chrono::{DateTime, NaiveDate, Utc};
 
-use crate::time_entry::TimeEntry;
+use crate::aggregation::{DurationGrid, Durations, MonthlyDurations, SortOrder};
+use crate::datetime::Zone;
+use crate::time_entry::{sorted_by_start, TimeEntry};
+
+/// 実行中のタイムエントリーの終了時刻に代えて表示する文字列。
+const IN_PROGRESS_STOP: &str = "now (in progress)";
+
+/// 実行中のタイムエントリーの経過時間を含む集計結果に付ける印。
+const IN_PROGRESS_MARK: &str = " (in progress)";
+
+/// タイムエントリーを取得できる過去の期間より前のため、集計しなかった月に表示する注記。
+const SKIPPED_MONTH_NOTE: &str =
+    "Skipped: Toggl API does not return time entries of this month. Use --source reports to aggregate it.";
+
+/// ユーザーに確認を求め、肯定的な回答が得られたかを返す。
+///
+/// `y`または`yes`(大文字小文字を区別しない)が入力された場合のみ`true`を返す。
+///
+/// # Arguments
+///
+/// * `reader` - 回答を読み込む入力
+/// * `writer` - 確認メッセージを表示する出力
+/// * `message` - 確認メッセージ
+pub fn confirm<R: BufRead, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    message: &str,
+) -> Result<bool> {
+    write!(writer, "{} [y/N]: ", message).context("Failed to write confirmation message")?;
+    writer
+        .flush()
+        .context("Failed to flush confirmation message")?;
+
+    let mut answer = String::new();
+    reader
+        .read_line(&mut answer)
+        .context("Failed to read confirmation answer")?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
 
 /// Consoleにtime entryを表示するためのtrait。
 pub trait ConsolePresenter {
@@ -13,55 +52,311 @@ pub trait ConsolePresenter {
     ///
     /// * `time_entries` - 表示するタイムエントリー
     fn show_time_entries(&mut self, time_entries: &[TimeEntry]) -> Result<()>;
+
+    /// 実行中のタイムエントリーを経過時間とともに表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `time_entry` - 表示するタイムエントリー
+    /// * `now` - 経過時間の計算に利用する現在時刻
+    fn show_current_time_entry(
+        &mut self,
+        time_entry: &TimeEntry,
+        now: &DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// 集計単位とタグごとの集計結果を表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `durations` - 集計単位とタグごとの合計秒数
+    fn show_durations(&mut self, durations: &Durations) -> Result<()>;
+
+    /// 日毎の集計結果を表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `daily_durations` - 日付と、その日の集計結果
+    fn show_daily_durations(&mut self, daily_durations: &[(NaiveDate, Durations)]) -> Result<()>;
+
+    /// 月毎の集計結果と、全ての月を合わせた集計結果を表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `monthly_durations` - 月ごとと期間全体の集計結果
+    fn show_monthly_durations(&mut self, monthly_durations: &MonthlyDurations) -> Result<()>;
+
+    /// 合計時間を表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - 合計秒数
+    /// * `in_progress` - 実行中のタイムエントリーの経過時間を含むか
+    fn show_total_duration(&mut self, total: i64, in_progress: bool) -> Result<()>;
+
+    /// 日付と集計単位ごとの集計結果を、行と列の合計とともに表示する。
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - 日付と集計単位ごとの合計秒数
+    fn show_duration_grid(&mut self, grid: &DurationGrid) -> Result<()>;
 }
 
 /// タイムエントリーをMarkdownのlist形式で表示する。
 pub struct ConsoleMarkdownList<'a, W: Write> {
     writer: &'a mut W,
+    numbered: bool,
+    detailed: bool,
+    sort_order: SortOrder,
+    zone: Zone,
 }
 
 impl<'a, W: Write> ConsoleMarkdownList<'a, W> {
     /// 新しい`ConsoleMarkdownList`を返す。
     pub fn new(writer: &'a mut W) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            numbered: false,
+            detailed: false,
+            sort_order: SortOrder::default(),
+            zone: Zone::default(),
+        }
+    }
+
+    /// 番号付きのlist形式で表示するかどうかを設定する。
+    ///
+    /// 番号は1から始まり、他のサブコマンドでタイムエントリーを指定するindexとして利用できる。
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.numbered = numbered;
+        self
+    }
+
+    /// タイムエントリーを開始日とIDを含めて表示するかどうかを設定する。
+    ///
+    /// 複数の日にまたがるタイムエントリーを区別できるよう、削除対象の確認などで利用する。
+    pub fn detailed(mut self, detailed: bool) -> Self {
+        self.detailed = detailed;
+        self
+    }
+
+    /// 集計結果を表示する並び順を設定する。
+    ///
+    /// 設定しない場合は名前順とし、実行するたびに同じ順で表示する。
+    pub fn sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// 時刻を表示するタイムゾーンを設定する。
+    ///
+    /// 設定しない場合はLocalタイムゾーンで表示する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
     }
 }
 
 impl<'a, W: Write> ConsolePresenter for ConsoleMarkdownList<'a, W> {
     // time entryをlist形式で表示する。
     fn show_time_entries(&mut self, time_entries: &[TimeEntry]) -> Result<()> {
-        let mut sorted_entries = time_entries.to_vec();
-        sorted_entries.sort_by_key(|entry| entry.start);
-
-        for entry in sorted_entries {
+        for (index, entry) in sorted_by_start(time_entries).into_iter().enumerate() {
+            let start_format = if self.detailed {
+                "%Y-%m-%d %H:%M"
+            } else {
+                "%H:%M"
+            };
             let start_str = entry
                 .start
-                .with_timezone(&Local)
-                .format("%H:%M")
+                .with_timezone(&self.zone)
+                .format(start_format)
                 .to_string();
             let end_str = entry
                 .stop
-                .map(|stop| stop.with_timezone(&Local).format("%H:%M").to_string())
-                .unwrap_or_else(|| "now".to_string());
+                .map(|stop| stop.with_timezone(&self.zone).format("%H:%M").to_string())
+                .unwrap_or_else(|| IN_PROGRESS_STOP.to_string());
+            let marker = if self.numbered {
+                format!("{}.", index + 1)
+            } else {
+                "-".to_string()
+            };
+            let id_str = if self.detailed {
+                format!(" (id: {})", entry.id)
+            } else {
+                String::new()
+            };
             writeln!(
                 self.writer,
-                "- {} ~ {}: {}",
-                start_str, end_str, entry.description
+                "{} {} ~ {}: {}{}",
+                marker, start_str, end_str, entry.description, id_str
             )
             .with_context(|| format!("Failed to write time entry: {:?}", entry))?;
         }
 
         Ok(())
     }
+
+    // 実行中のtime entryを項目ごとのlist形式で表示する。
+    fn show_current_time_entry(
+        &mut self,
+        time_entry: &TimeEntry,
+        now: &DateTime<Utc>,
+    ) -> Result<()> {
+        let elapsed = (*now - time_entry.start).num_seconds().max(0);
+        let lines = [
+            format!("- description: {}", time_entry.description),
+            format!(
+                "- project: {}",
+                time_entry.project.clone().unwrap_or_default()
+            ),
+            format!("- tags: {}", time_entry.tags.join(", ")),
+            format!(
+                "- start: {}",
+                time_entry
+                    .start
+                    .with_timezone(&self.zone)
+                    .format("%Y-%m-%d %H:%M")
+            ),
+            format!(
+                "- elapsed: {:02}:{:02}:{:02}{}",
+                elapsed / 3600,
+                elapsed % 3600 / 60,
+                elapsed % 60,
+                if time_entry.stop.is_none() {
+                    IN_PROGRESS_MARK
+                } else {
+                    ""
+                }
+            ),
+        ];
+        lines.iter().try_for_each(|line| {
+            writeln!(self.writer, "{}", line)
+                .with_context(|| format!("Failed to write time entry: {:?}", time_entry))
+        })?;
+
+        Ok(())
+    }
+
+    // 集計結果を、集計単位の合計とタグごとの入れ子のlist形式で、時間単位で表示する。
+    fn show_durations(&mut self, durations: &Durations) -> Result<()> {
+        for (project, group) in self.sort_order.sort_groups(durations) {
+            let total_hours = group.total as f64 / 3600.0;
+            let mark = if group.in_progress {
+                IN_PROGRESS_MARK
+            } else {
+                ""
+            };
+            writeln!(self.writer, "- {}: {:.2}{}", project, total_hours, mark)
+                .with_context(|| format!("Failed to write durations: {}", project))?;
+            for (tag, duration) in self.sort_order.sort_tags(&group.tags) {
+                let duration_hours = *duration as f64 / 3600.0;
+                writeln!(self.writer, "  - {}: {:.2}", tag, duration_hours)
+                    .with_context(|| format!("Failed to write durations: {}", tag))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // 日付を見出しとして、日毎の集計結果を表示する。
+    fn show_daily_durations(&mut self, daily_durations: &[(NaiveDate, Durations)]) -> Result<()> {
+        for (date, durations) in daily_durations {
+            writeln!(self.writer, "## {}", date)
+                .with_context(|| format!("Failed to write date: {}", date))?;
+            self.show_durations(durations)?;
+        }
+
+        Ok(())
+    }
+
+    // 年月を見出しとして月毎の集計結果を表示し、最後に全体の集計結果を表示する。
+    // 集計しなかった月は、集計結果が空の月と区別できるよう集計しなかったことを表示する。
+    fn show_monthly_durations(&mut self, monthly_durations: &MonthlyDurations) -> Result<()> {
+        for (month, durations) in &monthly_durations.months {
+            let skipped = monthly_durations.skipped.contains(month);
+            let month = month.format("%Y-%m");
+            writeln!(self.writer, "## {}", month)
+                .with_context(|| format!("Failed to write month: {}", month))?;
+            if skipped {
+                writeln!(self.writer, "{}", SKIPPED_MONTH_NOTE)
+                    .with_context(|| format!("Failed to write skipped month: {}", month))?;
+                continue;
+            }
+            self.show_durations(durations)?;
+        }
+        writeln!(self.writer, "## Total").context("Failed to write total heading")?;
+        self.show_durations(&monthly_durations.total)?;
+
+        Ok(())
+    }
+
+    // 合計時間を時間単位で表示する。
+    fn show_total_duration(&mut self, total: i64, in_progress: bool) -> Result<()> {
+        let total_hours = total as f64 / 3600.0;
+        let mark = if in_progress { IN_PROGRESS_MARK } else { "" };
+        writeln!(self.writer, "Total: {:.2}{}", total_hours, mark)
+            .context("Failed to write total duration")?;
+
+        Ok(())
+    }
+
+    // 集計結果をMarkdownの表形式で、時間単位で表示する。
+    fn show_duration_grid(&mut self, grid: &DurationGrid) -> Result<()> {
+        let hours = |durations: &[i64], total: i64| {
+            durations
+                .iter()
+                .chain([total].iter())
+                .map(|duration| format!("{:.2}", *duration as f64 / 3600.0))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        };
+        let header = grid
+            .dates
+            .iter()
+            .map(|date| date.format("%a %m-%d").to_string())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let separator = vec!["---:"; grid.dates.len() + 1].join(" | ");
+        let mut lines = vec![
+            format!("| Name | {} | Total |", header),
+            format!("| --- | {} |", separator),
+        ];
+        let mark = |in_progress: bool| if in_progress { IN_PROGRESS_MARK } else { "" };
+        lines.extend(grid.rows.iter().map(|row| {
+            format!(
+                "| {} | {}{} |",
+                row.name,
+                hours(&row.durations, row.total),
+                mark(row.in_progress)
+            )
+        }));
+        lines.push(format!(
+            "| Total | {}{} |",
+            hours(&grid.date_totals, grid.total),
+            mark(grid.in_progress)
+        ));
+        lines.iter().try_for_each(|line| {
+            writeln!(self.writer, "{}", line)
+                .with_context(|| format!("Failed to write duration grid: {}", line))
+        })?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::{Local, TimeZone, Utc};
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
     use rstest::rstest;
 
+    use super::confirm;
     use super::ConsoleMarkdownList;
     use super::ConsolePresenter;
+    use crate::aggregation::{
+        DurationGrid, DurationGridRow, Durations, GroupDurations, MonthlyDurations, SortOrder,
+    };
+    use crate::datetime::Zone;
     use crate::time_entry::TimeEntry;
 
     /// 正常系のテスト。
@@ -96,40 +391,475 @@ mod tests {
         assert_eq!(String::from_utf8(writer).unwrap(), expected);
     }
 
+    /// 番号付きで表示した場合に、開始時刻順に1から番号が振られることを確認する。
+    #[test]
+    fn test_show_numbered_time_entries() {
+        let entries = [dummy_entry(2), dummy_entry(1)];
+        let expected = [
+            expected_output(&dummy_entry(1)).replacen('-', "1.", 1),
+            expected_output(&dummy_entry(2)).replacen('-', "2.", 1),
+        ]
+        .join("");
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer).numbered(true);
+
+        presenter.show_time_entries(&entries).unwrap();
+
+        assert_eq!(String::from_utf8(writer).unwrap(), expected);
+    }
+
+    /// 指定したタイムゾーンで、ホストのタイムゾーンによらず時刻が表示されることを確認する。
+    #[rstest]
+    #[case::tokyo(chrono_tz::Asia::Tokyo, "- 09:00 ~ 10:00: entry1\n")]
+    #[case::new_york(chrono_tz::America::New_York, "- 19:00 ~ 20:00: entry1\n")]
+    #[case::kathmandu(chrono_tz::Asia::Kathmandu, "- 05:45 ~ 06:45: entry1\n")]
+    fn test_show_time_entries_time_zone(#[case] tz: chrono_tz::Tz, #[case] expected: &str) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let entry = TimeEntry {
+            start,
+            stop: Some(start + chrono::Duration::hours(1)),
+            description: "entry1".to_string(),
+            ..Default::default()
+        };
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer).time_zone(Zone::Named(tz));
+
+        presenter.show_time_entries(&[entry]).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 開始日とIDを含めて表示する場合は、日付をまたいでも区別できるよう表示することを確認する。
+    #[test]
+    fn test_show_detailed_time_entries() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let entries = [1, 2].map(|id| TimeEntry {
+            id,
+            start: start + chrono::Duration::days(id),
+            stop: Some(start + chrono::Duration::days(id) + chrono::Duration::hours(1)),
+            description: format!("entry{}", id),
+            ..Default::default()
+        });
+        let expected = "- 2024-01-02 09:00 ~ 10:00: entry1 (id: 1)\n- 2024-01-03 09:00 ~ 10:00: entry2 (id: 2)\n";
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer)
+            .detailed(true)
+            .time_zone(Zone::Named(chrono_tz::Asia::Tokyo));
+
+        presenter.show_time_entries(&entries).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 実行中のタイムエントリーの開始日時を、指定したタイムゾーンで表示することを確認する。
+    #[test]
+    fn test_show_current_time_entry_time_zone() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 20, 30, 0).unwrap();
+        let entry = TimeEntry {
+            start,
+            description: "entry1".to_string(),
+            ..Default::default()
+        };
+        let mut writer = Vec::new();
+        let mut presenter =
+            ConsoleMarkdownList::new(&mut writer).time_zone(Zone::Named(chrono_tz::Asia::Tokyo));
+
+        presenter
+            .show_current_time_entry(&entry, &(start + chrono::Duration::minutes(5)))
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("- start: 2024-01-02 05:30\n"), "{}", output);
+    }
+
+    /// 実行中のタイムエントリーが経過時間とともに表示されることを確認する。
+    #[rstest]
+    #[case::elapsed(
+        Utc.with_ymd_and_hms(2021, 1, 1, 6, 2, 3).unwrap(),
+        "01:02:03",
+    )]
+    #[case::just_started(
+        Utc.with_ymd_and_hms(2021, 1, 1, 5, 0, 0).unwrap(),
+        "00:00:00",
+    )]
+    #[case::over_a_day(
+        Utc.with_ymd_and_hms(2021, 1, 2, 6, 0, 0).unwrap(),
+        "25:00:00",
+    )]
+    fn test_show_current_time_entry(#[case] now: DateTime<Utc>, #[case] elapsed: &str) {
+        let entry = TimeEntry {
+            project: Some("project 1".to_string()),
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+            ..dummy_entry(4)
+        };
+        let expected = format!(
+            "- description: entry3\n- project: project 1\n- tags: tag 1, tag 2\n- start: {}\n- elapsed: {} (in progress)\n",
+            entry
+                .start
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            elapsed
+        );
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_current_time_entry(&entry, &now).unwrap();
+
+        assert_eq!(String::from_utf8(writer).unwrap(), expected);
+    }
+
+    /// 集計結果が時間単位で表示されることを確認する。
+    #[rstest]
+    #[case::empty(HashMap::new(), "")]
+    #[case::single(
+        HashMap::from([(
+            "project 1".to_string(),
+            GroupDurations {
+                total: 7200,
+                tags: HashMap::from([("tag 1".to_string(), 5400)]),
+                ..Default::default()
+            },
+        )]),
+        "- project 1: 2.00\n  - tag 1: 1.50\n",
+    )]
+    #[case::no_tags(
+        HashMap::from([(
+            "project 1".to_string(),
+            GroupDurations {
+                total: 900,
+                tags: HashMap::new(),
+                ..Default::default()
+            },
+        )]),
+        "- project 1: 0.25\n",
+    )]
+    fn test_show_durations(#[case] durations: Durations, #[case] expected: &str) {
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_durations(&durations).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 集計結果が並び順に従って、毎回同じ順で表示されることを確認する。
+    #[rstest]
+    #[case::default(
+        None,
+        "- project a: 0.50\n  - tag a: 0.25\n  - tag b: 0.50\n- project b: 1.00\n  - tag c: 1.00\n"
+    )]
+    #[case::name(
+        Some(SortOrder::Name),
+        "- project a: 0.50\n  - tag a: 0.25\n  - tag b: 0.50\n- project b: 1.00\n  - tag c: 1.00\n"
+    )]
+    #[case::duration(
+        Some(SortOrder::Duration),
+        "- project b: 1.00\n  - tag c: 1.00\n- project a: 0.50\n  - tag b: 0.50\n  - tag a: 0.25\n"
+    )]
+    #[case::custom(
+        Some(SortOrder::Custom(vec!["project b".to_string()])),
+        "- project b: 1.00\n  - tag c: 1.00\n- project a: 0.50\n  - tag a: 0.25\n  - tag b: 0.50\n",
+    )]
+    fn test_show_durations_sort_order(
+        #[case] sort_order: Option<SortOrder>,
+        #[case] expected: &str,
+    ) {
+        for _ in 0..10 {
+            let durations = HashMap::from([
+                (
+                    "project a".to_string(),
+                    GroupDurations {
+                        total: 1800,
+                        tags: HashMap::from([
+                            ("tag b".to_string(), 1800),
+                            ("tag a".to_string(), 900),
+                        ]),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "project b".to_string(),
+                    GroupDurations {
+                        total: 3600,
+                        tags: HashMap::from([("tag c".to_string(), 3600)]),
+                        ..Default::default()
+                    },
+                ),
+            ]);
+            let mut writer = Vec::new();
+            let presenter = ConsoleMarkdownList::new(&mut writer);
+            let mut presenter = match sort_order.clone() {
+                Some(sort_order) => presenter.sort_order(sort_order),
+                None => presenter,
+            };
+
+            presenter.show_durations(&durations).unwrap();
+
+            assert_eq!(expected, String::from_utf8(writer).unwrap());
+        }
+    }
+
+    /// 実行中のタイムエントリーを含む集計結果に印が付くことを確認する。
+    #[test]
+    fn test_show_durations_in_progress() {
+        let durations = HashMap::from([(
+            "project 1".to_string(),
+            GroupDurations {
+                total: 5400,
+                tags: HashMap::from([("tag 1".to_string(), 5400)]),
+                in_progress: true,
+            },
+        )]);
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_durations(&durations).unwrap();
+
+        assert_eq!(
+            "- project 1: 1.50 (in progress)\n  - tag 1: 1.50\n",
+            String::from_utf8(writer).unwrap()
+        );
+    }
+
+    /// 合計時間が時間単位で、実行中のタイムエントリーを含む場合は印を付けて表示されることを確認する。
+    #[rstest]
+    #[case::stopped(5400, false, "Total: 1.50\n")]
+    #[case::in_progress(900, true, "Total: 0.25 (in progress)\n")]
+    fn test_show_total_duration(
+        #[case] total: i64,
+        #[case] in_progress: bool,
+        #[case] expected: &str,
+    ) {
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_total_duration(total, in_progress).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 日付と集計単位ごとの集計結果が、合計とともに表形式で表示されることを確認する。
+    ///
+    /// 実行中のタイムエントリーを含む行と全体の合計には、実行中の印を付ける。
+    #[rstest]
+    #[case::finished(
+        false,
+        "| project 1 | 1.50 | 0.00 | 1.50 |",
+        "| Total | 1.75 | 0.50 | 2.25 |"
+    )]
+    #[case::in_progress(
+        true,
+        "| project 1 | 1.50 | 0.00 | 1.50 (in progress) |",
+        "| Total | 1.75 | 0.50 | 2.25 (in progress) |"
+    )]
+    fn test_show_duration_grid(
+        #[case] in_progress: bool,
+        #[case] expected_project_1: &str,
+        #[case] expected_total: &str,
+    ) {
+        let grid = DurationGrid {
+            dates: vec![
+                NaiveDate::from_ymd_opt(2024, 1, 29).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 30).unwrap(),
+            ],
+            rows: vec![
+                DurationGridRow {
+                    name: "project 1".to_string(),
+                    durations: vec![5400, 0],
+                    total: 5400,
+                    in_progress,
+                },
+                DurationGridRow {
+                    name: "project 2".to_string(),
+                    durations: vec![900, 1800],
+                    total: 2700,
+                    in_progress: false,
+                },
+            ],
+            date_totals: vec![6300, 1800],
+            total: 8100,
+            in_progress,
+        };
+        let expected = [
+            "| Name | Mon 01-29 | Tue 01-30 | Total |",
+            "| --- | ---: | ---: | ---: |",
+            expected_project_1,
+            "| project 2 | 0.25 | 0.50 | 0.75 |",
+            expected_total,
+            "",
+        ]
+        .join("\n");
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_duration_grid(&grid).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 日毎の集計結果が日付を見出しとして表示されることを確認する。
+    #[test]
+    fn test_show_daily_durations() {
+        let durations = HashMap::from([(
+            "project 1".to_string(),
+            GroupDurations {
+                total: 900,
+                tags: HashMap::from([("tag 1".to_string(), 900)]),
+                ..Default::default()
+            },
+        )]);
+        let daily_durations = [
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                durations.clone(),
+            ),
+            (NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(), durations),
+        ];
+        let expected = [
+            "## 2024-01-01\n- project 1: 0.25\n  - tag 1: 0.25\n",
+            "## 2024-01-02\n- project 1: 0.25\n  - tag 1: 0.25\n",
+        ]
+        .join("");
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter.show_daily_durations(&daily_durations).unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 月毎の集計結果が年月を見出しとして表示され、最後に全体の集計結果が表示されることを確認する。
+    #[test]
+    fn test_show_monthly_durations() {
+        let durations = HashMap::from([(
+            "project 1".to_string(),
+            GroupDurations {
+                total: 900,
+                tags: HashMap::from([("tag 1".to_string(), 900)]),
+                ..Default::default()
+            },
+        )]);
+        let monthly_durations = MonthlyDurations::new(vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                durations.clone(),
+            ),
+            (NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), HashMap::new()),
+            (NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), durations),
+        ]);
+        let expected = [
+            "## 2024-01\n- project 1: 0.25\n  - tag 1: 0.25\n",
+            "## 2024-02\n",
+            "## 2024-03\n- project 1: 0.25\n  - tag 1: 0.25\n",
+            "## Total\n- project 1: 0.50\n  - tag 1: 0.50\n",
+        ]
+        .join("");
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter
+            .show_monthly_durations(&monthly_durations)
+            .unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 集計しなかった月は、集計結果が空の月と区別して表示されることを確認する。
+    #[test]
+    fn test_show_monthly_durations_skipped() {
+        let monthly_durations = MonthlyDurations::new(vec![
+            (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), HashMap::new()),
+            (NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), HashMap::new()),
+        ])
+        .skipped(vec![NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()]);
+        let expected = [
+            "## 2024-01
+Skipped: Toggl API does not return time entries of this month. Use --source reports to aggregate it.
+",
+            "## 2024-02
+",
+            "## Total
+",
+        ]
+        .join("");
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+
+        presenter
+            .show_monthly_durations(&monthly_durations)
+            .unwrap();
+
+        assert_eq!(expected, String::from_utf8(writer).unwrap());
+    }
+
+    /// 確認への回答を正しく判定することを確認する。
+    #[rstest]
+    #[case::yes("y\n", true)]
+    #[case::yes_long("Yes\n", true)]
+    #[case::no("n\n", false)]
+    #[case::empty("\n", false)]
+    #[case::eof("", false)]
+    #[case::other("yeah\n", false)]
+    fn test_confirm(#[case] input: &str, #[case] expected: bool) {
+        let mut reader = input.as_bytes();
+        let mut writer = Vec::new();
+
+        let result = confirm(&mut reader, &mut writer, "Delete?");
+
+        assert_eq!(expected, result.unwrap());
+        assert_eq!("Delete? [y/N]: ", String::from_utf8(writer).unwrap());
+    }
+
     /// テスト用にダミーのTimeEntryを作成する。
     fn dummy_entry(pattern: u8) -> TimeEntry {
         match pattern {
             1 => TimeEntry {
+                id: 1,
+                workspace_id: 1, // 利用しないのでなんでも良い
                 description: "entry1".to_string(),
                 start: Utc.with_ymd_and_hms(2021, 1, 1, 1, 0, 0).unwrap(),
                 stop: Some(Utc.with_ymd_and_hms(2021, 1, 1, 2, 0, 0).unwrap()),
-                duration: 3600, // 利用しないのでなんでも良い
-                project: None,  // 利用しないのでなんでも良い
-                tags: vec![],   // 利用しないのでなんでも良い
+                duration: 3600,   // 利用しないのでなんでも良い
+                project_id: None, // 利用しないのでなんでも良い
+                project: None,    // 利用しないのでなんでも良い
+                tags: vec![],     // 利用しないのでなんでも良い
+                ..Default::default()
             },
             2 => TimeEntry {
+                id: 2,
+                workspace_id: 1, // 利用しないのでなんでも良い
                 description: "entry2".to_string(),
                 start: Utc.with_ymd_and_hms(2021, 1, 1, 3, 0, 0).unwrap(),
                 stop: Some(Utc.with_ymd_and_hms(2021, 1, 1, 4, 0, 0).unwrap()),
-                duration: 3600, // 利用しないのでなんでも良い
-                project: None,  // 利用しないのでなんでも良い
-                tags: vec![],   // 利用しないのでなんでも良い
+                duration: 3600,   // 利用しないのでなんでも良い
+                project_id: None, // 利用しないのでなんでも良い
+                project: None,    // 利用しないのでなんでも良い
+                tags: vec![],     // 利用しないのでなんでも良い
+                ..Default::default()
             },
             3 => TimeEntry {
+                id: 3,
+                workspace_id: 1, // 利用しないのでなんでも良い
                 description: "entry3".to_string(),
                 start: Utc.with_ymd_and_hms(2021, 1, 1, 3, 0, 0).unwrap(),
                 stop: Some(Utc.with_ymd_and_hms(2021, 1, 1, 5, 0, 0).unwrap()),
-                duration: 7200, // 利用しないのでなんでも良い
-                project: None,  // 利用しないのでなんでも良い
-                tags: vec![],   // 利用しないのでなんでも良い
+                duration: 7200,   // 利用しないのでなんでも良い
+                project_id: None, // 利用しないのでなんでも良い
+                project: None,    // 利用しないのでなんでも良い
+                tags: vec![],     // 利用しないのでなんでも良い
+                ..Default::default()
             },
             4 => TimeEntry {
+                id: 4,
+                workspace_id: 1, // 利用しないのでなんでも良い
                 description: "entry3".to_string(),
                 start: Utc.with_ymd_and_hms(2021, 1, 1, 5, 0, 0).unwrap(),
                 stop: None,
-                duration: 7200, // 利用しないのでなんでも良い
-                project: None,  // 利用しないのでなんでも良い
-                tags: vec![],   // 利用しないのでなんでも良い
+                duration: 7200,   // 利用しないのでなんでも良い
+                project_id: None, // 利用しないのでなんでも良い
+                project: None,    // 利用しないのでなんでも良い
+                tags: vec![],     // 利用しないのでなんでも良い
+                ..Default::default()
             },
             _ => panic!("Invalid pattern: {}", pattern),
         }
@@ -145,7 +875,7 @@ mod tests {
         let end_str = entry
             .stop
             .map(|stop| stop.with_timezone(&Local).format("%H:%M").to_string())
-            .unwrap_or_else(|| "now".to_string());
+            .unwrap_or_else(|| "now (in progress)".to_string());
         format!("- {} ~ {}: {}\n", start_str, end_str, entry.description)
     }
 }
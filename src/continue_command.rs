@@ -0,0 +1,265 @@
+use anyhow::Result;
+use log::info;
+
+use crate::datetime::{now, Zone};
+use crate::entry_selector::{
+    read_day_time_entries, read_recent_time_entries, select_time_entry, EntrySelector,
+};
+use crate::start_command::start_time_entry;
+use crate::time_entry::{NewTimeEntry, TimeEntry};
+use crate::toggl::TogglRepository;
+
+/// 以前のタイムエントリーと同じ内容で新しいタイムエントリーを開始するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct ContinueArgs {
+    #[clap(help = "Index of today's time entry shown by `daily --number`")]
+    index: Option<usize>,
+
+    #[clap(
+        short = 'm',
+        long = "match",
+        value_name = "TEXT",
+        help = "Continues the latest time entry whose description contains the text",
+        conflicts_with = "index"
+    )]
+    matches: Option<String>,
+}
+
+pub struct ContinueCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository> ContinueCommand<'a, T> {
+    /// 新しい`ContinueCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self {
+            toggl_client,
+            zone: Zone::default(),
+        }
+    }
+
+    /// 今日の範囲を決めるタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `continue`サブコマンドの処理を行う。
+    ///
+    /// 番号が指定された場合は今日のタイムエントリーから、それ以外は過去7日間のタイムエントリーから対象を選択し、
+    /// 同じ説明、プロジェクト、タグで新しいタイムエントリーを開始する。
+    /// 何も指定されていない場合は、最も新しく開始したタイムエントリーを対象とする。
+    /// 番号以外で選択する場合は、同じ内容を開始し直さないよう実行中のタイムエントリーを対象外とする。
+    pub async fn run(&self, args: ContinueArgs) -> Result<TimeEntry> {
+        let selector = match (args.index, args.matches) {
+            (Some(index), _) => EntrySelector::Index(index),
+            (None, Some(text)) => EntrySelector::Match(text),
+            (None, None) => EntrySelector::Last,
+        };
+        let time_entries = match selector {
+            // `daily --number`の番号と一致するよう、今日と重なるタイムエントリーから選択する
+            EntrySelector::Index(_) => {
+                read_day_time_entries(self.toggl_client, &now(), &self.zone).await?
+            }
+            _ => read_recent_time_entries(self.toggl_client, &now(), &self.zone)
+                .await?
+                .into_iter()
+                .filter(|entry| entry.stop.is_some())
+                .collect(),
+        };
+        let target = select_time_entry(&time_entries, &selector)?;
+        info!("Continue time entry: {}", target.id);
+
+        let new_entry = NewTimeEntry {
+            workspace_id: target.workspace_id,
+            start: now(),
+            stop: None,
+            description: target.description,
+            project_id: target.project_id,
+            tags: target.tags,
+        };
+
+        start_time_entry(self.toggl_client, &new_entry).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::ContinueArgs;
+    use super::ContinueCommand;
+    use crate::aggregation::CLIP_LOOKBACK_HOURS;
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::range::day_range;
+    use crate::time_entry::{NewTimeEntry, TimeEntry};
+    use crate::toggl::MockTogglRepository;
+
+    /// 選択方法に応じた期間から対象を選び、同じ内容で開始することを確認する。
+    ///
+    /// ホストのタイムゾーンによらないよう、日の境界はUTCとする。
+    #[tokio::test]
+    #[rstest]
+    #[case::last(None, None, Duration::days(6), 3)]
+    #[case::index(Some(1), None, Duration::hours(CLIP_LOOKBACK_HOURS), 1)]
+    #[case::matches(None, Some("review"), Duration::days(6), 2)]
+    async fn test_continue_command(
+        #[case] index: Option<usize>,
+        #[case] matches: Option<&str>,
+        #[case] lookback: Duration,
+        #[case] expected_id: i64,
+    ) {
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00+00:00")
+            .unwrap()
+            .to_utc();
+        mock_datetime::set_mock_time(now);
+        let zone = Zone::Named(chrono_tz::UTC);
+        let (today, tomorrow) = day_range(&now, &zone).unwrap();
+        let entries = dummy_entries(now);
+        let target = entries
+            .iter()
+            .find(|entry| entry.id == expected_id)
+            .unwrap()
+            .clone();
+        let expected_new_entry = NewTimeEntry {
+            workspace_id: target.workspace_id,
+            start: now,
+            stop: None,
+            description: target.description.clone(),
+            project_id: target.project_id,
+            tags: target.tags.clone(),
+        };
+        let created = TimeEntry {
+            id: 4,
+            start: now,
+            stop: None,
+            ..target
+        };
+        let expected = created.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(today - lookback), predicate::eq(tomorrow))
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(|| Ok(None));
+        toggl
+            .expect_create_time_entry()
+            .with(predicate::eq(expected_new_entry))
+            .times(1)
+            .returning(move |_| Ok(created.clone()));
+
+        let command = ContinueCommand::new(&toggl).time_zone(zone);
+        let result = command
+            .run(ContinueArgs {
+                index,
+                matches: matches.map(str::to_string),
+            })
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 選択方法を指定しない場合は、実行中のタイムエントリーを除いた最新のものを対象とすることを確認する。
+    #[tokio::test]
+    async fn test_continue_command_skip_running() {
+        let now = DateTime::parse_from_rfc3339("2024-01-10T12:00:00+00:00")
+            .unwrap()
+            .to_utc();
+        mock_datetime::set_mock_time(now);
+        let mut entries = dummy_entries(now);
+        entries.push(TimeEntry {
+            id: 5,
+            workspace_id: 10,
+            start: now - Duration::minutes(30),
+            stop: None,
+            duration: -1,
+            description: "running".to_string(),
+            ..Default::default()
+        });
+        let target = entries[2].clone();
+        let created = TimeEntry {
+            id: 6,
+            start: now,
+            stop: None,
+            ..target.clone()
+        };
+        let expected = created.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(|| Ok(None));
+        toggl
+            .expect_create_time_entry()
+            .withf(move |new_entry| new_entry.description == target.description)
+            .times(1)
+            .returning(move |_| Ok(created.clone()));
+
+        let command = ContinueCommand::new(&toggl);
+        let result = command
+            .run(ContinueArgs {
+                index: None,
+                matches: None,
+            })
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 対象のタイムエントリーがない場合は開始せずにエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_continue_command_no_target() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        toggl.expect_read_current_time_entry().never();
+        toggl.expect_create_time_entry().never();
+
+        let command = ContinueCommand::new(&toggl);
+        let result = command
+            .run(ContinueArgs {
+                index: None,
+                matches: None,
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用のタイムエントリー一覧を作成する。
+    fn dummy_entries(now: DateTime<Utc>) -> Vec<TimeEntry> {
+        [(1, 3, "meeting"), (2, 2, "review"), (3, 1, "coding")]
+            .into_iter()
+            .map(|(id, hours_ago, description)| TimeEntry {
+                id,
+                workspace_id: 10,
+                start: now - Duration::hours(hours_ago),
+                stop: Some(now - Duration::hours(hours_ago - 1)),
+                duration: 3600,
+                description: description.to_string(),
+                project_id: Some(id * 100),
+                project: Some(format!("project {}", id)),
+                tags: vec![format!("tag {}", id)],
+                ..Default::default()
+            })
+            .collect()
+    }
+}
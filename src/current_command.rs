@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use log::info;
+
+use crate::time_entry::TimeEntry;
+use crate::toggl::TogglRepository;
+
+/// 実行中のタイムエントリーを表示するためのサブコマンド。
+///
+/// 実行中のタイムエントリーがない場合は0以外の終了コードで終了する。
+#[derive(Debug, clap::Args)]
+pub struct CurrentArgs {}
+
+pub struct CurrentCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+}
+
+impl<'a, T: TogglRepository> CurrentCommand<'a, T> {
+    /// 新しい`CurrentCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self { toggl_client }
+    }
+
+    /// `current`サブコマンドの処理を行う。
+    ///
+    /// 実行中のタイムエントリーを返す。実行中のタイムエントリーが存在しない場合は`None`を返す。
+    pub async fn run(&self, _current: CurrentArgs) -> Result<Option<TimeEntry>> {
+        let entry = self
+            .toggl_client
+            .read_current_time_entry()
+            .await
+            .context("Failed to retrieve current time entry")?;
+        info!("Current time entry retrieved successfully.");
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rstest::rstest;
+
+    use super::CurrentArgs;
+    use super::CurrentCommand;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::MockTogglRepository;
+
+    /// 実行中のタイムエントリーの有無をそのまま返すことを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::running(Some(TimeEntry {
+        id: 1,
+        workspace_id: 10,
+        start: Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap(),
+        stop: None,
+        duration: -1,
+        description: "entry 1".to_string(),
+        project_id: None,
+        project: None,
+        tags: vec![],
+        ..Default::default()
+    }))]
+    #[case::not_running(None)]
+    async fn test_current_command(#[case] current: Option<TimeEntry>) {
+        let expected = current.clone();
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(move || Ok(current.clone()));
+
+        let command = CurrentCommand::new(&toggl);
+        let result = command.run(CurrentArgs {}).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 実行中のタイムエントリーの取得に失敗した場合にエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_error_current_command() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(|| Err(anyhow::anyhow!("Test error")));
+
+        let command = CurrentCommand::new(&toggl);
+        let result = command.run(CurrentArgs {}).await;
+
+        assert!(result.is_err());
+    }
+}
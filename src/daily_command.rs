@@ -1,10 +1,13 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike, Utc};
+use anyhow::Result;
 use log::info;
 
-use crate::datetime::now;
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::fetch::{read_range_time_entries, FetchOptions};
+use crate::range::day_range;
+use crate::source::Source;
 use crate::time_entry::TimeEntry;
-use crate::toggl::TogglRepository;
+use crate::toggl::{ReportsRepository, TogglRepository};
 
 /// 日毎の情報を出力するためのサブコマンド。
 #[derive(Debug, clap::Args)]
@@ -12,92 +15,151 @@ pub struct DailyArgs {
     #[clap(
         short = 'd',
         long = "date",
-        help = "Sets a custom date in the format YYYY-MM-DD",
-        parse(try_from_str = parse_date),
+        help = "Sets a custom date such as YYYY-MM-DD, MM-DD, yesterday, -2, last monday or 2 weeks ago",
+        allow_hyphen_values = true
     )]
-    date: Option<DateTime<Utc>>,
+    date: Option<DateExpr>,
+
+    #[clap(
+        short = 'n',
+        long = "number",
+        help = "Shows time entries as a numbered list. The numbers can be used as indices by other subcommands"
+    )]
+    pub number: bool,
+
+    #[clap(
+        long = "exclude-running",
+        help = "Excludes running time entries from the total. By default they are counted up to now"
+    )]
+    exclude_running: bool,
+
+    #[clap(
+        long = "no-clip",
+        help = "Shows and counts whole time entries on the day they start instead of splitting them at midnight"
+    )]
+    no_clip: bool,
+
+    #[clap(
+        long = "source",
+        help = "Fetches time entries from the time entries API or the detailed report of the Reports API, which interprets dates in the timezone of the Toggl profile and does not return running time entries. Cannot be used with --number",
+        arg_enum,
+        default_value = "time-entries",
+        conflicts_with = "number"
+    )]
+    source: Source,
 }
 
-pub struct DailyCommand<'a, T: TogglRepository> {
+pub struct DailyCommand<'a, T: TogglRepository, R: ReportsRepository> {
     toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    zone: Zone,
 }
 
-impl<'a, T: TogglRepository> DailyCommand<'a, T> {
+impl<'a, T: TogglRepository, R: ReportsRepository> DailyCommand<'a, T, R> {
     /// 新しい`DailyCommand`を返す。
     ///
     /// # Arguments
     /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
-    pub fn new(toggl_client: &'a T) -> Self {
-        Self { toggl_client }
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを日の範囲で切り詰めて表示、集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 日の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
     }
 
     /// `daily`サブコマンドの処理を行う。
     ///
-    /// Localタイムゾーンで指定された日付の00:00:00から始まる1日のタイムエントリーを取得し、表示する。
-    /// 日付が指定されていない場合は、Localタイムゾーンで現在の日付を利用する。
+    /// 設定したタイムゾーンで指定された日付の00:00:00から始まる1日のタイムエントリーを取得し、表示する。
+    /// 日付が指定されていない場合は、設定したタイムゾーンで現在の日付を利用する。
+    /// 実行中のタイムエントリーは、除外する指定がなければ現在までの経過時間で集計する。
+    /// ただし、Reports APIは実行中のタイムエントリーを返さないため、Reports APIから取得する場合は除外する。
+    /// 切り詰める場合は、前日から続くタイムエントリーを含め、日の範囲に含まれる部分のみを返す。
     pub async fn run(&self, daily: DailyArgs) -> Result<Vec<TimeEntry>> {
-        // Localのタイムゾーンで00:00:00から始まる1日とする
-        let date = daily.date.unwrap_or_else(now);
-        let local_date = date.with_timezone(&Local);
-        let start_at = local_date
-            .with_hour(0)
-            .context("Failed to set hour")?
-            .with_minute(0)
-            .context("Failed to set minute")?
-            .with_second(0)
-            .context("Failed to set second")?;
-        let end_at = start_at + chrono::Duration::days(1);
+        // 設定したタイムゾーンで00:00:00から始まる1日とする
+        let date = match daily.date {
+            Some(date) => date.start_of_day(&self.zone)?,
+            None => now(),
+        };
+        let (start_at, end_at) = day_range(&date, &self.zone)?;
 
         info!("Start at: {}, End at: {}", start_at, end_at);
-        let time_entries = self
-            .toggl_client
-            .read_time_entries(&start_at.to_utc(), &end_at.to_utc())
-            .await
-            .context("Failed to retrieve time entries")?;
-        info!("Time entries retrieved successfully.");
-
-        Ok(time_entries)
-    }
-}
 
-/// 日付をパースする。
-fn parse_date(s: &str) -> Result<DateTime<Utc>> {
-    let naive_date = NaiveDate::parse_from_str(s, "%Y-%m-%d")
-        .with_context(|| format!("Failed to parse date: {}", s))?;
-    let naive_datetime = naive_date
-        .and_hms_opt(0, 0, 0)
-        .context("Failed to set hour, minute, and second")?;
-    let datetime = Local
-        .from_local_datetime(&naive_datetime)
-        .single()
-        .context("Failed to convert to DateTime<Local>")?
-        .to_utc();
-
-    Ok(datetime)
+        let options = FetchOptions {
+            source: daily.source,
+            include_running: !daily.exclude_running && daily.source != Source::Reports,
+            clip: self.clip_to_range && !daily.no_clip,
+        };
+        read_range_time_entries(
+            self.toggl_client,
+            self.reports_client,
+            &options,
+            &start_at,
+            &end_at,
+            &self.zone,
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+    use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
     use mockall::predicate;
     use rstest::rstest;
 
-    use super::parse_date;
     use super::DailyArgs;
     use super::DailyCommand;
-    use crate::datetime::mock_datetime;
+    use crate::aggregation::{calc_total_duration, CLIP_LOOKBACK_HOURS};
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::source::Source;
     use crate::time_entry::TimeEntry;
-    use crate::toggl::MockTogglRepository;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
 
     #[tokio::test]
     #[rstest]
     #[case::none_date_to_now(None)]
-    #[case::specific_date(Some(DateTime::parse_from_rfc3339("2024-01-01T00:00:00+00:00").unwrap().to_utc()))]
-    async fn test_daily_command_no_date(#[case] date: Option<DateTime<Utc>>) {
-        let args = DailyArgs { date };
+    #[case::specific_date(Some("2024-01-01"))]
+    async fn test_daily_command_no_date(#[case] date: Option<&str>) {
+        let args = DailyArgs {
+            date: date.map(|date| date.parse().unwrap()),
+            number: false,
+            exclude_running: false,
+            no_clip: false,
+            source: Source::TimeEntries,
+        };
         let mut toggl = MockTogglRepository::new();
 
-        let now = date.unwrap_or(Utc::now());
+        let now = match date {
+            Some(date) => Local
+                .from_local_datetime(
+                    &NaiveDateTime::parse_from_str(
+                        &format!("{}T00:00:00", date),
+                        "%Y-%m-%dT%H:%M:%S",
+                    )
+                    .unwrap(),
+                )
+                .unwrap()
+                .to_utc(),
+            None => Utc::now(),
+        };
         let today = now
             .with_timezone(&Local)
             .with_hour(0)
@@ -105,78 +167,279 @@ mod tests {
             .with_minute(0)
             .unwrap()
             .with_second(0)
+            .unwrap()
+            .with_nanosecond(0)
             .unwrap();
         let tomorrow = today + chrono::Duration::days(1);
         mock_datetime::set_mock_time(now);
 
         let entries = vec![TimeEntry {
+            id: 1,
+            workspace_id: 1,
             description: "test 1".to_string(),
             start: today.with_hour(3).unwrap().to_utc(),
             stop: Some(today.with_hour(4).unwrap().to_utc()),
             duration: 3600,
+            project_id: None,
             project: None,
             tags: vec![],
+            ..Default::default()
         }];
         let expect_entries = entries.clone();
         toggl
             .expect_read_time_entries()
             .with(
-                predicate::eq(today.to_utc()),
+                predicate::eq(today.to_utc() - chrono::Duration::hours(CLIP_LOOKBACK_HOURS)),
                 predicate::eq(tomorrow.to_utc()),
             )
             .times(1)
             .returning(move |_, _| Ok(entries.clone()));
 
-        let command = DailyCommand::new(&toggl);
+        let reports = MockReportsRepository::new();
+        let command = DailyCommand::new(&toggl, &reports);
         let result = command.run(args).await;
 
         assert!(result.is_ok());
         assert_eq!(expect_entries, result.unwrap());
     }
 
-    /// time entriesの取得に失敗した場合にエラーとなることを確認する。
+    /// 指定したタイムゾーンの1日の範囲で、ホストのタイムゾーンによらずタイムエントリーを取得することを確認する。
+    ///
+    /// 現在はUTCで2024-01-01 20:00とし、東京では翌日となる。夏時間が始まる日は23時間となる。
     #[tokio::test]
-    async fn test_error_daily_command_get_time_entries() {
-        let daily = DailyArgs { date: None };
+    #[rstest]
+    #[case::tokyo_today(chrono_tz::Asia::Tokyo, None, (2024, 1, 1, 15), (2024, 1, 2, 15))]
+    #[case::tokyo_date(chrono_tz::Asia::Tokyo, Some("2024-03-10"), (2024, 3, 9, 15), (2024, 3, 10, 15))]
+    #[case::new_york_today(chrono_tz::America::New_York, None, (2024, 1, 1, 5), (2024, 1, 2, 5))]
+    #[case::new_york_dst(chrono_tz::America::New_York, Some("2024-03-10"), (2024, 3, 10, 5), (2024, 3, 11, 4))]
+    async fn test_daily_command_time_zone(
+        #[case] tz: chrono_tz::Tz,
+        #[case] date: Option<&str>,
+        #[case] expected_start: (i32, u32, u32, u32),
+        #[case] expected_end: (i32, u32, u32, u32),
+    ) {
+        mock_datetime::set_mock_time(Utc.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap());
+        let utc = |(year, month, day, hour): (i32, u32, u32, u32)| {
+            Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+        };
+        let args = DailyArgs {
+            date: date.map(|date| date.parse().unwrap()),
+            number: false,
+            exclude_running: false,
+            no_clip: false,
+            source: Source::TimeEntries,
+        };
         let mut toggl = MockTogglRepository::new();
         toggl
             .expect_read_time_entries()
+            .with(
+                predicate::eq(utc(expected_start) - chrono::Duration::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(utc(expected_end)),
+            )
             .times(1)
-            .returning(|_, _| Err(anyhow::anyhow!("Test error")));
+            .returning(|_, _| Ok(vec![]));
 
-        let command = DailyCommand::new(&toggl);
-        let result = command.run(daily).await;
+        let reports = MockReportsRepository::new();
+        let result = DailyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(tz))
+            .run(args)
+            .await;
+        mock_datetime::clear_mock_time();
 
-        assert!(result.is_err());
+        assert!(result.is_ok());
     }
 
-    /// 正常に日付をパースできることを確認する。
-    #[test]
-    fn test_parse_date_valid_date() {
-        let date_str = "2022-12-31";
-        let expected_date = Local
-            .from_local_datetime(
-                &NaiveDateTime::parse_from_str("2022-12-31T00:00:00", "%Y-%m-%dT%H:%M:%S").unwrap(),
-            )
-            .unwrap()
-            .to_utc();
+    /// 日付をまたぐタイムエントリーは、切り詰める場合に日の範囲に含まれる部分のみを返すことを確認する。
+    ///
+    /// 東京の2024-01-10を対象とし、前日から続くタイムエントリーも含める。
+    /// 切り詰めない場合は、その日に開始したタイムエントリーの全体を返す。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, &[("00:00", "02:00"), ("22:00", "00:00")], 4 * 3600)]
+    #[case::no_clip(true, &[("22:00", "02:00")], 4 * 3600)]
+    async fn test_daily_command_cross_midnight(
+        #[case] no_clip: bool,
+        #[case] expected_times: &[(&str, &str)],
+        #[case] expected_total: i64,
+    ) {
+        let tz = chrono_tz::Asia::Tokyo;
+        let tokyo = |day: u32, hour: u32| {
+            tz.with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+                .unwrap()
+                .to_utc()
+        };
+        let entry = |id: i64, start: DateTime<Utc>| TimeEntry {
+            id,
+            start,
+            stop: Some(start + chrono::Duration::hours(4)),
+            duration: 4 * 3600,
+            ..Default::default()
+        };
+        // 前日の22:00からのタイムエントリーと、当日の22:00からのタイムエントリー
+        let entries = vec![entry(1, tokyo(9, 22)), entry(2, tokyo(10, 22))];
+        let start_at = tokyo(10, 0);
+        let fetch_start_at = if no_clip {
+            start_at
+        } else {
+            start_at - chrono::Duration::hours(CLIP_LOOKBACK_HOURS)
+        };
+        let mut toggl = MockTogglRepository::new();
+        // 実際のAPIと同じく、取得する範囲に開始日時が含まれるタイムエントリーのみを返す
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(fetch_start_at), predicate::eq(tokyo(11, 0)))
+            .times(1)
+            .returning(move |start_at, end_at| {
+                Ok(entries
+                    .iter()
+                    .filter(|entry| *start_at <= entry.start && entry.start < *end_at)
+                    .cloned()
+                    .collect())
+            });
+        let args = DailyArgs {
+            date: Some("2024-01-10".parse().unwrap()),
+            number: false,
+            exclude_running: false,
+            no_clip,
+            source: Source::TimeEntries,
+        };
 
-        let result = parse_date(date_str);
+        let reports = MockReportsRepository::new();
+        let result = DailyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(tz))
+            .run(args)
+            .await;
 
-        assert!(result.is_ok());
-        assert_eq!(expected_date, result.unwrap());
+        let time_entries = result.unwrap();
+        let times: Vec<(String, String)> = time_entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.start.with_timezone(&tz).format("%H:%M").to_string(),
+                    entry
+                        .stop
+                        .unwrap()
+                        .with_timezone(&tz)
+                        .format("%H:%M")
+                        .to_string(),
+                )
+            })
+            .collect();
+        let expected_times: Vec<(String, String)> = expected_times
+            .iter()
+            .map(|(start, stop)| (start.to_string(), stop.to_string()))
+            .collect();
+        assert_eq!(expected_times, times);
+        assert_eq!((expected_total, false), calc_total_duration(&time_entries));
     }
 
-    /// 入力日付が間違っている場合にエラーを返すことを確認する。
+    /// 実行中のタイムエントリーは、除外しない場合に現在までの経過時間とすることを確認する。
+    #[tokio::test]
     #[rstest]
-    #[test]
-    #[case::invalid_year("20xx-01-01")]
-    #[case::invalid_month("2024-13-01")]
-    #[case::invalid_day("2024-02-30")]
-    #[case::invalid_format("2024/01/01")]
-    #[case::empty_string("")]
-    fn test_parse_date_invalid_date(#[case] date_str: &str) {
-        let result = parse_date(date_str);
+    #[case::count(false, 1800)]
+    #[case::exclude(true, -1704067200)]
+    async fn test_daily_command_running(#[case] exclude_running: bool, #[case] expected: i64) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        mock_datetime::set_mock_time(start + chrono::Duration::minutes(30));
+        let args = DailyArgs {
+            date: None,
+            number: false,
+            exclude_running,
+            no_clip: false,
+            source: Source::TimeEntries,
+        };
+        let entry = TimeEntry {
+            id: 1,
+            start,
+            stop: None,
+            // Toggl APIでは実行中のタイムエントリーは負の値となる
+            duration: -start.timestamp(),
+            ..Default::default()
+        };
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(vec![entry.clone()]));
+
+        let reports = MockReportsRepository::new();
+        let result = DailyCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        let time_entries = result.unwrap();
+        assert_eq!(expected, time_entries[0].duration);
+        assert_eq!(None, time_entries[0].stop);
+    }
+
+    /// Reports APIを指定した場合に、詳細レポートから1日のタイムエントリーを取得することを確認する。
+    ///
+    /// Reports APIは実行中のタイムエントリーを返さないため、`--exclude-running`の指定によらず除外して取得する。
+    #[tokio::test]
+    #[rstest]
+    #[case::exclude_running(true)]
+    #[case::default(false)]
+    async fn test_daily_command_reports_source(#[case] exclude_running: bool) {
+        let tz = chrono_tz::Asia::Tokyo;
+        let start = tz.with_ymd_and_hms(2024, 1, 10, 9, 0, 0).unwrap().to_utc();
+        let entry = TimeEntry {
+            id: 1,
+            start,
+            stop: Some(start + chrono::Duration::hours(1)),
+            duration: 3600,
+            ..Default::default()
+        };
+        let expected = vec![entry.clone()];
+        let args = DailyArgs {
+            date: Some("2024-01-10".parse().unwrap()),
+            number: false,
+            exclude_running,
+            no_clip: false,
+            source: Source::Reports,
+        };
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entries().never();
+        toggl
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10]));
+        let mut reports = MockReportsRepository::new();
+        reports
+            .expect_read_detailed_time_entries()
+            .with(
+                predicate::eq(10),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 11).unwrap()),
+            )
+            .times(1)
+            .returning(move |_, _, _| Ok(vec![entry.clone()]));
+
+        let result = DailyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(tz))
+            .run(args)
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// time entriesの取得に失敗した場合にエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_error_daily_command_get_time_entries() {
+        let daily = DailyArgs {
+            date: None,
+            number: false,
+            exclude_running: false,
+            no_clip: false,
+            source: Source::TimeEntries,
+        };
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(|_, _| Err(anyhow::anyhow!("Test error")));
+
+        let reports = MockReportsRepository::new();
+        let command = DailyCommand::new(&toggl, &reports);
+        let result = command.run(daily).await;
 
         assert!(result.is_err());
     }
@@ -0,0 +1,345 @@
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Result};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone, Utc, Weekday};
+
+use crate::datetime::{now, start_of_day};
+use crate::range::{DAYS_IN_WEEK, MONTHS_IN_YEAR};
+
+/// `MM-DD`形式の日付を検証するための閏年。
+const LEAP_YEAR: i32 = 2000;
+
+/// コマンドラインで指定する日付。
+///
+/// 相対的な日付は、実行時に今日の日付をもとに解決する。次の形式を受け付ける(大文字小文字を区別しない)。
+///
+/// * `YYYY-MM-DD`、`YYYY-MM`(月の1日)、`MM-DD`(今年の日付)
+/// * `YYYY-Www`(ISO週の月曜日)、`YYYY-Www-D`(ISO週の曜日、月曜日が1)
+/// * `today`、`yesterday`、`tomorrow`
+/// * `-2`や`+1`(今日からの日数)
+/// * `last monday`(今日より前の直近の曜日)
+/// * `last week`、`last month`、`last year`
+/// * `2 days ago`、`2 weeks ago`、`2 months ago`、`2 years ago`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateExpr {
+    /// 特定の日付。
+    Date(NaiveDate),
+    /// 今年の月日。
+    MonthDay { month: u32, day: u32 },
+    /// 今日からの日数。
+    DaysFromToday(i64),
+    /// 今日からの月数。月末を超える場合は、その月の末日とする。
+    MonthsFromToday(i32),
+    /// 今日より前の直近の曜日。
+    LastWeekday(Weekday),
+}
+
+impl DateExpr {
+    /// 今日の日付をもとに、日付を解決する。
+    ///
+    /// # Arguments
+    ///
+    /// * `today` - 相対的な日付の基準とする今日の日付
+    pub fn resolve(&self, today: &NaiveDate) -> Result<NaiveDate> {
+        match *self {
+            DateExpr::Date(date) => Ok(date),
+            DateExpr::MonthDay { month, day } => NaiveDate::from_ymd_opt(today.year(), month, day)
+                .with_context(|| {
+                    format!("Invalid date in {}: {:02}-{:02}", today.year(), month, day)
+                }),
+            DateExpr::DaysFromToday(days) => {
+                let result = if days < 0 {
+                    today.checked_sub_days(Days::new(days.unsigned_abs()))
+                } else {
+                    today.checked_add_days(Days::new(days.unsigned_abs()))
+                };
+                result.with_context(|| format!("Failed to get {} days from {}", days, today))
+            }
+            DateExpr::MonthsFromToday(months) => {
+                let result = if months < 0 {
+                    today.checked_sub_months(Months::new(months.unsigned_abs()))
+                } else {
+                    today.checked_add_months(Months::new(months.unsigned_abs()))
+                };
+                result.with_context(|| format!("Failed to get {} months from {}", months, today))
+            }
+            DateExpr::LastWeekday(weekday) => {
+                let days_since = (today.weekday().num_days_from_monday() + 7
+                    - weekday.num_days_from_monday()
+                    - 1)
+                    % 7
+                    + 1;
+                today
+                    .checked_sub_days(Days::new(days_since.into()))
+                    .with_context(|| format!("Failed to get last {} from {}", weekday, today))
+            }
+        }
+    }
+
+    /// 指定したタイムゾーンの今日の日付をもとに日付を解決し、その日が始まる日時を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `tz` - 今日の日付と日の始まりを決めるタイムゾーン
+    pub fn start_of_day<Tz: TimeZone>(&self, tz: &Tz) -> Result<DateTime<Utc>> {
+        let today = now().with_timezone(tz).date_naive();
+
+        start_of_day(&self.resolve(&today)?, tz)
+    }
+}
+
+impl FromStr for DateExpr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let input = s.trim().to_lowercase();
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let expr = match words.as_slice() {
+            ["today"] => DateExpr::DaysFromToday(0),
+            ["yesterday"] => DateExpr::DaysFromToday(-1),
+            ["tomorrow"] => DateExpr::DaysFromToday(1),
+            ["last", "week"] => DateExpr::DaysFromToday(-(DAYS_IN_WEEK as i64)),
+            ["last", "month"] => DateExpr::MonthsFromToday(-1),
+            ["last", "year"] => DateExpr::MonthsFromToday(-(MONTHS_IN_YEAR as i32)),
+            ["last", weekday] => DateExpr::LastWeekday(
+                weekday
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Failed to parse weekday: {}", s))?,
+            ),
+            [number, unit, "ago"] => parse_ago(number, unit)
+                .with_context(|| format!("Failed to parse relative date: {}", s))?,
+            [word] => parse_word(word).with_context(|| format!("Failed to parse date: {}", s))?,
+            _ => bail!("Failed to parse date: {}", s),
+        };
+
+        Ok(expr)
+    }
+}
+
+/// `2 weeks ago`の形式の、数と単位をパースする。
+fn parse_ago(number: &str, unit: &str) -> Result<DateExpr> {
+    ensure!(
+        number.chars().all(|c| c.is_ascii_digit()),
+        "Invalid number: {}",
+        number
+    );
+    let number: i32 = number.parse().context("Invalid number")?;
+    let expr = match unit.trim_end_matches('s') {
+        "day" => DateExpr::DaysFromToday(-i64::from(number)),
+        "week" => DateExpr::DaysFromToday(-i64::from(number) * DAYS_IN_WEEK as i64),
+        "month" => DateExpr::MonthsFromToday(-number),
+        "year" => DateExpr::MonthsFromToday(
+            number
+                .checked_mul(-(MONTHS_IN_YEAR as i32))
+                .context("Too many years")?,
+        ),
+        _ => bail!("Unknown unit: {}", unit),
+    };
+
+    Ok(expr)
+}
+
+/// 空白を含まない、日付や今日からの日数をパースする。
+fn parse_word(word: &str) -> Result<DateExpr> {
+    if let Some(days) = word.strip_prefix(['-', '+']) {
+        ensure!(
+            !days.is_empty() && days.chars().all(|c| c.is_ascii_digit()),
+            "Invalid number of days"
+        );
+        let days: i64 = days.parse().context("Invalid number of days")?;
+        return Ok(DateExpr::DaysFromToday(if word.starts_with('-') {
+            -days
+        } else {
+            days
+        }));
+    }
+    if let Some((year, week)) = word.split_once("-w") {
+        return parse_iso_week(year, week).map(DateExpr::Date);
+    }
+
+    let parts: Vec<&str> = word.split('-').collect();
+    ensure!(
+        parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())),
+        "Invalid date"
+    );
+    match parts.as_slice() {
+        [year, _, _] if year.len() == 4 => NaiveDate::parse_from_str(word, "%Y-%m-%d")
+            .map(DateExpr::Date)
+            .context("Invalid date"),
+        [year, month] if year.len() == 4 => {
+            NaiveDate::from_ymd_opt(year.parse()?, month.parse()?, 1)
+                .map(DateExpr::Date)
+                .context("Invalid month")
+        }
+        [month, day] if month.len() <= 2 => {
+            let (month, day) = (month.parse()?, day.parse()?);
+            NaiveDate::from_ymd_opt(LEAP_YEAR, month, day).context("Invalid month and day")?;
+            Ok(DateExpr::MonthDay { month, day })
+        }
+        _ => bail!("Unknown date format"),
+    }
+}
+
+/// `YYYY-Www`または`YYYY-Www-D`形式のISO週をパースし、その週の日付を返す。
+fn parse_iso_week(year: &str, week: &str) -> Result<NaiveDate> {
+    let (week, weekday) = match week.split_once('-') {
+        Some((week, weekday)) => (week, weekday.parse::<u32>().context("Invalid weekday")?),
+        None => (week, 1),
+    };
+    let year: i32 = year.parse().context("Invalid year of week")?;
+    let week: u32 = week.parse().context("Invalid week number")?;
+    let weekday = match weekday {
+        1 => Weekday::Mon,
+        2 => Weekday::Tue,
+        3 => Weekday::Wed,
+        4 => Weekday::Thu,
+        5 => Weekday::Fri,
+        6 => Weekday::Sat,
+        7 => Weekday::Sun,
+        _ => bail!("Invalid weekday: {}", weekday),
+    };
+
+    NaiveDate::from_isoywd_opt(year, week, weekday).context("Invalid week")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, Utc, Weekday};
+    use rstest::rstest;
+
+    use super::DateExpr;
+    use crate::datetime::mock_datetime;
+
+    /// 日付や相対的な日付の表現をパースできることを確認する。
+    #[rstest]
+    #[case::date("2024-05-15", DateExpr::Date(date("2024-05-15")))]
+    #[case::month("2024-05", DateExpr::Date(date("2024-05-01")))]
+    #[case::month_day("05-15", DateExpr::MonthDay { month: 5, day: 15 })]
+    #[case::month_day_single_digit("5-1", DateExpr::MonthDay { month: 5, day: 1 })]
+    #[case::leap_day("02-29", DateExpr::MonthDay { month: 2, day: 29 })]
+    #[case::iso_week("2024-W05", DateExpr::Date(date("2024-01-29")))]
+    #[case::iso_week_lowercase("2024-w05", DateExpr::Date(date("2024-01-29")))]
+    #[case::iso_week_in_previous_year("2025-W01", DateExpr::Date(date("2024-12-30")))]
+    #[case::iso_week_53("2020-W53", DateExpr::Date(date("2020-12-28")))]
+    #[case::iso_week_weekday("2024-W05-7", DateExpr::Date(date("2024-02-04")))]
+    #[case::today("today", DateExpr::DaysFromToday(0))]
+    #[case::today_uppercase(" Today ", DateExpr::DaysFromToday(0))]
+    #[case::yesterday("yesterday", DateExpr::DaysFromToday(-1))]
+    #[case::tomorrow("tomorrow", DateExpr::DaysFromToday(1))]
+    #[case::minus_days("-2", DateExpr::DaysFromToday(-2))]
+    #[case::plus_days("+3", DateExpr::DaysFromToday(3))]
+    #[case::last_monday("last monday", DateExpr::LastWeekday(Weekday::Mon))]
+    #[case::last_fri("last fri", DateExpr::LastWeekday(Weekday::Fri))]
+    #[case::last_week("last week", DateExpr::DaysFromToday(-7))]
+    #[case::last_month("last month", DateExpr::MonthsFromToday(-1))]
+    #[case::last_year("last year", DateExpr::MonthsFromToday(-12))]
+    #[case::day_ago("1 day ago", DateExpr::DaysFromToday(-1))]
+    #[case::days_ago("3 days ago", DateExpr::DaysFromToday(-3))]
+    #[case::weeks_ago("2 weeks ago", DateExpr::DaysFromToday(-14))]
+    #[case::weeks_ago_extra_spaces("2   weeks  ago", DateExpr::DaysFromToday(-14))]
+    #[case::months_ago("2 months ago", DateExpr::MonthsFromToday(-2))]
+    #[case::years_ago("1 year ago", DateExpr::MonthsFromToday(-12))]
+    fn test_parse(#[case] input: &str, #[case] expected: DateExpr) {
+        let result: anyhow::Result<DateExpr> = input.parse();
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 解釈できない表現がエラーとなることを確認する。
+    #[rstest]
+    #[case::empty("")]
+    #[case::invalid_year("20xx-01-01")]
+    #[case::invalid_month("2024-13-01")]
+    #[case::invalid_day("2024-02-30")]
+    #[case::invalid_format("2024/01/01")]
+    #[case::invalid_month_only("2024-13")]
+    #[case::invalid_month_day("02-30")]
+    #[case::three_digit_month("123-01")]
+    #[case::no_iso_week_53("2024-W53")]
+    #[case::iso_week_0("2024-W00")]
+    #[case::iso_weekday_8("2024-W05-8")]
+    #[case::minus_only("-")]
+    #[case::minus_not_number("-x")]
+    #[case::double_sign("--2")]
+    #[case::last_unknown("last decade")]
+    #[case::next_monday("next monday")]
+    #[case::ago_unknown_unit("2 hours ago")]
+    #[case::ago_negative("-2 days ago")]
+    #[case::ago_not_number("two days ago")]
+    #[case::ago_without_number("days ago")]
+    #[case::unknown_word("someday")]
+    fn test_parse_error(#[case] input: &str) {
+        let result: anyhow::Result<DateExpr> = input.parse();
+
+        assert!(result.is_err(), "{:?}", result);
+    }
+
+    /// 今日の日付をもとに相対的な日付を解決することを確認する。
+    ///
+    /// 今日は2024-03-31(日曜日)とする。
+    #[rstest]
+    #[case::date("2023-12-01", "2023-12-01")]
+    #[case::month_day("01-15", "2024-01-15")]
+    #[case::leap_day("02-29", "2024-02-29")]
+    #[case::iso_week("2024-W13", "2024-03-25")]
+    #[case::today("today", "2024-03-31")]
+    #[case::yesterday("yesterday", "2024-03-30")]
+    #[case::tomorrow("tomorrow", "2024-04-01")]
+    #[case::minus_days("-31", "2024-02-29")]
+    #[case::plus_days("+1", "2024-04-01")]
+    #[case::last_sunday("last sunday", "2024-03-24")]
+    #[case::last_saturday("last saturday", "2024-03-30")]
+    #[case::last_monday("last monday", "2024-03-25")]
+    #[case::last_week("last week", "2024-03-24")]
+    #[case::last_month_end_of_month("last month", "2024-02-29")]
+    #[case::last_year("last year", "2023-03-31")]
+    #[case::weeks_ago("2 weeks ago", "2024-03-17")]
+    #[case::months_ago("13 months ago", "2023-02-28")]
+    fn test_resolve(#[case] input: &str, #[case] expected: &str) {
+        let expr: DateExpr = input.parse().unwrap();
+
+        let result = expr.resolve(&date("2024-03-31"));
+
+        assert_eq!(date(expected), result.unwrap());
+    }
+
+    /// 今年に存在しない月日はエラーとなることを確認する。
+    #[test]
+    fn test_resolve_leap_day_in_common_year() {
+        let expr: DateExpr = "02-29".parse().unwrap();
+
+        let result = expr.resolve(&date("2023-03-01"));
+
+        assert!(result.is_err());
+    }
+
+    /// 現在の日時とタイムゾーンから今日の日付を決め、解決した日付の始まりの日時を返すことを確認する。
+    ///
+    /// UTCでは前日でも、タイムゾーンの日付を今日とする。
+    #[rstest]
+    #[case::today("today", "2024-03-31T15:00:00+00:00")]
+    #[case::yesterday("yesterday", "2024-03-30T15:00:00+00:00")]
+    #[case::last_month("last month", "2024-02-29T15:00:00+00:00")]
+    #[case::month_day("04-01", "2024-03-31T15:00:00+00:00")]
+    fn test_start_of_day(#[case] input: &str, #[case] expected: &str) {
+        mock_datetime::set_mock_time(datetime("2024-03-31T20:00:00+00:00"));
+        let expr: DateExpr = input.parse().unwrap();
+
+        let result = expr.start_of_day(&chrono_tz::Asia::Tokyo);
+        mock_datetime::clear_mock_time();
+
+        assert_eq!(datetime(expected), result.unwrap());
+    }
+
+    /// テスト用にYYYY-MM-DD形式の文字列から日付を作成する。
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    /// テスト用にRFC 3339形式の文字列から日時を作成する。
+    fn datetime(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().to_utc()
+    }
+}
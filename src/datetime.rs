@@ -1,4 +1,12 @@
-use chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{
+    DateTime, FixedOffset, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeDelta, TimeZone, Utc,
+};
+use serde::Deserialize;
 
 #[cfg(not(test))]
 /// 現在のUTC時間を取得する。
@@ -37,11 +45,123 @@ pub mod mock_datetime {
 #[cfg(test)]
 pub use mock_datetime::now;
 
+/// 日や月の境界の計算と、時刻の表示に利用するタイムゾーン。
+///
+/// 指定がない場合は、実行環境のLocalタイムゾーンを利用する。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Zone {
+    /// 実行環境のLocalタイムゾーン。
+    #[default]
+    Local,
+    /// IANAのタイムゾーン。
+    Named(chrono_tz::Tz),
+    /// 固定のオフセット。`DateTime<Zone>`のオフセットから復元する場合に利用する。
+    Fixed(FixedOffset),
+}
+
+impl TimeZone for Zone {
+    type Offset = FixedOffset;
+
+    fn from_offset(offset: &FixedOffset) -> Self {
+        Zone::Fixed(*offset)
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        match self {
+            Zone::Local => Local.offset_from_local_date(local),
+            Zone::Named(tz) => tz.offset_from_local_date(local).map(|offset| offset.fix()),
+            Zone::Fixed(offset) => LocalResult::Single(*offset),
+        }
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        match self {
+            Zone::Local => Local.offset_from_local_datetime(local),
+            Zone::Named(tz) => tz
+                .offset_from_local_datetime(local)
+                .map(|offset| offset.fix()),
+            Zone::Fixed(offset) => LocalResult::Single(*offset),
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        match self {
+            Zone::Local => Local.offset_from_utc_date(utc),
+            Zone::Named(tz) => tz.offset_from_utc_date(utc).fix(),
+            Zone::Fixed(offset) => *offset,
+        }
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        match self {
+            Zone::Local => Local.offset_from_utc_datetime(utc),
+            Zone::Named(tz) => tz.offset_from_utc_datetime(utc).fix(),
+            Zone::Fixed(offset) => *offset,
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = anyhow::Error;
+
+    /// `local`(大文字小文字を区別しない)またはIANAのタイムゾーン名をパースする。
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Zone::Local);
+        }
+
+        s.parse()
+            .map(Zone::Named)
+            .map_err(|_| anyhow::anyhow!("Unknown time zone: {}", s))
+    }
+}
+
+impl TryFrom<String> for Zone {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Local => write!(f, "local"),
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+            Zone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// 指定したタイムゾーンで、日付が始まる日時を返す。
+///
+/// 夏時間の切り替えで00:00:00が存在しない場合は、その日で最初に存在する時刻とする。
+///
+/// # Arguments
+///
+/// * `date` - 日付
+/// * `tz` - 日付を解釈するタイムゾーン
+pub fn start_of_day<Tz: TimeZone>(date: &NaiveDate, tz: &Tz) -> Result<DateTime<Utc>> {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    (0..24 * 4)
+        .map(|quarter| midnight + TimeDelta::minutes(15 * quarter))
+        .find_map(|datetime| tz.from_local_datetime(&datetime).earliest())
+        .map(|datetime| datetime.to_utc())
+        .with_context(|| format!("Failed to find the start of day: {}", date))
+}
+
 #[cfg(test)]
 mod tests {
-    use chrono::{DateTime, SecondsFormat, Utc};
+    use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+    use chrono_tz::Tz;
+    use rstest::rstest;
 
     use super::mock_datetime;
+    use super::start_of_day;
+    use super::Zone;
 
     /// 何も設定しない場合は、現在時間が取得できることを確認する。
     ///
@@ -83,4 +203,61 @@ mod tests {
             Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
         );
     }
+
+    /// 指定したタイムゾーンで日付が始まる日時を取得できることを確認する。
+    ///
+    /// 夏時間の切り替えで00:00:00が存在しない日は、切り替え後の時刻となる。
+    #[rstest]
+    #[case::utc(chrono_tz::UTC, (2024, 1, 1), "2024-01-01T00:00:00+00:00")]
+    #[case::tokyo(chrono_tz::Asia::Tokyo, (2024, 1, 1), "2023-12-31T15:00:00+00:00")]
+    #[case::new_york_dst_start(chrono_tz::America::New_York, (2024, 3, 10), "2024-03-10T05:00:00+00:00")]
+    #[case::new_york_dst_end(chrono_tz::America::New_York, (2024, 11, 3), "2024-11-03T04:00:00+00:00")]
+    #[case::sao_paulo_midnight_gap(chrono_tz::America::Sao_Paulo, (2018, 11, 4), "2018-11-04T03:00:00+00:00")]
+    #[case::santiago_dst_end(chrono_tz::America::Santiago, (2024, 4, 7), "2024-04-07T04:00:00+00:00")]
+    fn test_start_of_day(#[case] tz: Tz, #[case] date: (i32, u32, u32), #[case] expected: &str) {
+        let date = NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap();
+
+        let result = start_of_day(&date, &tz).unwrap();
+
+        assert_eq!(expected, result.to_rfc3339());
+        assert_eq!(date, result.with_timezone(&tz).date_naive());
+    }
+
+    /// `local`またはIANAのタイムゾーン名をパースできることを確認する。
+    #[rstest]
+    #[case::local("local", Some(Zone::Local))]
+    #[case::local_uppercase("Local", Some(Zone::Local))]
+    #[case::tokyo("Asia/Tokyo", Some(Zone::Named(chrono_tz::Asia::Tokyo)))]
+    #[case::utc("UTC", Some(Zone::Named(chrono_tz::UTC)))]
+    #[case::unknown("Asia/Nowhere", None)]
+    #[case::offset("+09:00", None)]
+    #[case::empty("", None)]
+    fn test_parse_zone(#[case] input: &str, #[case] expected: Option<Zone>) {
+        let result: Result<Zone, _> = input.parse();
+
+        assert_eq!(expected, result.ok());
+    }
+
+    /// IANAのタイムゾーンでは、ホストのタイムゾーンによらず日付の始まりと時刻の表示が決まることを確認する。
+    #[rstest]
+    #[case::tokyo(chrono_tz::Asia::Tokyo, (2024, 1, 1), "2023-12-31T15:00:00+00:00", "09:00")]
+    #[case::new_york_dst_start(chrono_tz::America::New_York, (2024, 3, 10), "2024-03-10T05:00:00+00:00", "19:00")]
+    fn test_zone_named(
+        #[case] tz: Tz,
+        #[case] date: (i32, u32, u32),
+        #[case] expected_start: &str,
+        #[case] expected_time: &str,
+    ) {
+        let zone = Zone::Named(tz);
+        let date = NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap();
+        let datetime = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+00:00")
+            .unwrap()
+            .to_utc();
+
+        let start_at = start_of_day(&date, &zone).unwrap();
+        let time = datetime.with_timezone(&zone).format("%H:%M").to_string();
+
+        assert_eq!(expected_start, start_at.to_rfc3339());
+        assert_eq!(expected_time, time);
+    }
 }
@@ -0,0 +1,464 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use log::info;
+
+use crate::console::ConsolePresenter;
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::entry_selector::{
+    day_time_entries, parse_entry_selector, read_recent_time_entries, select_time_entry,
+    EntrySelector,
+};
+use crate::range::DateRange;
+use crate::time_entry::{sorted_by_start, TimeEntry};
+use crate::toggl::TogglRepository;
+
+/// タイムエントリーを削除するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct DeleteArgs {
+    #[clap(
+        help = "Time entries to delete: `last`, indices shown by `daily --number`, or `id:<ID>`",
+        parse(try_from_str = parse_entry_selector),
+        required_unless_present = "from",
+        conflicts_with = "from",
+    )]
+    selectors: Vec<EntrySelector>,
+
+    #[clap(
+        short = 'd',
+        long = "date",
+        help = "Sets the date such as YYYY-MM-DD or yesterday used to resolve indices. `last` is searched in the 7 days up to the date",
+        allow_hyphen_values = true
+    )]
+    date: Option<DateExpr>,
+
+    #[clap(
+        long = "from",
+        help = "Deletes time entries started on or after the date such as YYYY-MM-DD or last monday",
+        allow_hyphen_values = true
+    )]
+    from: Option<DateExpr>,
+
+    #[clap(
+        long = "to",
+        help = "Deletes time entries started on or before the date such as YYYY-MM-DD or yesterday. Defaults to --from",
+        requires = "from",
+        allow_hyphen_values = true
+    )]
+    to: Option<DateExpr>,
+
+    #[clap(
+        short = 'm',
+        long = "match",
+        value_name = "TEXT",
+        help = "Deletes only time entries whose description contains the text",
+        requires = "from"
+    )]
+    matches: Option<String>,
+
+    #[clap(short = 'y', long = "yes", help = "Deletes without confirmation")]
+    yes: bool,
+
+    #[clap(long = "dry-run", help = "Only shows time entries to be deleted")]
+    dry_run: bool,
+}
+
+pub struct DeleteCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository> DeleteCommand<'a, T> {
+    /// 新しい`DeleteCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self {
+            toggl_client,
+            zone: Zone::default(),
+        }
+    }
+
+    /// 日の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `delete`サブコマンドの処理を行う。
+    ///
+    /// 削除対象のタイムエントリーを表示し、確認が取れた場合に削除する。
+    /// 削除したタイムエントリーを返し、dry-runの場合や確認が取れなかった場合は何も削除せずに空を返す。
+    ///
+    /// # Arguments
+    /// * `args` - `delete`サブコマンドの引数
+    /// * `presenter` - 削除対象を表示するためのpresenter
+    /// * `confirm` - 削除対象を受け取り、削除してよいかを返す関数
+    pub async fn run<P, F>(
+        &self,
+        args: DeleteArgs,
+        presenter: &mut P,
+        confirm: F,
+    ) -> Result<Vec<TimeEntry>>
+    where
+        P: ConsolePresenter,
+        F: FnOnce(&[TimeEntry]) -> Result<bool>,
+    {
+        let today = now().with_timezone(&self.zone).date_naive();
+        let time_entries = match args.from {
+            Some(from) => {
+                let from = from.resolve(&today)?;
+                let to = match args.to {
+                    Some(to) => to.resolve(&today)?,
+                    None => from,
+                };
+                self.select_by_range(&from, &to, args.matches.as_deref())
+                    .await?
+            }
+            None => {
+                let date = match args.date {
+                    Some(date) => date.start_of_day(&self.zone)?,
+                    None => now(),
+                };
+                self.select_by_selectors(&args.selectors, &date).await?
+            }
+        };
+        if time_entries.is_empty() {
+            bail!("No time entry found to delete");
+        }
+
+        presenter
+            .show_time_entries(&time_entries)
+            .context("Failed to show time entries")?;
+        if args.dry_run {
+            info!(
+                "Dry run: {} time entries would be deleted.",
+                time_entries.len()
+            );
+            return Ok(vec![]);
+        }
+        if !args.yes && !confirm(&time_entries)? {
+            info!("Deletion was cancelled.");
+            return Ok(vec![]);
+        }
+
+        for entry in &time_entries {
+            self.toggl_client
+                .delete_time_entry(entry.workspace_id, entry.id)
+                .await
+                .context("Failed to delete time entry")?;
+            info!("Deleted time entry: {}", entry.id);
+        }
+
+        Ok(time_entries)
+    }
+
+    /// 選択方法に該当するタイムエントリーを重複なく取得する。
+    async fn select_by_selectors(
+        &self,
+        selectors: &[EntrySelector],
+        date: &DateTime<Utc>,
+    ) -> Result<Vec<TimeEntry>> {
+        // ID以外の選択方法がある場合のみ、指定日までの過去7日間のタイムエントリーを取得する
+        // 番号は`daily --number`の番号と一致するよう、指定日と重なるタイムエントリーから選択する
+        let (recent_entries, day_entries) = if selectors
+            .iter()
+            .any(|selector| !matches!(selector, EntrySelector::Id(_)))
+        {
+            let recent_entries =
+                read_recent_time_entries(self.toggl_client, date, &self.zone).await?;
+            let day_entries = day_time_entries(&recent_entries, date, &self.zone)?;
+            (recent_entries, day_entries)
+        } else {
+            (vec![], vec![])
+        };
+
+        let mut time_entries: Vec<TimeEntry> = Vec::new();
+        for selector in selectors {
+            let entry = match selector {
+                EntrySelector::Id(id) => self
+                    .toggl_client
+                    .read_time_entry(*id)
+                    .await
+                    .context("Failed to retrieve time entry")?,
+                EntrySelector::Index(_) => select_time_entry(&day_entries, selector)?,
+                _ => select_time_entry(&recent_entries, selector)?,
+            };
+            if !time_entries.iter().any(|selected| selected.id == entry.id) {
+                time_entries.push(entry);
+            }
+        }
+
+        Ok(sorted_by_start(&time_entries))
+    }
+
+    /// 設定したタイムゾーンで期間内に開始し、説明に指定した文字列を含むタイムエントリーを取得する。
+    async fn select_by_range(
+        &self,
+        from: &NaiveDate,
+        to: &NaiveDate,
+        matches: Option<&str>,
+    ) -> Result<Vec<TimeEntry>> {
+        if to < from {
+            bail!("--to must not be before --from");
+        }
+        let (start_at, end_at) = DateRange::inclusive(from, to)?.to_utc_range(&self.zone)?;
+
+        info!("Start at: {}, End at: {}", start_at, end_at);
+        let time_entries = self
+            .toggl_client
+            .read_time_entries(&start_at, &end_at)
+            .await
+            .context("Failed to retrieve time entries")?;
+        let text = matches.map(str::to_lowercase);
+        let filtered: Vec<TimeEntry> = time_entries
+            .into_iter()
+            .filter(|entry| match &text {
+                Some(text) => entry.description.to_lowercase().contains(text),
+                None => true,
+            })
+            .collect();
+
+        Ok(sorted_by_start(&filtered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::DeleteArgs;
+    use super::DeleteCommand;
+    use crate::console::ConsoleMarkdownList;
+    use crate::date_expr::DateExpr;
+    use crate::datetime::Zone;
+    use crate::entry_selector::{EntrySelector, LOOKBACK_DAYS};
+    use crate::range::day_range;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::MockTogglRepository;
+
+    /// 選択方法で指定したタイムエントリーを重複なく削除することを確認する。
+    ///
+    /// ホストのタイムゾーンによらないよう、日の境界はUTCとする。
+    #[tokio::test]
+    async fn test_delete_command_by_selectors() {
+        let zone = Zone::Named(chrono_tz::UTC);
+        let date = Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+        let (start_at, end_at) = day_range(&date, &zone).unwrap();
+        let entries = dummy_entries(date);
+        let by_id = entries[0].clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(start_at - Duration::days(LOOKBACK_DAYS - 1)),
+                predicate::eq(end_at),
+            )
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+            .expect_read_time_entry()
+            .with(predicate::eq(1))
+            .times(1)
+            .returning(move |_| Ok(by_id.clone()));
+        toggl
+            .expect_delete_time_entry()
+            .with(predicate::eq(10), predicate::in_iter([1, 3]))
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+        let command = DeleteCommand::new(&toggl).time_zone(zone);
+        let result = command
+            .run(
+                DeleteArgs {
+                    selectors: vec![
+                        EntrySelector::Last,
+                        EntrySelector::Id(1),
+                        EntrySelector::Index(1),
+                    ],
+                    date: Some(DateExpr::Date(date.date_naive())),
+                    ..dummy_args(None)
+                },
+                &mut presenter,
+                |_| Ok(true),
+            )
+            .await;
+
+        let deleted_ids: Vec<i64> = result.unwrap().iter().map(|entry| entry.id).collect();
+        assert_eq!(vec![1, 3], deleted_ids);
+        assert_eq!(2, String::from_utf8(writer).unwrap().lines().count());
+    }
+
+    /// 期間と説明で絞り込んだタイムエントリーを削除することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::all(None, &[1, 2, 3])]
+    #[case::matches(Some("Review"), &[2, 3])]
+    async fn test_delete_command_by_range(
+        #[case] matches: Option<&str>,
+        #[case] expected_ids: &[i64],
+    ) {
+        let from = Local
+            .with_ymd_and_hms(2024, 1, 9, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let to = Local
+            .with_ymd_and_hms(2024, 1, 10, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let end_at = Local
+            .with_ymd_and_hms(2024, 1, 11, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let entries = dummy_entries(to);
+        let delete_times = expected_ids.len();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(from), predicate::eq(end_at))
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+            .expect_delete_time_entry()
+            .times(delete_times)
+            .returning(|_, _| Ok(()));
+
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+        let command = DeleteCommand::new(&toggl);
+        let result = command
+            .run(
+                DeleteArgs {
+                    from: Some("2024-01-09".parse().unwrap()),
+                    to: Some("2024-01-10".parse().unwrap()),
+                    matches: matches.map(str::to_string),
+                    ..dummy_args(None)
+                },
+                &mut presenter,
+                |_| Ok(true),
+            )
+            .await;
+
+        let deleted_ids: Vec<i64> = result.unwrap().iter().map(|entry| entry.id).collect();
+        assert_eq!(expected_ids.to_vec(), deleted_ids);
+    }
+
+    /// dry-run、確認の拒否、`--yes`の指定に応じて削除の有無が変わることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::dry_run(true, false, Ok(true), 0, false)]
+    #[case::declined(false, false, Ok(false), 0, true)]
+    #[case::confirmed(false, false, Ok(true), 1, true)]
+    #[case::yes(false, true, Ok(false), 1, false)]
+    async fn test_delete_command_confirmation(
+        #[case] dry_run: bool,
+        #[case] yes: bool,
+        #[case] answer: Result<bool>,
+        #[case] delete_times: usize,
+        #[case] expect_confirm: bool,
+    ) {
+        let date = Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+        let entries = dummy_entries(date);
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+            .expect_delete_time_entry()
+            .times(delete_times)
+            .returning(|_, _| Ok(()));
+
+        let mut confirmed = false;
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+        let command = DeleteCommand::new(&toggl);
+        let result = command
+            .run(
+                DeleteArgs {
+                    selectors: vec![EntrySelector::Last],
+                    yes,
+                    dry_run,
+                    ..dummy_args(Some(date))
+                },
+                &mut presenter,
+                |_| {
+                    confirmed = true;
+                    answer
+                },
+            )
+            .await;
+
+        assert_eq!(delete_times, result.unwrap().len());
+        assert_eq!(expect_confirm, confirmed);
+        assert_eq!(1, String::from_utf8(writer).unwrap().lines().count());
+    }
+
+    /// 削除対象がない場合はエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_delete_command_no_entry() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        toggl.expect_delete_time_entry().never();
+
+        let mut writer = Vec::new();
+        let mut presenter = ConsoleMarkdownList::new(&mut writer);
+        let command = DeleteCommand::new(&toggl);
+        let result = command
+            .run(
+                DeleteArgs {
+                    from: Some("2024-01-09".parse().unwrap()),
+                    ..dummy_args(None)
+                },
+                &mut presenter,
+                |_| Ok(true),
+            )
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用の引数を作成する。
+    fn dummy_args(date: Option<DateTime<Utc>>) -> DeleteArgs {
+        DeleteArgs {
+            selectors: vec![],
+            date: date.map(|date| DateExpr::Date(date.with_timezone(&Local).date_naive())),
+            from: None,
+            to: None,
+            matches: None,
+            yes: false,
+            dry_run: false,
+        }
+    }
+
+    /// テスト用のタイムエントリー一覧を作成する。
+    fn dummy_entries(date: DateTime<Utc>) -> Vec<TimeEntry> {
+        [(1, 3, "meeting"), (2, 2, "review"), (3, 1, "review")]
+            .into_iter()
+            .map(|(id, hours_ago, description)| TimeEntry {
+                id,
+                workspace_id: 10,
+                start: date - Duration::hours(hours_ago),
+                stop: Some(date - Duration::hours(hours_ago - 1)),
+                duration: 3600,
+                description: description.to_string(),
+                project_id: None,
+                project: None,
+                tags: vec![],
+                ..Default::default()
+            })
+            .collect()
+    }
+}
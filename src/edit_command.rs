@@ -0,0 +1,545 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use log::info;
+
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::entry_selector::{find_time_entry, parse_entry_selector, EntrySelector};
+use crate::project::{find_project_by_name, Project};
+use crate::time_entry::{TimeEntry, TimeEntryUpdate};
+use crate::toggl::TogglRepository;
+
+/// 既存のタイムエントリーを修正するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct EditArgs {
+    #[clap(
+        help = "Time entry to edit: `last`, an index shown by `daily --number`, or `id:<ID>`",
+        parse(try_from_str = parse_entry_selector),
+    )]
+    selector: EntrySelector,
+
+    #[clap(
+        short = 'd',
+        long = "date",
+        help = "Sets the date such as YYYY-MM-DD or yesterday used to resolve indices. `last` is searched in the 7 days up to the date",
+        allow_hyphen_values = true
+    )]
+    date: Option<DateExpr>,
+
+    #[clap(
+        long = "start",
+        help = "Sets the start time in the format HH:MM on the date the entry started",
+        parse(try_from_str = parse_time),
+    )]
+    start: Option<NaiveTime>,
+
+    #[clap(
+        long = "stop",
+        help = "Sets the stop time in the format HH:MM. A time before the start is treated as the next day",
+        parse(try_from_str = parse_time),
+    )]
+    stop: Option<NaiveTime>,
+
+    #[clap(long = "description", help = "Sets the description")]
+    description: Option<String>,
+
+    #[clap(
+        short = 'p',
+        long = "project",
+        help = "Sets the project by name. An empty name removes the project"
+    )]
+    project: Option<String>,
+
+    #[clap(long = "add-tag", help = "Adds a tag. Can be specified multiple times")]
+    add_tags: Vec<String>,
+
+    #[clap(
+        long = "remove-tag",
+        help = "Removes a tag. Can be specified multiple times"
+    )]
+    remove_tags: Vec<String>,
+}
+
+pub struct EditCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository> EditCommand<'a, T> {
+    /// 新しい`EditCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self {
+            toggl_client,
+            zone: Zone::default(),
+        }
+    }
+
+    /// 日付と時刻を扱うタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `edit`サブコマンドの処理を行う。
+    ///
+    /// 選択したタイムエントリーを指定された内容で更新し、更新後のタイムエントリーを返す。
+    /// 時刻は設定したタイムゾーンでタイムエントリーを開始した日の時刻として扱う。
+    pub async fn run(&self, args: EditArgs) -> Result<TimeEntry> {
+        let date = match args.date {
+            Some(date) => date.start_of_day(&self.zone)?,
+            None => now(),
+        };
+        let entry = find_time_entry(self.toggl_client, &args.selector, &date, &self.zone).await?;
+        info!("Edit time entry: {}", entry.id);
+
+        let project_id = match args.project {
+            // 空のプロジェクト名はプロジェクトを外す指定として扱う
+            Some(name) if name.is_empty() => Some(None),
+            Some(name) => {
+                let projects = self
+                    .toggl_client
+                    .read_projects()
+                    .await
+                    .context("Failed to retrieve projects")?;
+                // タイムエントリーと異なるワークスペースのプロジェクトは指定できない
+                let projects: Vec<Project> = projects
+                    .into_iter()
+                    .filter(|project| project.workspace_id == entry.workspace_id)
+                    .collect();
+                Some(Some(find_project_by_name(&projects, &name)?.id))
+            }
+            None => None,
+        };
+        let tags = if args.add_tags.is_empty() && args.remove_tags.is_empty() {
+            None
+        } else {
+            Some(edit_tags(&entry.tags, &args.add_tags, &args.remove_tags))
+        };
+        let update = TimeEntryUpdate {
+            description: args.description,
+            project_id,
+            tags,
+            ..edit_times(&entry, args.start, args.stop, &self.zone)?
+        };
+        if update == TimeEntryUpdate::default() {
+            bail!("Nothing to update");
+        }
+
+        self.toggl_client
+            .update_time_entry(entry.workspace_id, entry.id, &update)
+            .await
+            .context("Failed to update time entry")
+    }
+}
+
+/// 時刻をパースする。
+fn parse_time(s: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .with_context(|| format!("Failed to parse time: {}", s))
+}
+
+/// 指定したタイムゾーンの日付と時刻から日時を作成する。
+fn to_datetime<Tz: TimeZone>(date: NaiveDate, time: NaiveTime, tz: &Tz) -> Result<DateTime<Utc>> {
+    let datetime = tz
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .with_context(|| format!("Failed to convert to DateTime: {}", date.and_time(time)))?
+        .to_utc();
+
+    Ok(datetime)
+}
+
+/// 開始日時と終了日時のみを設定した更新内容を計算する。
+///
+/// 開始と終了のどちらかを変更する場合、終了済みのタイムエントリーは経過時間を再計算するために両方を返す。
+/// 終了時刻が開始日時より前になる場合は、翌日の時刻として扱う。
+fn edit_times<Tz: TimeZone>(
+    entry: &TimeEntry,
+    start: Option<NaiveTime>,
+    stop: Option<NaiveTime>,
+    tz: &Tz,
+) -> Result<TimeEntryUpdate>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    if start.is_none() && stop.is_none() {
+        return Ok(TimeEntryUpdate::default());
+    }
+
+    let date = entry.start.with_timezone(tz).date_naive();
+    let new_start = match start {
+        Some(time) => to_datetime(date, time, tz)?,
+        None => entry.start,
+    };
+    let new_stop = match stop {
+        Some(time) => {
+            let stop = to_datetime(date, time, tz)?;
+            if stop < new_start {
+                let next_date = date.succ_opt().context("Failed to get next date")?;
+                Some(to_datetime(next_date, time, tz)?)
+            } else {
+                Some(stop)
+            }
+        }
+        None => entry.stop,
+    };
+    if let Some(new_stop) = new_stop {
+        if new_stop <= new_start {
+            bail!(
+                "Stop time must be after start time: {} ~ {}",
+                new_start.with_timezone(tz),
+                new_stop.with_timezone(tz)
+            );
+        }
+    }
+
+    Ok(TimeEntryUpdate {
+        start: Some(new_start),
+        stop: new_stop,
+        ..Default::default()
+    })
+}
+
+/// タグを追加、削除した後のタグ一覧を返す。
+///
+/// 既に付与されているタグは重複して追加しない。
+fn edit_tags(tags: &[String], add_tags: &[String], remove_tags: &[String]) -> Vec<String> {
+    let mut new_tags: Vec<String> = Vec::new();
+    tags.iter().chain(add_tags.iter()).for_each(|tag| {
+        if !new_tags.contains(tag) && !remove_tags.contains(tag) {
+            new_tags.push(tag.clone());
+        }
+    });
+
+    new_tags
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::edit_tags;
+    use super::edit_times;
+    use super::parse_time;
+    use super::EditArgs;
+    use super::EditCommand;
+    use crate::datetime::Zone;
+    use crate::entry_selector::EntrySelector;
+    use crate::project::Project;
+    use crate::time_entry::{TimeEntry, TimeEntryUpdate};
+    use crate::toggl::MockTogglRepository;
+
+    /// 指定された内容でタイムエントリーを更新することを確認する。
+    #[tokio::test]
+    async fn test_edit_command() {
+        let entry = dummy_entry(None);
+        let stop = local(2024, 1, 10, 18, 30);
+        let expected_update = TimeEntryUpdate {
+            start: Some(entry.start),
+            stop: Some(stop),
+            description: Some("new description".to_string()),
+            project_id: Some(Some(3)),
+            tags: Some(vec!["tag 1".to_string(), "tag 3".to_string()]),
+        };
+        let updated = TimeEntry {
+            stop: Some(stop),
+            ..entry.clone()
+        };
+        let expected = updated.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entry()
+            .with(predicate::eq(1))
+            .times(1)
+            .returning(move |_| Ok(entry.clone()));
+        toggl
+            .expect_read_projects()
+            .times(1)
+            .returning(|| Ok(dummy_projects()));
+        toggl
+            .expect_update_time_entry()
+            .with(
+                predicate::eq(10),
+                predicate::eq(1),
+                predicate::eq(expected_update),
+            )
+            .times(1)
+            .returning(move |_, _, _| Ok(updated.clone()));
+
+        let command = EditCommand::new(&toggl);
+        let result = command
+            .run(EditArgs {
+                selector: EntrySelector::Id(1),
+                date: None,
+                start: None,
+                stop: Some(NaiveTime::from_hms_opt(18, 30, 0).unwrap()),
+                description: Some("new description".to_string()),
+                project: Some("project 1".to_string()),
+                add_tags: vec!["tag 3".to_string()],
+                remove_tags: vec!["tag 2".to_string()],
+            })
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 空のプロジェクト名を指定した場合は、プロジェクトを外すことを確認する。
+    #[tokio::test]
+    async fn test_edit_command_clear_project() {
+        let entry = TimeEntry {
+            project_id: Some(3),
+            project: Some("project 1".to_string()),
+            ..dummy_entry(None)
+        };
+        let expected_update = TimeEntryUpdate {
+            project_id: Some(None),
+            ..Default::default()
+        };
+        let updated = dummy_entry(None);
+        let expected = updated.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entry()
+            .times(1)
+            .returning(move |_| Ok(entry.clone()));
+        toggl.expect_read_projects().never();
+        toggl
+            .expect_update_time_entry()
+            .with(
+                predicate::eq(10),
+                predicate::eq(1),
+                predicate::eq(expected_update),
+            )
+            .times(1)
+            .returning(move |_, _, _| Ok(updated.clone()));
+
+        let command = EditCommand::new(&toggl);
+        let result = command
+            .run(EditArgs {
+                selector: EntrySelector::Id(1),
+                date: None,
+                start: None,
+                stop: None,
+                description: None,
+                project: Some("".to_string()),
+                add_tags: vec![],
+                remove_tags: vec![],
+            })
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 更新内容が指定されていない場合はエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_edit_command_nothing_to_update() {
+        let entry = dummy_entry(None);
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entry()
+            .times(1)
+            .returning(move |_| Ok(entry.clone()));
+        toggl.expect_update_time_entry().never();
+
+        let command = EditCommand::new(&toggl);
+        let result = command
+            .run(EditArgs {
+                selector: EntrySelector::Id(1),
+                date: None,
+                start: None,
+                stop: None,
+                description: None,
+                project: None,
+                add_tags: vec![],
+                remove_tags: vec![],
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    /// 開始日時と終了日時が正しく計算されることを確認する。
+    #[rstest]
+    #[case::no_change(dummy_entry(Some(local(2024, 1, 10, 10, 0))), None, None, None, None)]
+    #[case::stop_running(
+        dummy_entry(None),
+        None,
+        Some((18, 30)),
+        Some(local(2024, 1, 10, 9, 0)),
+        Some(local(2024, 1, 10, 18, 30)),
+    )]
+    #[case::start_of_stopped(
+        dummy_entry(Some(local(2024, 1, 10, 10, 0))),
+        Some((9, 5)),
+        None,
+        Some(local(2024, 1, 10, 9, 5)),
+        Some(local(2024, 1, 10, 10, 0)),
+    )]
+    #[case::start_of_running(
+        dummy_entry(None),
+        Some((9, 5)),
+        None,
+        Some(local(2024, 1, 10, 9, 5)),
+        None,
+    )]
+    #[case::stop_next_day(
+        dummy_entry(None),
+        None,
+        Some((1, 0)),
+        Some(local(2024, 1, 10, 9, 0)),
+        Some(local(2024, 1, 11, 1, 0)),
+    )]
+    fn test_edit_times(
+        #[case] entry: TimeEntry,
+        #[case] start: Option<(u32, u32)>,
+        #[case] stop: Option<(u32, u32)>,
+        #[case] expected_start: Option<DateTime<Utc>>,
+        #[case] expected_stop: Option<DateTime<Utc>>,
+    ) {
+        let to_time =
+            |(hour, minute): (u32, u32)| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+
+        let result = edit_times(&entry, start.map(to_time), stop.map(to_time), &Local);
+
+        let expected = TimeEntryUpdate {
+            start: expected_start,
+            stop: expected_stop,
+            ..Default::default()
+        };
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 時刻を指定したタイムゾーンで、タイムエントリーを開始した日の時刻として扱うことを確認する。
+    ///
+    /// UTCで2024-01-10 15:30に開始したタイムエントリーは、東京では2024-01-11に開始している。
+    #[test]
+    fn test_edit_times_time_zone() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 10, 15, 30, 0).unwrap();
+        let entry = TimeEntry {
+            start,
+            stop: Some(start + chrono::Duration::hours(1)),
+            ..dummy_entry(None)
+        };
+
+        let result = edit_times(
+            &entry,
+            Some(NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+            Some(NaiveTime::from_hms_opt(8, 0, 0).unwrap()),
+            &Zone::Named(chrono_tz::Asia::Tokyo),
+        );
+
+        let expected = TimeEntryUpdate {
+            start: Some(Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap()),
+            stop: Some(Utc.with_ymd_and_hms(2024, 1, 11, 23, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 開始日時が終了日時以降になる場合にエラーとなることを確認する。
+    #[test]
+    fn test_edit_times_error() {
+        let entry = dummy_entry(Some(local(2024, 1, 10, 10, 0)));
+
+        let result = edit_times(
+            &entry,
+            Some(NaiveTime::from_hms_opt(11, 0, 0).unwrap()),
+            None,
+            &Local,
+        );
+
+        assert!(result.is_err());
+    }
+
+    /// タグの追加と削除が正しく行われることを確認する。
+    #[rstest]
+    #[case::add(&["a"], &["b"], &[], &["a", "b"])]
+    #[case::add_existing(&["a"], &["a"], &[], &["a"])]
+    #[case::remove(&["a", "b"], &[], &["a"], &["b"])]
+    #[case::remove_unknown(&["a"], &[], &["c"], &["a"])]
+    #[case::add_and_remove(&["a", "b"], &["c"], &["b"], &["a", "c"])]
+    fn test_edit_tags(
+        #[case] tags: &[&str],
+        #[case] add_tags: &[&str],
+        #[case] remove_tags: &[&str],
+        #[case] expected: &[&str],
+    ) {
+        let to_strings = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+
+        let result = edit_tags(
+            &to_strings(tags),
+            &to_strings(add_tags),
+            &to_strings(remove_tags),
+        );
+
+        assert_eq!(to_strings(expected), result);
+    }
+
+    /// 正常に時刻をパースできることを確認する。
+    #[rstest]
+    #[case::minutes("09:05", NaiveTime::from_hms_opt(9, 5, 0).unwrap())]
+    #[case::seconds("18:30:15", NaiveTime::from_hms_opt(18, 30, 15).unwrap())]
+    fn test_parse_time(#[case] s: &str, #[case] expected: NaiveTime) {
+        assert_eq!(expected, parse_time(s).unwrap());
+    }
+
+    /// 入力時刻が間違っている場合にエラーを返すことを確認する。
+    #[rstest]
+    #[case::invalid_hour("25:00")]
+    #[case::invalid_format("9.05")]
+    #[case::empty_string("")]
+    fn test_parse_time_invalid(#[case] s: &str) {
+        assert!(parse_time(s).is_err());
+    }
+
+    /// Localタイムゾーンの日時を作成する。
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用に、Localタイムゾーンで2024-01-10 09:00に開始したタイムエントリーを作成する。
+    fn dummy_entry(stop: Option<DateTime<Utc>>) -> TimeEntry {
+        let start = local(2024, 1, 10, 9, 0);
+        TimeEntry {
+            id: 1,
+            workspace_id: 10,
+            start,
+            stop,
+            duration: stop.map(|stop| (stop - start).num_seconds()).unwrap_or(-1),
+            description: "entry 1".to_string(),
+            project_id: None,
+            project: None,
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// テスト用のプロジェクト一覧を作成する。
+    ///
+    /// `project 1`は複数のワークスペースに存在する。
+    fn dummy_projects() -> Vec<Project> {
+        vec![
+            Project {
+                id: 2,
+                workspace_id: 20,
+                name: "project 1".to_string(),
+            },
+            Project {
+                id: 3,
+                workspace_id: 10,
+                name: "project 1".to_string(),
+            },
+        ]
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use log::info;
+
+use crate::aggregation::{overlaps, CLIP_LOOKBACK_HOURS};
+use crate::range::day_range;
+use crate::time_entry::{sorted_by_start, TimeEntry};
+use crate::toggl::TogglRepository;
+
+/// 番号以外で選択する場合に遡って検索する日数。
+pub const LOOKBACK_DAYS: i64 = 7;
+
+/// タイムエントリーの一覧から対象を選択する方法。
+#[derive(Clone, PartialEq, Debug)]
+pub enum EntrySelector {
+    /// タイムエントリーのID
+    Id(i64),
+    /// 最も新しく開始したタイムエントリー
+    Last,
+    /// `daily --number`で表示される1から始まる番号
+    Index(usize),
+    /// 説明に指定した文字列を含む、最も新しく開始したタイムエントリー
+    Match(String),
+}
+
+/// 選択方法をパースする。
+///
+/// `last`は最も新しいタイムエントリー、`id:<ID>`はタイムエントリーのID、数値は`daily --number`の番号として扱う。
+pub fn parse_entry_selector(s: &str) -> Result<EntrySelector> {
+    if s == "last" {
+        return Ok(EntrySelector::Last);
+    }
+    if let Some(id) = s.strip_prefix("id:") {
+        let id = id
+            .parse::<i64>()
+            .with_context(|| format!("Failed to parse time entry ID: {}", id))?;
+        return Ok(EntrySelector::Id(id));
+    }
+    let index = s.parse::<usize>().with_context(|| {
+        format!(
+            "Failed to parse selector: {}. Use last, an index or id:<ID>",
+            s
+        )
+    })?;
+
+    Ok(EntrySelector::Index(index))
+}
+
+/// タイムエントリーの一覧から選択方法に該当するタイムエントリーを返す。
+///
+/// 該当するタイムエントリーが存在しない場合はエラーを返す。
+///
+/// # Arguments
+///
+/// * `time_entries` - 選択対象のタイムエントリー
+/// * `selector` - 選択方法
+pub fn select_time_entry(
+    time_entries: &[TimeEntry],
+    selector: &EntrySelector,
+) -> Result<TimeEntry> {
+    let sorted_entries = sorted_by_start(time_entries);
+
+    match selector {
+        EntrySelector::Id(id) => sorted_entries
+            .into_iter()
+            .find(|entry| entry.id == *id)
+            .with_context(|| format!("No time entry found with ID: {}", id)),
+        EntrySelector::Last => sorted_entries
+            .last()
+            .cloned()
+            .context("No time entry found"),
+        EntrySelector::Index(index) => {
+            if *index == 0 {
+                bail!("Index must start from 1");
+            }
+            sorted_entries.get(index - 1).cloned().with_context(|| {
+                format!(
+                    "Index {} is out of range (found {} time entries). Use id:<ID> to select a time entry by its ID",
+                    index,
+                    sorted_entries.len()
+                )
+            })
+        }
+        EntrySelector::Match(text) => {
+            let text = text.to_lowercase();
+            sorted_entries
+                .iter()
+                .rev()
+                .find(|entry| entry.description.to_lowercase().contains(&text))
+                .cloned()
+                .with_context(|| format!("No time entry matches: {}", text))
+        }
+    }
+}
+
+/// 選択方法に該当するタイムエントリーをToggl APIから取得する。
+///
+/// IDが指定された場合はIDで取得し、番号の場合は指定された日時を含む1日のタイムエントリーから選択する。
+/// それ以外は、指定された日時を含む日までの過去7日間のタイムエントリーから選択する。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `selector` - 選択方法
+/// * `date` - 選択対象とする日を含む日時
+/// * `tz` - 日の境界とするタイムゾーン
+pub async fn find_time_entry<T: TogglRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    selector: &EntrySelector,
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<TimeEntry> {
+    if let EntrySelector::Id(id) = selector {
+        return toggl_client
+            .read_time_entry(*id)
+            .await
+            .context("Failed to retrieve time entry");
+    }
+
+    let time_entries = match selector {
+        EntrySelector::Index(_) => read_day_time_entries(toggl_client, date, tz).await?,
+        _ => read_recent_time_entries(toggl_client, date, tz).await?,
+    };
+
+    select_time_entry(&time_entries, selector)
+}
+
+/// 指定された日時を含む日までの過去7日間のタイムエントリーをToggl APIから取得する。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `date` - 最後の日を含む日時
+/// * `tz` - 日の境界とするタイムゾーン
+pub async fn read_recent_time_entries<T: TogglRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Vec<TimeEntry>> {
+    let (day_start_at, end_at) = day_range(date, tz)?;
+    let start_at = day_start_at - TimeDelta::days(LOOKBACK_DAYS - 1);
+    info!("Start at: {}, End at: {}", start_at, end_at);
+
+    toggl_client
+        .read_time_entries(&start_at, &end_at)
+        .await
+        .context("Failed to retrieve time entries")
+}
+
+/// 指定された日時を含む1日と重なるタイムエントリーをToggl APIから取得する。
+///
+/// `daily --number`の番号と一致するよう、前日から続くタイムエントリーも含める。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `date` - 取得する日を含む日時
+/// * `tz` - 日の境界とするタイムゾーン
+pub async fn read_day_time_entries<T: TogglRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Vec<TimeEntry>> {
+    let (start_at, end_at) = day_range(date, tz)?;
+    info!("Start at: {}, End at: {}", start_at, end_at);
+    let time_entries = toggl_client
+        .read_time_entries(&(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)), &end_at)
+        .await
+        .context("Failed to retrieve time entries")?;
+
+    day_time_entries(&time_entries, date, tz)
+}
+
+/// タイムエントリーの一覧から、指定された日時を含む1日と重なるものを返す。
+///
+/// # Arguments
+///
+/// * `time_entries` - 絞り込むタイムエントリー
+/// * `date` - 対象の日を含む日時
+/// * `tz` - 日の境界とするタイムゾーン
+pub fn day_time_entries<Tz: TimeZone>(
+    time_entries: &[TimeEntry],
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Vec<TimeEntry>> {
+    let (start_at, end_at) = day_range(date, tz)?;
+
+    Ok(time_entries
+        .iter()
+        .filter(|entry| overlaps(entry, &start_at, &end_at))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Asia::Tokyo;
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::find_time_entry;
+    use super::parse_entry_selector;
+    use super::read_day_time_entries;
+    use super::select_time_entry;
+    use super::EntrySelector;
+    use super::LOOKBACK_DAYS;
+    use crate::aggregation::CLIP_LOOKBACK_HOURS;
+    use crate::range::day_range;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::MockTogglRepository;
+
+    /// 選択方法に該当するタイムエントリーが返ることを確認する。
+    #[rstest]
+    #[case::id(EntrySelector::Id(2), 2)]
+    #[case::last(EntrySelector::Last, 3)]
+    #[case::first_index(EntrySelector::Index(1), 2)]
+    #[case::last_index(EntrySelector::Index(3), 3)]
+    #[case::match_latest(EntrySelector::Match("Review".to_string()), 3)]
+    #[case::match_unique(EntrySelector::Match("meeting".to_string()), 1)]
+    fn test_select_time_entry(#[case] selector: EntrySelector, #[case] expected_id: i64) {
+        let result = select_time_entry(&dummy_entries(), &selector);
+
+        assert_eq!(expected_id, result.unwrap().id);
+    }
+
+    /// 該当するタイムエントリーがない場合にエラーとなることを確認する。
+    #[rstest]
+    #[case::unknown_id(EntrySelector::Id(4), &dummy_entries())]
+    #[case::zero_index(EntrySelector::Index(0), &dummy_entries())]
+    #[case::out_of_range(EntrySelector::Index(4), &dummy_entries())]
+    #[case::no_match(EntrySelector::Match("lunch".to_string()), &dummy_entries())]
+    #[case::last_of_empty(EntrySelector::Last, &[])]
+    fn test_select_time_entry_error(
+        #[case] selector: EntrySelector,
+        #[case] time_entries: &[TimeEntry],
+    ) {
+        let result = select_time_entry(time_entries, &selector);
+
+        assert!(result.is_err());
+    }
+
+    /// IDが指定された場合はIDで取得することを確認する。
+    #[tokio::test]
+    async fn test_find_time_entry_by_id() {
+        let date = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let expected = dummy_entries()[1].clone();
+        let entry = expected.clone();
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entry()
+            .with(predicate::eq(2))
+            .times(1)
+            .returning(move |_| Ok(entry.clone()));
+        toggl.expect_read_time_entries().never();
+
+        let result = find_time_entry(&toggl, &EntrySelector::Id(2), &date, &Tokyo).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 番号の場合は指定日、それ以外は指定日までの過去7日間のタイムエントリーから選択することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::index(
+        EntrySelector::Index(1),
+        chrono::Duration::hours(CLIP_LOOKBACK_HOURS),
+        2
+    )]
+    #[case::last(EntrySelector::Last, chrono::Duration::days(LOOKBACK_DAYS - 1), 3)]
+    #[case::matches(EntrySelector::Match("meeting".to_string()), chrono::Duration::days(LOOKBACK_DAYS - 1), 1)]
+    async fn test_find_time_entry_from_range(
+        #[case] selector: EntrySelector,
+        #[case] lookback: chrono::Duration,
+        #[case] expected_id: i64,
+    ) {
+        let date = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let (start_at, end_at) = day_range(&date, &Tokyo).unwrap();
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entry().never();
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(start_at - lookback), predicate::eq(end_at))
+            .times(1)
+            .returning(|_, _| Ok(dummy_entries()));
+
+        let result = find_time_entry(&toggl, &selector, &date, &Tokyo).await;
+
+        assert_eq!(expected_id, result.unwrap().id);
+    }
+
+    /// 指定日と重なるタイムエントリーのみを、前日から続くものも含めて返すことを確認する。
+    ///
+    /// 東京の2024-01-01は、UTCで2023-12-31 15:00から始まる。
+    #[tokio::test]
+    async fn test_read_day_time_entries() {
+        let date = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let entry = |id: i64, hour: u32| TimeEntry {
+            id,
+            start: Utc.with_ymd_and_hms(2023, 12, 31, hour, 0, 0).unwrap(),
+            stop: Some(Utc.with_ymd_and_hms(2023, 12, 31, hour + 2, 0, 0).unwrap()),
+            duration: 2 * 3600,
+            ..Default::default()
+        };
+        let mut entries = vec![entry(4, 12), entry(5, 14)];
+        entries.extend(dummy_entries());
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+
+        let result = read_day_time_entries(&toggl, &date, &Tokyo).await;
+
+        let ids: Vec<i64> = result.unwrap().iter().map(|entry| entry.id).collect();
+        assert_eq!(vec![5, 1, 2, 3], ids);
+    }
+
+    /// 選択方法をパースできることを確認する。
+    #[rstest]
+    #[case::last("last", EntrySelector::Last)]
+    #[case::index("2", EntrySelector::Index(2))]
+    #[case::id("id:1234567890", EntrySelector::Id(1234567890))]
+    fn test_parse_entry_selector(#[case] s: &str, #[case] expected: EntrySelector) {
+        let result = parse_entry_selector(s);
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 不正な選択方法がエラーとなることを確認する。
+    #[rstest]
+    #[case::empty("")]
+    #[case::negative_index("-1")]
+    #[case::text("first")]
+    #[case::invalid_id("id:abc")]
+    #[case::empty_id("id:")]
+    fn test_parse_entry_selector_error(#[case] s: &str) {
+        let result = parse_entry_selector(s);
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用のタイムエントリー一覧を作成する。
+    ///
+    /// 開始時刻順にならないように並べている。
+    fn dummy_entries() -> Vec<TimeEntry> {
+        [(1, 10, "meeting"), (2, 9, "review"), (3, 11, "review")]
+            .into_iter()
+            .map(|(id, hour, description)| TimeEntry {
+                id,
+                workspace_id: 1,
+                start: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+                stop: Some(Utc.with_ymd_and_hms(2024, 1, 1, hour + 1, 0, 0).unwrap()),
+                duration: 3600,
+                description: description.to_string(),
+                project_id: None,
+                project: None,
+                tags: vec![],
+                ..Default::default()
+            })
+            .collect()
+    }
+}
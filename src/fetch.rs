@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, TimeZone, Utc};
+use log::info;
+
+use crate::aggregation::{
+    clip_time_entries, count_running_until, split_by_day, CLIP_LOOKBACK_HOURS,
+};
+use crate::datetime::now;
+use crate::source::{read_time_entries, Source};
+use crate::time_entry::TimeEntry;
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 期間のタイムエントリーを取得する方法。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FetchOptions {
+    /// タイムエントリーの取得元
+    pub source: Source,
+    /// 実行中のタイムエントリーを現在までの経過時間で集計するか
+    pub include_running: bool,
+    /// タイムエントリーを期間の範囲で切り詰めるか
+    pub clip: bool,
+}
+
+/// 期間のタイムエントリーを取得し、集計できるように整えて返す。
+///
+/// 実行中のタイムエントリーを集計する場合は、現在までの経過時間を設定する。
+/// 切り詰める場合は、期間の開始前から続くタイムエントリーも含めるため早めに取得し、期間に含まれる部分のみを返す。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+/// * `options` - タイムエントリーを取得する方法
+/// * `start_at` - 期間の開始日時
+/// * `end_at` - 期間の終了日時(この日時を含まない)
+/// * `tz` - Reports APIに指定する日付を決めるタイムゾーン
+pub async fn read_range_time_entries<T: TogglRepository, R: ReportsRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    reports_client: &R,
+    options: &FetchOptions,
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Vec<TimeEntry>> {
+    let fetch_start_at = if options.clip {
+        *start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)
+    } else {
+        *start_at
+    };
+    let time_entries = read_time_entries(
+        toggl_client,
+        reports_client,
+        options.source,
+        options.include_running,
+        &fetch_start_at,
+        end_at,
+        tz,
+    )
+    .await
+    .context("Failed to retrieve time entries")?;
+    info!("Time entries retrieved successfully.");
+    let time_entries = if options.include_running {
+        count_running_until(time_entries, &now())
+    } else {
+        time_entries
+    };
+    if !options.clip {
+        return Ok(time_entries);
+    }
+
+    Ok(clip_time_entries(&time_entries, start_at, end_at))
+}
+
+/// タイムエントリーを、指定したタイムゾーンの日ごとにまとめる。
+///
+/// 切り詰める場合は、日付をまたぐタイムエントリーをそれぞれの日に含まれる部分に分割する。
+/// 切り詰めない場合は、開始した日にまとめる。
+///
+/// # Arguments
+///
+/// * `time_entries` - まとめるタイムエントリー
+/// * `clip` - 日付をまたぐタイムエントリーを日ごとに切り詰めるか
+/// * `tz` - 日の区切りに利用するタイムゾーン
+pub fn group_by_day<Tz: TimeZone>(
+    time_entries: Vec<TimeEntry>,
+    clip: bool,
+    tz: &Tz,
+) -> Result<HashMap<NaiveDate, Vec<TimeEntry>>> {
+    if clip {
+        return split_by_day(&time_entries, tz);
+    }
+
+    Ok(time_entries
+        .into_iter()
+        .fold(HashMap::new(), |mut acc, entry| {
+            let start = entry.start.with_timezone(tz).date_naive();
+            acc.entry(start).or_default().push(entry);
+            acc
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Asia::Tokyo;
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::group_by_day;
+    use super::read_range_time_entries;
+    use super::FetchOptions;
+    use crate::aggregation::CLIP_LOOKBACK_HOURS;
+    use crate::datetime::mock_datetime;
+    use crate::source::Source;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// 切り詰める場合は早めに取得して期間の範囲に切り詰め、実行中のタイムエントリーは指定に応じて現在までの経過時間とすることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(true, false, TimeDelta::hours(CLIP_LOOKBACK_HOURS), &[(3600, true), (-1, false)])]
+    #[case::clip_include_running(true, true, TimeDelta::hours(CLIP_LOOKBACK_HOURS), &[(3600, true), (1800, false)])]
+    #[case::no_clip(false, false, TimeDelta::zero(), &[(7200, true), (-1, false)])]
+    async fn test_read_range_time_entries(
+        #[case] clip: bool,
+        #[case] include_running: bool,
+        #[case] lookback: TimeDelta,
+        #[case] expected: &[(i64, bool)],
+    ) {
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap();
+        mock_datetime::set_mock_time(start_at + TimeDelta::hours(1));
+        let entries = vec![
+            // 期間の開始前から続くタイムエントリー
+            TimeEntry {
+                id: 1,
+                start: start_at - TimeDelta::hours(1),
+                stop: Some(start_at + TimeDelta::hours(1)),
+                duration: 7200,
+                ..Default::default()
+            },
+            // 実行中のタイムエントリー
+            TimeEntry {
+                id: 2,
+                start: start_at + TimeDelta::minutes(30),
+                stop: None,
+                duration: -1,
+                ..Default::default()
+            },
+        ];
+        let mut toggl_client = MockTogglRepository::new();
+        toggl_client
+            .expect_read_time_entries()
+            .with(predicate::eq(start_at - lookback), predicate::eq(end_at))
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let reports_client = MockReportsRepository::new();
+        let options = FetchOptions {
+            source: Source::TimeEntries,
+            include_running,
+            clip,
+        };
+
+        let result = read_range_time_entries(
+            &toggl_client,
+            &reports_client,
+            &options,
+            &start_at,
+            &end_at,
+            &Utc,
+        )
+        .await;
+        mock_datetime::clear_mock_time();
+
+        let durations: Vec<(i64, bool)> = result
+            .unwrap()
+            .iter()
+            .map(|entry| (entry.duration, entry.stop.is_some()))
+            .collect();
+        assert_eq!(expected, durations);
+    }
+
+    /// 切り詰める場合は日付をまたぐタイムエントリーを日ごとに分割し、切り詰めない場合は開始した日にまとめることを確認する。
+    #[rstest]
+    #[case::clip(true, &[("2024-01-01", 3600), ("2024-01-02", 3600)])]
+    #[case::no_clip(false, &[("2024-01-01", 7200)])]
+    fn test_group_by_day(#[case] clip: bool, #[case] expected: &[(&str, i64)]) {
+        let start = Tokyo
+            .with_ymd_and_hms(2024, 1, 1, 23, 0, 0)
+            .unwrap()
+            .to_utc();
+        let entries = vec![TimeEntry {
+            id: 1,
+            start,
+            stop: Some(start + TimeDelta::hours(2)),
+            duration: 7200,
+            ..Default::default()
+        }];
+
+        let result = group_by_day(entries, clip, &Tokyo).unwrap();
+
+        let mut durations: Vec<(NaiveDate, i64)> = result
+            .iter()
+            .map(|(date, entries)| (*date, entries.iter().map(|entry| entry.duration).sum()))
+            .collect();
+        durations.sort();
+        let expected: Vec<(NaiveDate, i64)> = expected
+            .iter()
+            .map(|(date, duration)| (date.parse().unwrap(), *duration))
+            .collect();
+        assert_eq!(expected, durations);
+    }
+}
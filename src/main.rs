@@ -1,29 +1,78 @@
 use std::error::Error as StdError;
 use std::path::PathBuf;
+use std::process::ExitCode;
 use std::{env, path::Path};
 
 use anyhow::{Context, Error, Result};
 use clap::{Parser, Subcommand};
+use log::info;
 
+mod aggregation;
+mod config;
 mod console;
+mod continue_command;
+mod current_command;
 mod daily_command;
+mod date_expr;
 mod datetime;
+mod delete_command;
+mod edit_command;
+mod entry_selector;
+mod fetch;
 mod monthly_command;
+mod notice;
+mod project;
+mod quarterly_command;
+mod range;
+mod report_command;
+mod retry;
+mod source;
+mod start_command;
+mod stop_command;
+mod summary_args;
 mod time_entry;
 mod toggl;
+mod toggl_error;
+mod weekly_command;
+mod yearly_command;
 
-use console::{ConsoleMarkdownList, ConsolePresenter};
+use aggregation::{calc_total_duration, SortOrder};
+use config::Config;
+use console::{confirm, ConsoleMarkdownList, ConsolePresenter};
+use continue_command::{ContinueArgs, ContinueCommand};
+use current_command::{CurrentArgs, CurrentCommand};
 use daily_command::{DailyArgs, DailyCommand};
+use delete_command::{DeleteArgs, DeleteCommand};
+use edit_command::{EditArgs, EditCommand};
 use fern::colors::{Color, ColoredLevelConfig};
-use monthly_command::{monthly_command, MonthlyCommand};
+use monthly_command::{MonthlyArgs, MonthlyCommand, MonthlySummary};
+use quarterly_command::{QuarterlyArgs, QuarterlyCommand};
+use report_command::{ReportArgs, ReportCommand};
+use start_command::{StartArgs, StartCommand};
+use stop_command::{StopArgs, StopCommand};
 use toggl::TogglClient;
+use toggl_error::TogglError;
+use weekly_command::{WeeklyArgs, WeeklyCommand};
+use yearly_command::{YearlyArgs, YearlyCommand};
 
 /// time entryを取得するためのCLIアプリケーション。
 ///
 /// # Examples
 /// ```
 /// $ cargo run -- daily
+/// $ cargo run -- weekly --week 2024-W05
 /// $ cargo run -- monthly
+/// $ cargo run -- quarterly --quarter 2024-Q3
+/// $ cargo run -- yearly --year 2024
+/// $ cargo run -- report --from 2024-05-01 --to 2024-05-10
+/// $ cargo run -- report --last 7d
+/// $ cargo run -- --tz Asia/Tokyo weekly
+/// $ cargo run -- start "description" --project project --tag tag
+/// $ cargo run -- stop
+/// $ cargo run -- current
+/// $ cargo run -- continue
+/// $ cargo run -- edit last --stop 18:30
+/// $ cargo run -- delete last --dry-run
 /// ```
 #[derive(Debug, Parser)]
 #[clap(version, about)]
@@ -35,13 +84,29 @@ struct Args {
     /// If nothing is specified, it will default to the error level.
     verbose: u8,
 
+    #[clap(long = "tz", global = true)]
+    /// Sets the IANA time zone such as Asia/Tokyo, or local, used for day boundaries and times.
+    /// If not specified, it will be obtained from the config file, defaulting to the local time zone.
+    tz: Option<datetime::Zone>,
+
     #[clap(subcommand)]
     subcommand: SubCommands,
 }
 #[derive(Debug, Subcommand)]
 enum SubCommands {
     Daily(DailyArgs),
-    Monthly(MonthlyCommand),
+    Weekly(WeeklyArgs),
+    Monthly(MonthlyArgs),
+    Quarterly(QuarterlyArgs),
+    Yearly(YearlyArgs),
+    Report(ReportArgs),
+    Start(StartArgs),
+    Stop(StopArgs),
+    #[clap(visible_alias = "status")]
+    Current(CurrentArgs),
+    Continue(ContinueArgs),
+    Edit(EditArgs),
+    Delete(DeleteArgs),
 }
 
 /// ログファイルのパスを決定する。
@@ -155,7 +220,7 @@ fn init_logger(log_dir: &Path, log_level: &log::LevelFilter) -> Result<()> {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
 
     // 引数によるログレベルの指定がない場合は、環境変数から取得する。
@@ -187,26 +252,217 @@ async fn main() -> Result<()> {
         return Err(err);
     }
 
-    if let Err(err) = match args.subcommand {
+    let mut config = match Config::load().context("Failed to load config") {
+        Ok(config) => config,
+        Err(err) => {
+            let formatted_error = format_error_chain(&err);
+            log::error!("Failed to load config:\n{}", formatted_error);
+            return Err(err);
+        }
+    };
+
+    if let Some(tz) = args.tz {
+        config.report.timezone = tz;
+    }
+
+    match run_subcommand(args.subcommand, &config).await {
+        Ok(exit_code) => Ok(exit_code),
+        Err(err) => {
+            // Toggl APIのエラーは、シェルから原因を判別できるよう種類ごとの終了コードとし、
+            // 同じエラーを重ねて出力しないようメッセージとヒントのみを表示する
+            let Some(toggl_error) = err.chain().find_map(|e| e.downcast_ref::<TogglError>()) else {
+                let formatted_error = format_error_chain(&err);
+                log::error!("Failed to execute subcommand:\n{}", formatted_error);
+                return Err(err);
+            };
+            eprintln!("Error: {:?}", err);
+            if let Some(hint) = toggl_error.hint() {
+                eprintln!("\nHint: {}", hint);
+            }
+            Ok(ExitCode::from(toggl_error.exit_code()))
+        }
+    }
+}
+
+/// サブコマンドを実行し、プロセスの終了コードを返す。
+async fn run_subcommand(subcommand: SubCommands, config: &Config) -> Result<ExitCode> {
+    let client = TogglClient::new(&config.api).context("Failed to create Toggl client")?;
+    let zone = config.report.timezone;
+
+    match subcommand {
         SubCommands::Daily(daily) => {
-            let time_entries =
-                DailyCommand::new(&TogglClient::new().context("Failed to create Toggl client")?)
-                    .run(daily)
-                    .await
-                    .context("Failed to execute daily command")?;
-            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+            let numbered = daily.number;
+            let time_entries = DailyCommand::new(&client, &client)
+                .clip_to_range(config.report.clip_to_range)
+                .time_zone(zone)
+                .run(daily)
+                .await
+                .context("Failed to execute daily command")?;
+            let (total, in_progress) = calc_total_duration(&time_entries);
+            let mut stdout = std::io::stdout().lock();
+            let mut presenter = ConsoleMarkdownList::new(&mut stdout)
+                .numbered(numbered)
+                .time_zone(zone);
+            presenter
                 .show_time_entries(time_entries.as_ref())
                 .context("Failed to show time entries")?;
-            Ok(())
+            presenter
+                .show_total_duration(total, in_progress)
+                .context("Failed to show total duration")?;
+        }
+        SubCommands::Weekly(weekly) => {
+            let grid = WeeklyCommand::new(&client, &client)
+                .time_zone(zone)
+                .clip_to_range(config.report.clip_to_range)
+                .run(weekly)
+                .await
+                .context("Failed to execute weekly command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_duration_grid(&grid)
+                .context("Failed to show duration grid")?;
+        }
+        SubCommands::Monthly(monthly) => {
+            let sort_order = SortOrder::new(monthly.summary.sort, &config.report.project_order);
+            let summary = MonthlyCommand::new(&client, &client)
+                .time_zone(zone)
+                .clip_to_range(config.report.clip_to_range)
+                .run(monthly)
+                .await
+                .context("Failed to execute monthly command")?;
+            let mut stdout = std::io::stdout().lock();
+            let mut presenter = ConsoleMarkdownList::new(&mut stdout)
+                .sort_order(sort_order)
+                .time_zone(zone);
+            match summary {
+                MonthlySummary::Total(durations) => presenter.show_durations(&durations),
+                MonthlySummary::Daily(daily_durations) => {
+                    presenter.show_daily_durations(&daily_durations)
+                }
+            }
+            .context("Failed to show durations")?;
+        }
+        SubCommands::Quarterly(quarterly) => {
+            let sort_order = SortOrder::new(quarterly.summary.sort, &config.report.project_order);
+            let monthly_durations = QuarterlyCommand::new(&client, &client)
+                .time_zone(zone)
+                .clip_to_range(config.report.clip_to_range)
+                .fiscal_year_start_month(config.report.fiscal_year_start_month)
+                .run(quarterly)
+                .await
+                .context("Failed to execute quarterly command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .sort_order(sort_order)
+                .show_monthly_durations(&monthly_durations)
+                .context("Failed to show durations")?;
+        }
+        SubCommands::Yearly(yearly) => {
+            let sort_order = SortOrder::new(yearly.summary.sort, &config.report.project_order);
+            let monthly_durations = YearlyCommand::new(&client, &client)
+                .time_zone(zone)
+                .clip_to_range(config.report.clip_to_range)
+                .fiscal_year_start_month(config.report.fiscal_year_start_month)
+                .run(yearly)
+                .await
+                .context("Failed to execute yearly command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .sort_order(sort_order)
+                .show_monthly_durations(&monthly_durations)
+                .context("Failed to show durations")?;
+        }
+        SubCommands::Report(report) => {
+            let sort_order = SortOrder::new(report.summary.sort, &config.report.project_order);
+            let durations = ReportCommand::new(&client, &client)
+                .time_zone(zone)
+                .clip_to_range(config.report.clip_to_range)
+                .run(report)
+                .await
+                .context("Failed to execute report command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .sort_order(sort_order)
+                .show_durations(&durations)
+                .context("Failed to show durations")?;
+        }
+        SubCommands::Start(start) => {
+            let time_entry = StartCommand::new(&client)
+                .run(start)
+                .await
+                .context("Failed to execute start command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_time_entries(&[time_entry])
+                .context("Failed to show time entry")?;
+        }
+        SubCommands::Stop(stop) => {
+            let time_entry = StopCommand::new(&client)
+                .run(stop)
+                .await
+                .context("Failed to execute stop command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_time_entries(&[time_entry])
+                .context("Failed to show time entry")?;
+        }
+        SubCommands::Current(current) => {
+            let time_entry = CurrentCommand::new(&client)
+                .run(current)
+                .await
+                .context("Failed to execute current command")?;
+            // 実行中のタイムエントリーがない場合は、シェルから判定できるよう失敗の終了コードとする
+            let Some(time_entry) = time_entry else {
+                info!("No time entry is running.");
+                return Ok(ExitCode::FAILURE);
+            };
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_current_time_entry(&time_entry, &datetime::now())
+                .context("Failed to show current time entry")?;
+        }
+        SubCommands::Continue(continue_args) => {
+            let time_entry = ContinueCommand::new(&client)
+                .time_zone(zone)
+                .run(continue_args)
+                .await
+                .context("Failed to execute continue command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_time_entries(&[time_entry])
+                .context("Failed to show time entry")?;
+        }
+        SubCommands::Edit(edit) => {
+            let time_entry = EditCommand::new(&client)
+                .time_zone(zone)
+                .run(edit)
+                .await
+                .context("Failed to execute edit command")?;
+            ConsoleMarkdownList::new(&mut std::io::stdout().lock())
+                .time_zone(zone)
+                .show_time_entries(&[time_entry])
+                .context("Failed to show time entry")?;
+        }
+        SubCommands::Delete(delete) => {
+            let mut stdout = std::io::stdout().lock();
+            // 削除対象を取り違えないよう、開始日とIDを含めて表示する
+            let mut presenter = ConsoleMarkdownList::new(&mut stdout)
+                .detailed(true)
+                .time_zone(zone);
+            let deleted = DeleteCommand::new(&client)
+                .time_zone(zone)
+                .run(delete, &mut presenter, |time_entries| {
+                    confirm(
+                        &mut std::io::stdin().lock(),
+                        &mut std::io::stderr(),
+                        &format!("Delete {} time entries?", time_entries.len()),
+                    )
+                })
+                .await
+                .context("Failed to execute delete command")?;
+            info!("{} time entries deleted.", deleted.len());
         }
-        SubCommands::Monthly(monthly) => monthly_command(monthly)
-            .await
-            .context("Failed to execute monthly command"),
-    } {
-        let formatted_error = format_error_chain(&err);
-        log::error!("Failed to execute subcommand:\n{}", formatted_error);
-        return Err(err);
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -1,151 +1,753 @@
-use std::collections::HashMap;
-
-use anyhow::{Context, Ok, Result};
-use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Timelike, Utc};
+use anyhow::Result;
+use chrono::NaiveDate;
 use log::info;
 
-use crate::time_entry::TimeEntry;
-use crate::toggl::TogglClient;
-use crate::toggl::TogglRepository;
+use crate::aggregation::{calc_project_tag_duration, Durations};
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::fetch::{group_by_day, read_range_time_entries, FetchOptions};
+use crate::range::month_range;
+use crate::summary_args::{summarize_range, SummaryArgs};
+use crate::toggl::{ReportsRepository, TogglRepository};
 
 /// 月毎の情報を出力するためのサブコマンド。
 #[derive(Debug, clap::Args)]
-pub struct MonthlyCommand {
+pub struct MonthlyArgs {
     #[clap(
         short = 'm',
         long = "month",
-        help = "Sets a custom month in the format YYYY-MM",
-        parse(try_from_str = parse_month),
+        help = "Sets a custom month in the format YYYY-MM, or the month containing a date such as last month or 2 months ago",
+        allow_hyphen_values = true
     )]
-    month: Option<DateTime<Utc>>,
+    month: Option<DateExpr>,
 
     #[clap(long = "daily", help = "Show summary by day")]
     daily: bool,
+
+    #[clap(flatten)]
+    pub summary: SummaryArgs,
 }
 
-/// `monthly`サブコマンドの処理を行う。
-///
-/// Localタイムゾーンで指定された月のtime entryでタグごとの集計結果を日毎に出力する。
-/// 日付が指定されていない場合は、Localタイムゾーンで現在の月を利用する。
-///
-/// # Arguments
-///
-/// * `monthly` - `monthly`サブコマンドの引数
-///
-/// # Examples
-///
-/// ```
-/// let daily = Daily { date: None };
-/// monthly_command(daily).await.unwrap();
-/// ```
-pub async fn monthly_command(monthly: MonthlyCommand) -> Result<()> {
-    // Localのタイムゾーンで00:00:00から始まる1日とする
-    let date = monthly.month.unwrap_or_else(|| Local::now().to_utc());
-    let local_date = date.with_timezone(&Local);
-    let start_at = local_date
-        .with_day0(0)
-        .context("Failed to set day")?
-        .with_hour(0)
-        .context("Failed to set hour")?
-        .with_minute(0)
-        .context("Failed to set minute")?
-        .with_second(0)
-        .context("Failed to set second")?;
-    let end_at = start_at
-        .with_month(start_at.month() + 1)
-        .context("Failed to set month")?;
-    info!("Start at: {}, End at: {}", start_at, end_at);
-
-    let client = TogglClient::new().context("Failed to new toggl client")?;
-    let time_entries = client
-        .read_time_entries(&start_at.to_utc(), &end_at.to_utc())
-        .await
-        .context("Failed to retrieve time entries")?;
-    info!("Time entries retrieved successfully.");
-
-    if monthly.daily {
-        let daily_time_entries: HashMap<NaiveDate, Vec<TimeEntry>> =
-            time_entries.iter().fold(HashMap::new(), |mut acc, entry| {
-                let start = entry.start.with_timezone(&Local).date_naive();
-                acc.entry(start).or_default().push(entry.clone());
-                acc
-            });
-        let mut sorted_time_entries = daily_time_entries
-            .iter()
-            .map(|(date, entries)| (*date, entries.clone()))
-            .collect::<Vec<_>>();
-        sorted_time_entries.sort_by_key(|(date, _)| *date);
-        sorted_time_entries.iter().try_for_each(|(date, entries)| {
-            println!("## {}", date);
-            let daily_duration = calc_project_tag_duration(entries).with_context(|| {
-                format!(
-                    "Failed to calculate project tag duration for date: {}",
-                    date
-                )
-            })?;
-            show_durations(&daily_duration);
-            Ok(())
-        })?;
-    } else {
-        let project_tag_duration = calc_project_tag_duration(&time_entries)
-            .context("Failed to calculate project tag duration")?;
-        show_durations(&project_tag_duration);
-    }
-
-    Ok(())
+/// `monthly`サブコマンドの集計結果。
+#[derive(Debug, PartialEq)]
+pub enum MonthlySummary {
+    /// 月全体の集計結果
+    Total(Durations),
+    /// 日付順に並べた日毎の集計結果
+    Daily(Vec<(NaiveDate, Durations)>),
 }
 
-/// 月をパースする。
-fn parse_month(s: &str) -> Result<DateTime<Utc>> {
-    let target_date = s.to_string() + "-01";
-    let naive_date = NaiveDate::parse_from_str(&target_date, "%Y-%m-%d")
-        .with_context(|| format!("Failed to parse date: {}", target_date))?;
-    let naive_datetime = naive_date
-        .with_day0(0)
-        .context("Failed to set day")?
-        .and_hms_opt(0, 0, 0)
-        .context("Failed to set hour, minute, and second")?;
-    let datetime = Local
-        .from_local_datetime(&naive_datetime)
-        .single()
-        .context("Failed to convert to DateTime<Local>")?
-        .to_utc();
-
-    Ok(datetime)
+pub struct MonthlyCommand<'a, T: TogglRepository, R: ReportsRepository> {
+    toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    zone: Zone,
 }
 
-/// プロジェクトごと、かつタグごとの集計結果を計算する。
-///
-/// 終了していないtime entryは集計対象外とする。
-fn calc_project_tag_duration(
-    time_entries: &[TimeEntry],
-) -> Result<HashMap<String, HashMap<String, i64>>> {
-    let project_tag_duration: HashMap<String, HashMap<String, i64>> =
-        time_entries.iter().fold(HashMap::new(), |mut acc, entry| {
-            if entry.stop.is_none() {
-                return acc;
-            }
-
-            let key = entry.project.clone().unwrap_or_default();
-            let project_entry = acc.entry(key).or_default();
-            entry.tags.iter().for_each(|tag| {
-                *project_entry.entry(tag.clone()).or_insert(0) += entry.duration;
-            });
-            acc
-        });
+impl<'a, T: TogglRepository, R: ReportsRepository> MonthlyCommand<'a, T, R> {
+    /// 新しい`MonthlyCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを月や日の範囲で切り詰めて集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 月や日の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `monthly`サブコマンドの処理を行う。
+    ///
+    /// 設定したタイムゾーンで指定された月のtime entryを、指定した単位とタグごとに集計する。
+    /// 日付が指定されていない場合は、設定したタイムゾーンで現在の月を利用する。
+    /// 切り詰める場合は、月や日の範囲に含まれる部分のみを集計する。
+    pub async fn run(&self, monthly: MonthlyArgs) -> Result<MonthlySummary> {
+        let date = match monthly.month {
+            Some(month) => month.start_of_day(&self.zone)?,
+            None => now(),
+        };
+        let (start_at, end_at) = month_range(&date, &self.zone)?;
+        info!("Start at: {}, End at: {}", start_at, end_at);
 
-    Ok(project_tag_duration)
+        if !monthly.daily {
+            return summarize_range(
+                self.toggl_client,
+                self.reports_client,
+                &start_at,
+                &end_at,
+                &monthly.summary,
+                self.clip_to_range,
+                &self.zone,
+            )
+            .await
+            .map(MonthlySummary::Total);
+        }
+
+        let options = FetchOptions {
+            source: monthly.summary.source,
+            include_running: monthly.summary.include_running,
+            clip: self.clip_to_range && !monthly.summary.no_clip,
+        };
+        let time_entries = read_range_time_entries(
+            self.toggl_client,
+            self.reports_client,
+            &options,
+            &start_at,
+            &end_at,
+            &self.zone,
+        )
+        .await?;
+        let daily_time_entries = group_by_day(time_entries, options.clip, &self.zone)?;
+        let mut daily_durations: Vec<(NaiveDate, Durations)> = daily_time_entries
+            .into_iter()
+            .map(|(date, entries)| {
+                let durations = calc_project_tag_duration(
+                    &entries,
+                    monthly.summary.group_by,
+                    monthly.summary.tag_mode,
+                );
+                (date, durations)
+            })
+            .collect();
+        daily_durations.sort_by_key(|(date, _)| *date);
+
+        Ok(MonthlySummary::Daily(daily_durations))
+    }
 }
 
-/// project, tagごとの集計結果を表示する。
-///
-/// 表示は時間単位で行う。
-fn show_durations(durations: &HashMap<String, HashMap<String, i64>>) {
-    durations.iter().for_each(|(project, tags)| {
-        println!("- {}", project);
-        tags.iter().for_each(|(tag, duration)| {
-            let duration_hours = *duration as f64 / 3600.0;
-            println!("  - {}: {:.2}", tag, duration_hours);
-        });
-    });
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::MonthlyArgs;
+    use super::MonthlyCommand;
+    use super::MonthlySummary;
+    use crate::aggregation::{GroupBy, GroupDurations, TagMode, CLIP_LOOKBACK_HOURS};
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::source::Source;
+    use crate::summary_args::SummaryArgs;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository, SummaryGroup, SummaryGrouping};
+
+    /// 指定した月の1日から翌月の1日までのタイムエントリーを取得することを確認する。
+    ///
+    /// 切り詰める場合は、月の開始前から続くタイムエントリーを含めるため早めに取得する。
+    #[tokio::test]
+    #[rstest]
+    #[case::january("2024-01", (2024, 1), (2024, 2))]
+    #[case::leap_february("2024-02", (2024, 2), (2024, 3))]
+    #[case::february("2023-02", (2023, 2), (2023, 3))]
+    #[case::november("2024-11", (2024, 11), (2024, 12))]
+    #[case::december("2024-12", (2024, 12), (2025, 1))]
+    async fn test_run_month_range(
+        #[case] month: &str,
+        #[case] start: (i32, u32),
+        #[case] end: (i32, u32),
+        #[values(false, true)] no_clip: bool,
+    ) {
+        let start_at = local_datetime(start.0, start.1, 1, 0);
+        let start_at = if no_clip {
+            start_at
+        } else {
+            start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)
+        };
+        let end_at = local_datetime(end.0, end.1, 1, 0);
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(start_at), predicate::eq(end_at))
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some(month.parse().unwrap()),
+            summary: SummaryArgs {
+                no_clip,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(MonthlySummary::Total(HashMap::new()), result.unwrap());
+    }
+
+    /// 月が指定されていない場合は、現在の月を利用することを確認する。
+    #[tokio::test]
+    async fn test_run_current_month() {
+        mock_datetime::set_mock_time(local_datetime(2024, 5, 15, 12));
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(
+                    local_datetime(2024, 5, 1, 0) - TimeDelta::hours(CLIP_LOOKBACK_HOURS),
+                ),
+                predicate::eq(local_datetime(2024, 6, 1, 0)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let reports = MockReportsRepository::new();
+
+        let result = MonthlyCommand::new(&toggl, &reports)
+            .run(default_args())
+            .await;
+        mock_datetime::clear_mock_time();
+
+        assert!(result.is_ok());
+    }
+
+    /// 月全体をプロジェクトとタグごとに集計することを確認する。
+    ///
+    /// タグのないタイムエントリーは、プロジェクトの合計のみに含まれる。
+    #[tokio::test]
+    async fn test_run_total() {
+        let entries = vec![
+            dummy_entry(
+                1,
+                local_datetime(2024, 1, 1, 9),
+                3600,
+                "project 1",
+                &["tag 1"],
+            ),
+            dummy_entry(
+                2,
+                local_datetime(2024, 1, 2, 9),
+                1800,
+                "project 1",
+                &["tag 1", "tag 2"],
+            ),
+            dummy_entry(3, local_datetime(2024, 1, 31, 23), 600, "project 2", &[]),
+        ];
+        let expected = MonthlySummary::Total(HashMap::from([
+            (
+                "project 1".to_string(),
+                group_durations(5400, &[("tag 1", 5400), ("tag 2", 1800)]),
+            ),
+            ("project 2".to_string(), group_durations(600, &[])),
+        ]));
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// `--daily`を指定した場合に、Localタイムゾーンの開始日ごとに日付順で集計することを確認する。
+    #[tokio::test]
+    async fn test_run_daily() {
+        let entries = vec![
+            dummy_entry(
+                1,
+                local_datetime(2024, 1, 2, 9),
+                3600,
+                "project 1",
+                &["tag 1"],
+            ),
+            dummy_entry(
+                2,
+                local_datetime(2024, 1, 1, 23),
+                1800,
+                "project 1",
+                &["tag 1"],
+            ),
+            dummy_entry(
+                3,
+                local_datetime(2024, 1, 2, 0),
+                600,
+                "project 2",
+                &["tag 2"],
+            ),
+        ];
+        let expected = MonthlySummary::Daily(vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                HashMap::from([(
+                    "project 1".to_string(),
+                    group_durations(1800, &[("tag 1", 1800)]),
+                )]),
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+                HashMap::from([
+                    (
+                        "project 1".to_string(),
+                        group_durations(3600, &[("tag 1", 3600)]),
+                    ),
+                    (
+                        "project 2".to_string(),
+                        group_durations(600, &[("tag 2", 600)]),
+                    ),
+                ]),
+            ),
+        ]);
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            daily: true,
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 月をまたぐタイムエントリーは、月に含まれる部分のみを集計することを確認する。
+    ///
+    /// 切り詰めない場合は、月の中で開始したタイムエントリーの全体を集計する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(true, false, 3600 + 1800)]
+    #[case::no_clip_option(true, true, 3600 + 3600)]
+    #[case::no_clip_config(false, false, 3600 + 3600)]
+    async fn test_run_clip_month(
+        #[case] clip_to_range: bool,
+        #[case] no_clip: bool,
+        #[case] expected_total: i64,
+    ) {
+        let mut entries = vec![dummy_entry(
+            1,
+            local_datetime(2024, 1, 31, 23) + TimeDelta::minutes(30),
+            3600,
+            "project 1",
+            &["tag 1"],
+        )];
+        // 切り詰めない場合は、月の開始前に開始したタイムエントリーは取得されない
+        if !no_clip && clip_to_range {
+            entries.push(dummy_entry(
+                2,
+                local_datetime(2023, 12, 31, 23),
+                2 * 3600,
+                "project 1",
+                &["tag 1"],
+            ));
+        } else {
+            entries.push(dummy_entry(
+                2,
+                local_datetime(2024, 1, 1, 0),
+                3600,
+                "project 1",
+                &["tag 1"],
+            ));
+        }
+        let expected = MonthlySummary::Total(HashMap::from([(
+            "project 1".to_string(),
+            group_durations(expected_total, &[("tag 1", expected_total)]),
+        )]));
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            summary: SummaryArgs {
+                no_clip,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports)
+            .clip_to_range(clip_to_range)
+            .run(args)
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// `--daily`を指定した場合に、日付をまたぐタイムエントリーを日ごとに分割して集計することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, &[(1, 2 * 3600), (2, 2 * 3600)])]
+    #[case::no_clip(true, &[(1, 4 * 3600)])]
+    async fn test_run_daily_clip(#[case] no_clip: bool, #[case] expected_days: &[(u32, i64)]) {
+        let entries = vec![dummy_entry(
+            1,
+            local_datetime(2024, 1, 1, 22),
+            4 * 3600,
+            "project 1",
+            &["tag 1"],
+        )];
+        let expected = MonthlySummary::Daily(
+            expected_days
+                .iter()
+                .map(|(day, duration)| {
+                    (
+                        NaiveDate::from_ymd_opt(2024, 1, *day).unwrap(),
+                        HashMap::from([(
+                            "project 1".to_string(),
+                            group_durations(*duration, &[("tag 1", *duration)]),
+                        )]),
+                    )
+                })
+                .collect(),
+        );
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            daily: true,
+            summary: SummaryArgs {
+                no_clip,
+                ..Default::default()
+            },
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 指定したタイムゾーンの月と日の境界で、ホストのタイムゾーンによらず集計することを確認する。
+    ///
+    /// 東京の2024-02は、UTCで2024-01-31 15:00から2024-02-29 15:00までとなる。
+    #[tokio::test]
+    async fn test_run_daily_time_zone() {
+        // 東京の2024-02-01 23:00から2時間
+        let entries = vec![dummy_entry(
+            1,
+            Utc.with_ymd_and_hms(2024, 2, 1, 14, 0, 0).unwrap(),
+            2 * 3600,
+            "project 1",
+            &[],
+        )];
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 31, 15, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 2, 29, 15, 0, 0).unwrap();
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(end_at),
+            )
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-02".parse().unwrap()),
+            daily: true,
+            ..default_args()
+        };
+        let expected = MonthlySummary::Daily(
+            [1, 2]
+                .iter()
+                .map(|day| {
+                    (
+                        NaiveDate::from_ymd_opt(2024, 2, *day).unwrap(),
+                        HashMap::from([("project 1".to_string(), group_durations(3600, &[]))]),
+                    )
+                })
+                .collect(),
+        );
+
+        let result = MonthlyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(chrono_tz::Asia::Tokyo))
+            .run(args)
+            .await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// `--include-running`を指定した場合に、実行中のタイムエントリーを現在までの経過時間で集計することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::include(true, Some(1800))]
+    #[case::exclude(false, None)]
+    async fn test_run_include_running(
+        #[case] include_running: bool,
+        #[case] expected_running: Option<i64>,
+    ) {
+        let start = local_datetime(2024, 1, 15, 9);
+        mock_datetime::set_mock_time(start + TimeDelta::minutes(30));
+        let entries = vec![
+            dummy_entry(
+                1,
+                local_datetime(2024, 1, 1, 9),
+                3600,
+                "project 1",
+                &["tag 1"],
+            ),
+            TimeEntry {
+                stop: None,
+                duration: -start.timestamp(),
+                ..dummy_entry(2, start, 0, "project 2", &["tag 1"])
+            },
+        ];
+        let mut expected = HashMap::from([(
+            "project 1".to_string(),
+            group_durations(3600, &[("tag 1", 3600)]),
+        )]);
+        if let Some(duration) = expected_running {
+            expected.insert(
+                "project 2".to_string(),
+                GroupDurations {
+                    in_progress: true,
+                    ..group_durations(duration, &[("tag 1", duration)])
+                },
+            );
+        }
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            summary: SummaryArgs {
+                include_running,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        assert_eq!(MonthlySummary::Total(expected), result.unwrap());
+    }
+
+    /// `--tag-mode`に従ってタグへ時間を割り当て、プロジェクトの合計は記録時間となることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::per_tag(TagMode::PerTag, &[("tag 1", 1801), ("tag 2", 1801)])]
+    #[case::untagged(
+        TagMode::Untagged,
+        &[("tag 1", 1801), ("tag 2", 1801), ("(no tag)", 600)],
+    )]
+    #[case::split(
+        TagMode::Split,
+        &[("tag 1", 901), ("tag 2", 900), ("(no tag)", 600)],
+    )]
+    async fn test_run_tag_mode(#[case] tag_mode: TagMode, #[case] expected_tags: &[(&str, i64)]) {
+        let entries = vec![
+            dummy_entry(
+                1,
+                local_datetime(2024, 1, 1, 9),
+                1801,
+                "project 1",
+                &["tag 1", "tag 2"],
+            ),
+            dummy_entry(2, local_datetime(2024, 1, 2, 9), 600, "project 1", &[]),
+        ];
+        let expected = MonthlySummary::Total(HashMap::from([(
+            "project 1".to_string(),
+            group_durations(2401, expected_tags),
+        )]));
+        let toggl = mock_toggl(entries);
+        let reports = MockReportsRepository::new();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            summary: SummaryArgs {
+                tag_mode,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// Reports APIを指定した場合に、サマリーレポートの集計結果を利用することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::per_tag(TagMode::PerTag, &[("tag 1", 7200)])]
+    #[case::untagged(TagMode::Untagged, &[("tag 1", 7200), ("(no tag)", 1800)])]
+    async fn test_run_reports_summary(
+        #[case] tag_mode: TagMode,
+        #[case] expected_tags: &[(&str, i64)],
+    ) {
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entries().never();
+        toggl
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10]));
+        let mut reports = MockReportsRepository::new();
+        reports
+            .expect_read_summary()
+            .with(
+                predicate::eq(10),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()),
+                predicate::eq(SummaryGrouping::Clients),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(vec![SummaryGroup {
+                    name: "client 1".to_string(),
+                    seconds: 9000,
+                    untagged: 1800,
+                    tags: HashMap::from([("tag 1".to_string(), 7200)]),
+                }])
+            });
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            summary: SummaryArgs {
+                group_by: GroupBy::Client,
+                tag_mode,
+                source: Source::Reports,
+                no_clip: true,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+        let expected = MonthlySummary::Total(HashMap::from([(
+            "client 1".to_string(),
+            group_durations(9000, expected_tags),
+        )]));
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// Reports APIを指定してタグごとに等分する場合は、詳細レポートのタイムエントリーから集計することを確認する。
+    #[tokio::test]
+    async fn test_run_reports_split() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10]));
+        let mut reports = MockReportsRepository::new();
+        reports.expect_read_summary().never();
+        reports
+            .expect_read_detailed_time_entries()
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(vec![dummy_entry(
+                    1,
+                    local_datetime(2024, 1, 1, 9),
+                    3600,
+                    "project 1",
+                    &["tag 1", "tag 2"],
+                )])
+            });
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            summary: SummaryArgs {
+                tag_mode: TagMode::Split,
+                source: Source::Reports,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+        let expected = MonthlySummary::Total(HashMap::from([(
+            "project 1".to_string(),
+            group_durations(3600, &[("tag 1", 1800), ("tag 2", 1800)]),
+        )]));
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// Reports APIは実行中のタイムエントリーを返さないため、日ごとの集計でも`--include-running`と同時に指定するとエラーになることを確認する。
+    #[tokio::test]
+    async fn test_run_daily_reports_include_running() {
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entries().never();
+        toggl.expect_read_workspace_ids().never();
+        let mut reports = MockReportsRepository::new();
+        reports.expect_read_detailed_time_entries().never();
+        let args = MonthlyArgs {
+            month: Some("2024-01".parse().unwrap()),
+            daily: true,
+            summary: SummaryArgs {
+                include_running: true,
+                source: Source::Reports,
+                ..Default::default()
+            },
+        };
+
+        let result = MonthlyCommand::new(&toggl, &reports).run(args).await;
+
+        assert!(format!("{:#}", result.unwrap_err())
+            .contains("Running time entries cannot be counted with --source reports"));
+    }
+
+    /// テスト用にデフォルトの引数を作成する。
+    fn default_args() -> MonthlyArgs {
+        MonthlyArgs {
+            month: None,
+            daily: false,
+            summary: SummaryArgs::default(),
+        }
+    }
+
+    /// テスト用に集計結果を作成する。
+    fn group_durations(total: i64, tags: &[(&str, i64)]) -> GroupDurations {
+        GroupDurations {
+            total,
+            tags: tags
+                .iter()
+                .map(|(tag, duration)| (tag.to_string(), *duration))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// テスト用に指定したタイムエントリーを返すリポジトリを作成する。
+    fn mock_toggl(entries: Vec<TimeEntry>) -> MockTogglRepository {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        toggl
+    }
+
+    /// テスト用にLocalタイムゾーンの日時を作成する。
+    fn local_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(
+        id: i64,
+        start: DateTime<Utc>,
+        duration: i64,
+        project: &str,
+        tags: &[&str],
+    ) -> TimeEntry {
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + chrono::Duration::seconds(duration)),
+            duration,
+            description: format!("entry{}", id),
+            project: Some(project.to_string()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        }
+    }
 }
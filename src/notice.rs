@@ -0,0 +1,35 @@
+#[cfg(not(test))]
+use log::info;
+
+/// 集計結果に影響する注意を、ログレベルによらず標準エラー出力に表示する。
+///
+/// 既定のログレベルでは警告のログが表示されないため、利用者が気付く必要がある注意はこちらで表示する。
+/// 後から確認できるよう、ログファイルにも出力する。
+#[cfg(not(test))]
+pub fn notice(message: &str) {
+    info!("{}", message);
+    eprintln!("Warning: {}", message);
+}
+
+#[cfg(test)]
+pub use mock_notice::notice;
+
+/// テスト時に表示した注意を記録する。
+#[cfg(test)]
+pub mod mock_notice {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static NOTICES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// 表示する注意を記録する。
+    pub fn notice(message: &str) {
+        NOTICES.with(|cell| cell.borrow_mut().push(message.to_string()));
+    }
+
+    /// 記録した注意を取り出し、記録をクリアする。
+    pub fn take_notices() -> Vec<String> {
+        NOTICES.with(|cell| cell.take())
+    }
+}
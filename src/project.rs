@@ -0,0 +1,100 @@
+use anyhow::{bail, Result};
+
+/// Togglのプロジェクト。
+#[derive(Clone, PartialEq, Debug)]
+pub struct Project {
+    pub id: i64,
+    pub workspace_id: i64,
+    pub name: String,
+}
+
+/// プロジェクト名から一意にプロジェクトを特定する。
+///
+/// 該当するプロジェクトが存在しない場合、または複数存在する場合はエラーを返す。
+///
+/// # Arguments
+///
+/// * `projects` - 検索対象のプロジェクト一覧
+/// * `name` - 検索するプロジェクト名
+pub fn find_project_by_name<'a>(projects: &'a [Project], name: &str) -> Result<&'a Project> {
+    let matched: Vec<&Project> = projects
+        .iter()
+        .filter(|project| project.name == name)
+        .collect();
+
+    match matched.as_slice() {
+        [] => bail!("Unknown project: {}", name),
+        [project] => Ok(project),
+        _ => bail!(
+            "Ambiguous project name: {} (found in workspaces: {})",
+            name,
+            matched
+                .iter()
+                .map(|project| project.workspace_id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::find_project_by_name;
+    use super::Project;
+
+    /// プロジェクト名から一意に特定できることを確認する。
+    #[rstest]
+    #[case::first("project 1", 1)]
+    #[case::second("project 2", 2)]
+    fn test_find_project_by_name(#[case] name: &str, #[case] expected_id: i64) {
+        let projects = dummy_projects();
+
+        let result = find_project_by_name(&projects, name);
+
+        assert_eq!(expected_id, result.unwrap().id);
+    }
+
+    /// 該当するプロジェクトがない、または複数ある場合にエラーとなることを確認する。
+    #[rstest]
+    #[case::unknown("project 4")]
+    #[case::ambiguous("project 3")]
+    #[case::case_sensitive("Project 1")]
+    #[case::empty("")]
+    fn test_find_project_by_name_error(#[case] name: &str) {
+        let projects = dummy_projects();
+
+        let result = find_project_by_name(&projects, name);
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用のプロジェクト一覧を作成する。
+    ///
+    /// `project 3`は異なるワークスペースに同名で存在する。
+    fn dummy_projects() -> Vec<Project> {
+        vec![
+            Project {
+                id: 1,
+                workspace_id: 10,
+                name: "project 1".to_string(),
+            },
+            Project {
+                id: 2,
+                workspace_id: 10,
+                name: "project 2".to_string(),
+            },
+            Project {
+                id: 3,
+                workspace_id: 10,
+                name: "project 3".to_string(),
+            },
+            Project {
+                id: 4,
+                workspace_id: 20,
+                name: "project 3".to_string(),
+            },
+        ]
+    }
+}
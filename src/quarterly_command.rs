@@ -0,0 +1,220 @@
+use anyhow::{Context, Result};
+
+use crate::aggregation::MonthlyDurations;
+use crate::datetime::{now, Zone};
+use crate::range::{fiscal_quarter_of, DateRange};
+use crate::summary_args::{summarize_by_month, SummaryArgs};
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 四半期毎の情報を出力するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct QuarterlyArgs {
+    #[clap(
+        short = 'q',
+        long = "quarter",
+        help = "Sets a custom quarter of the fiscal year in the format YYYY-Qn",
+        parse(try_from_str = parse_quarter)
+    )]
+    quarter: Option<(i32, u32)>,
+
+    #[clap(flatten)]
+    pub summary: SummaryArgs,
+}
+
+pub struct QuarterlyCommand<'a, T: TogglRepository, R: ReportsRepository> {
+    toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    fiscal_year_start_month: u32,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository, R: ReportsRepository> QuarterlyCommand<'a, T, R> {
+    /// 新しい`QuarterlyCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            fiscal_year_start_month: 1,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを月の範囲で切り詰めて集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 年度が始まる月(1から12)を設定する。四半期は年度の最初の月から数える。
+    pub fn fiscal_year_start_month(mut self, fiscal_year_start_month: u32) -> Self {
+        self.fiscal_year_start_month = fiscal_year_start_month;
+        self
+    }
+
+    /// 月の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `quarterly`サブコマンドの処理を行う。
+    ///
+    /// 設定したタイムゾーンで指定された四半期のtime entryを、月ごとに指定した単位とタグごとに集計する。
+    /// 四半期が指定されていない場合は、設定したタイムゾーンで現在の日付を含む四半期を利用する。
+    pub async fn run(&self, quarterly: QuarterlyArgs) -> Result<MonthlyDurations> {
+        let (year, quarter) = match quarterly.quarter {
+            Some(quarter) => quarter,
+            None => {
+                let today = now().with_timezone(&self.zone).date_naive();
+                fiscal_quarter_of(&today, self.fiscal_year_start_month)?
+            }
+        };
+        let range = DateRange::fiscal_quarter(year, quarter, self.fiscal_year_start_month)?;
+
+        summarize_by_month(
+            self.toggl_client,
+            self.reports_client,
+            &range,
+            &quarterly.summary,
+            self.clip_to_range,
+            &self.zone,
+        )
+        .await
+    }
+}
+
+/// `YYYY-Qn`形式の四半期をパースし、年度と四半期を返す。
+fn parse_quarter(s: &str) -> Result<(i32, u32)> {
+    let (year, quarter) = s
+        .split_once("-Q")
+        .with_context(|| format!("Failed to parse quarter: {}", s))?;
+    let year: i32 = year
+        .parse()
+        .with_context(|| format!("Failed to parse year of quarter: {}", s))?;
+    let quarter: u32 = quarter
+        .parse()
+        .with_context(|| format!("Failed to parse quarter number: {}", s))?;
+    anyhow::ensure!((1..=4).contains(&quarter), "Invalid quarter: {}", s);
+
+    Ok((year, quarter))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+    use clap::Parser;
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::parse_quarter;
+    use super::QuarterlyArgs;
+    use super::QuarterlyCommand;
+    use crate::aggregation::CLIP_LOOKBACK_HOURS;
+    use crate::datetime::mock_datetime;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// テスト用にサブコマンドの引数だけをパースするためのパーサー。
+    #[derive(Debug, Parser)]
+    struct TestParser {
+        #[clap(flatten)]
+        quarterly: QuarterlyArgs,
+    }
+
+    /// `YYYY-Qn`形式の四半期をパースできることを確認する。
+    #[rstest]
+    #[case::first("2024-Q1", Some((2024, 1)))]
+    #[case::last("2024-Q4", Some((2024, 4)))]
+    #[case::quarter_0("2024-Q0", None)]
+    #[case::quarter_5("2024-Q5", None)]
+    #[case::lowercase("2024-q3", None)]
+    #[case::no_quarter("2024", None)]
+    #[case::invalid_year("Y-Q1", None)]
+    fn test_parse_quarter(#[case] input: &str, #[case] expected: Option<(i32, u32)>) {
+        let result = parse_quarter(input);
+
+        assert_eq!(expected, result.ok());
+    }
+
+    /// 指定した四半期や現在の日付を含む四半期の月ごとに、タイムエントリーを取得することを確認する。
+    ///
+    /// 現在は2024-05-15とし、四半期は年度の最初の月から数える。
+    #[tokio::test]
+    #[rstest]
+    #[case::quarter(&["--quarter", "2024-Q3"], 1, (2024, 7))]
+    #[case::fiscal_quarter(&["--quarter", "2024-Q3"], 4, (2024, 10))]
+    #[case::fiscal_quarter_next_year(&["--quarter", "2023-Q4"], 4, (2024, 1))]
+    #[case::current_quarter(&[], 1, (2024, 4))]
+    #[case::current_fiscal_quarter(&[], 3, (2024, 3))]
+    async fn test_run_months(
+        #[case] args: &[&str],
+        #[case] start_month: u32,
+        #[case] expected_first_month: (i32, u32),
+    ) {
+        mock_datetime::set_mock_time(local_datetime(2024, 5, 15, 12));
+        let first_month =
+            NaiveDate::from_ymd_opt(expected_first_month.0, expected_first_month.1, 1).unwrap();
+        let expected_months: Vec<NaiveDate> = (0..3)
+            .map(|index| first_month + chrono::Months::new(index))
+            .collect();
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_history_start().returning(|| None);
+        for month in &expected_months {
+            let start_at = local_date(month);
+            if start_at > local_datetime(2024, 5, 15, 12) {
+                continue;
+            }
+            let end_at = local_date(&(*month + chrono::Months::new(1)));
+            toggl
+                .expect_read_time_entries()
+                .with(
+                    predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                    predicate::eq(end_at),
+                )
+                .times(1)
+                .returning(|_, _| Ok(vec![]));
+        }
+        let args =
+            TestParser::try_parse_from(std::iter::once("quarterly").chain(args.iter().copied()))
+                .unwrap()
+                .quarterly;
+        let reports = MockReportsRepository::new();
+
+        let result = QuarterlyCommand::new(&toggl, &reports)
+            .fiscal_year_start_month(start_month)
+            .run(args)
+            .await;
+        mock_datetime::clear_mock_time();
+
+        let months: Vec<NaiveDate> = result
+            .unwrap()
+            .months
+            .iter()
+            .map(|(month, _)| *month)
+            .collect();
+        assert_eq!(expected_months, months);
+    }
+
+    /// テスト用にLocalタイムゾーンの日時を作成する。
+    fn local_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にLocalタイムゾーンの日付の始まりの日時を作成する。
+    fn local_date(date: &NaiveDate) -> DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            .unwrap()
+            .to_utc()
+    }
+}
@@ -0,0 +1,534 @@
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, TimeZone, Utc};
+
+use crate::datetime::start_of_day;
+
+/// 1週間の日数。
+pub const DAYS_IN_WEEK: u64 = 7;
+
+/// 四半期の月数。
+const MONTHS_IN_QUARTER: u32 = 3;
+
+/// 1年の月数。
+pub const MONTHS_IN_YEAR: u32 = 12;
+
+/// 1年の四半期の数。
+const QUARTERS_IN_YEAR: u32 = 4;
+
+/// 週の始まりの曜日。
+#[derive(Debug, Clone, Copy, PartialEq, clap::ArgEnum)]
+pub enum WeekStart {
+    Mon,
+    Sun,
+}
+
+impl WeekStart {
+    /// 指定した日付を含む週の、最初の日付を返す。
+    pub fn first_day_of_week(&self, date: &NaiveDate) -> NaiveDate {
+        let days_from_start = match self {
+            WeekStart::Mon => date.weekday().num_days_from_monday(),
+            WeekStart::Sun => date.weekday().num_days_from_sunday(),
+        };
+
+        *date - Days::new(days_from_start.into())
+    }
+}
+
+/// 集計する日付の範囲。
+///
+/// 開始日を含み、終了日を含まない。日時への変換はタイムゾーンの日の始まりを境界とする。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+    /// 範囲の開始日。
+    pub start: NaiveDate,
+    /// 範囲の終了日(この日を含まない)。
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// 最初の日と最後の日(この日を含む)から範囲を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `first_day` - 範囲の最初の日
+    /// * `last_day` - 範囲の最後の日(この日を含む)
+    pub fn inclusive(first_day: &NaiveDate, last_day: &NaiveDate) -> Result<Self> {
+        ensure!(
+            first_day <= last_day,
+            "The last day {} is before the first day {}",
+            last_day,
+            first_day
+        );
+
+        Ok(Self {
+            start: *first_day,
+            end: next_day(last_day)?,
+        })
+    }
+
+    /// 指定した日付の1日の範囲を返す。
+    pub fn day(date: &NaiveDate) -> Result<Self> {
+        Self::inclusive(date, date)
+    }
+
+    /// 指定した日付を含む1週間の範囲を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - 範囲に含める日付
+    /// * `week_start` - 週の始まりの曜日
+    pub fn week(date: &NaiveDate, week_start: WeekStart) -> Result<Self> {
+        let start = week_start.first_day_of_week(date);
+        let end = start
+            .checked_add_days(Days::new(DAYS_IN_WEEK))
+            .with_context(|| format!("Failed to get the next week of {}", start))?;
+
+        Ok(Self { start, end })
+    }
+
+    /// 指定した日付を含む、1日から翌月の1日までの範囲を返す。
+    ///
+    /// 12月の場合は、翌年の1月1日までとする。
+    pub fn month(date: &NaiveDate) -> Result<Self> {
+        Self::months_from(&first_day_of_month(date)?, 1)
+    }
+
+    /// 指定した日付を含む四半期(1月、4月、7月、10月から始まる3ヶ月)の範囲を返す。
+    pub fn quarter(date: &NaiveDate) -> Result<Self> {
+        let (year, quarter) = fiscal_quarter_of(date, 1)?;
+
+        Self::fiscal_quarter(year, quarter, 1)
+    }
+
+    /// 年度の範囲を返す。
+    ///
+    /// 年度は開始する月の年で表す。例えば4月始まりの2024年度は、2024年4月1日から2025年4月1日までとなる。
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - 年度
+    /// * `start_month` - 年度が始まる月(1から12)
+    pub fn fiscal_year(year: i32, start_month: u32) -> Result<Self> {
+        ensure!(
+            (1..=MONTHS_IN_YEAR).contains(&start_month),
+            "The start month of the fiscal year must be between 1 and 12: {}",
+            start_month
+        );
+        let start = NaiveDate::from_ymd_opt(year, start_month, 1)
+            .with_context(|| format!("Failed to get the fiscal year {}", year))?;
+
+        Self::months_from(&start, MONTHS_IN_YEAR)
+    }
+
+    /// 年度の四半期の範囲を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `year` - 年度
+    /// * `quarter` - 年度の最初から数えた四半期(1から4)
+    /// * `start_month` - 年度が始まる月(1から12)
+    pub fn fiscal_quarter(year: i32, quarter: u32, start_month: u32) -> Result<Self> {
+        ensure!(
+            (1..=QUARTERS_IN_YEAR).contains(&quarter),
+            "The quarter must be between 1 and 4: {}",
+            quarter
+        );
+        let start = Self::fiscal_year(year, start_month)?
+            .start
+            .checked_add_months(Months::new((quarter - 1) * MONTHS_IN_QUARTER))
+            .with_context(|| format!("Failed to get the quarter {} of {}", quarter, year))?;
+
+        Self::months_from(&start, MONTHS_IN_QUARTER)
+    }
+
+    /// 指定した日付を含む月の、前月の範囲を返す。
+    pub fn previous_month(date: &NaiveDate) -> Result<Self> {
+        let start = first_day_of_month(date)?
+            .checked_sub_months(Months::new(1))
+            .with_context(|| format!("Failed to get the previous month of {}", date))?;
+
+        Self::months_from(&start, 1)
+    }
+
+    /// 指定した日付までの、指定した日数の範囲を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `last_day` - 範囲の最後の日(この日を含む)
+    /// * `days` - 範囲の日数
+    pub fn last_days(last_day: &NaiveDate, days: u64) -> Result<Self> {
+        ensure!(days > 0, "The number of days must be positive");
+        let first_day = last_day
+            .checked_sub_days(Days::new(days - 1))
+            .with_context(|| format!("Failed to get {} days before {}", days, last_day))?;
+
+        Self::inclusive(&first_day, last_day)
+    }
+
+    /// 指定した日付から、指定した月数の範囲を返す。
+    fn months_from(start: &NaiveDate, months: u32) -> Result<Self> {
+        let end = start
+            .checked_add_months(Months::new(months))
+            .with_context(|| format!("Failed to get {} months after {}", months, start))?;
+
+        Ok(Self { start: *start, end })
+    }
+
+    /// 範囲を月の境界で分割し、開始日から順に返す。
+    ///
+    /// 開始日や終了日が月の途中の場合は、最初や最後の範囲は1ヶ月より短くなる。
+    pub fn months(&self) -> Result<Vec<Self>> {
+        let mut months = vec![];
+        let mut start = self.start;
+        while start < self.end {
+            let end = Self::month(&start)?.end.min(self.end);
+            months.push(Self { start, end });
+            start = end;
+        }
+
+        Ok(months)
+    }
+
+    /// 範囲に含まれる日付を、開始日から順に返す。
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.start
+            .iter_days()
+            .take_while(|date| *date < self.end)
+            .collect()
+    }
+
+    /// 指定したタイムゾーンで、開始日の始まりから終了日の始まりまでの日時の範囲を返す。
+    pub fn to_utc_range<Tz: TimeZone>(self, tz: &Tz) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+        Ok((start_of_day(&self.start, tz)?, start_of_day(&self.end, tz)?))
+    }
+}
+
+/// 指定された日時を含む、指定したタイムゾーンで00:00:00から始まる1日の範囲を返す。
+///
+/// # Arguments
+/// * `date` - 範囲に含める日時
+/// * `tz` - 日の境界とするタイムゾーン
+pub fn day_range<Tz: TimeZone>(
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    DateRange::day(&date.with_timezone(tz).date_naive())?.to_utc_range(tz)
+}
+
+/// 指定された日時を含む、指定したタイムゾーンの1日00:00:00から始まる1ヶ月の範囲を返す。
+///
+/// # Arguments
+/// * `date` - 範囲に含める日時
+/// * `tz` - 月の境界とするタイムゾーン
+pub fn month_range<Tz: TimeZone>(
+    date: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    DateRange::month(&date.with_timezone(tz).date_naive())?.to_utc_range(tz)
+}
+
+/// 指定した日付を含む年度を返す。
+///
+/// # Arguments
+///
+/// * `date` - 年度に含める日付
+/// * `start_month` - 年度が始まる月(1から12)
+pub fn fiscal_year_of(date: &NaiveDate, start_month: u32) -> i32 {
+    if date.month() < start_month {
+        date.year() - 1
+    } else {
+        date.year()
+    }
+}
+
+/// 指定した日付を含む年度と、年度の最初から数えた四半期を返す。
+///
+/// # Arguments
+///
+/// * `date` - 四半期に含める日付
+/// * `start_month` - 年度が始まる月(1から12)
+pub fn fiscal_quarter_of(date: &NaiveDate, start_month: u32) -> Result<(i32, u32)> {
+    ensure!(
+        (1..=MONTHS_IN_YEAR).contains(&start_month),
+        "The start month of the fiscal year must be between 1 and 12: {}",
+        start_month
+    );
+    let months_from_start = (date.month() + MONTHS_IN_YEAR - start_month) % MONTHS_IN_YEAR;
+
+    Ok((
+        fiscal_year_of(date, start_month),
+        months_from_start / MONTHS_IN_QUARTER + 1,
+    ))
+}
+
+/// 指定した日付の翌日を返す。
+fn next_day(date: &NaiveDate) -> Result<NaiveDate> {
+    date.succ_opt()
+        .with_context(|| format!("Failed to get the next day of {}", date))
+}
+
+/// 指定した日付を含む月の1日を返す。
+fn first_day_of_month(date: &NaiveDate) -> Result<NaiveDate> {
+    date.with_day(1)
+        .with_context(|| format!("Failed to get the first day of the month of {}", date))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chrono::{DateTime, Datelike, NaiveDate, TimeZone};
+    use rstest::rstest;
+
+    use super::fiscal_quarter_of;
+    use super::fiscal_year_of;
+    use super::month_range;
+    use super::DateRange;
+    use super::WeekStart;
+
+    /// 全ての月で、複数のタイムゾーンの1日の始まりから翌月の1日の始まりまでの範囲となることを確認する。
+    ///
+    /// 月の途中に夏時間の切り替えがあるタイムゾーンや、UTCとの日付がずれるタイムゾーンを含める。
+    #[rstest]
+    fn test_month(
+        #[values(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12)] month: u32,
+        #[values(
+            chrono_tz::UTC,
+            chrono_tz::Asia::Tokyo,
+            chrono_tz::America::New_York,
+            chrono_tz::Europe::London,
+            chrono_tz::Australia::Sydney,
+            chrono_tz::Pacific::Kiritimati
+        )]
+        tz: chrono_tz::Tz,
+    ) {
+        let (next_year, next_month) = if month == 12 {
+            (2025, 1)
+        } else {
+            (2024, month + 1)
+        };
+        let expected_start = tz
+            .with_ymd_and_hms(2024, month, 1, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let expected_end = tz
+            .with_ymd_and_hms(next_year, next_month, 1, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+
+        // 月の最初、途中、最後の日時のいずれからも同じ範囲となる
+        for date in [
+            expected_start,
+            tz.with_ymd_and_hms(2024, month, 15, 12, 0, 0)
+                .unwrap()
+                .to_utc(),
+            expected_end - chrono::Duration::seconds(1),
+        ] {
+            let result = month_range(&date, &tz);
+
+            assert_eq!((expected_start, expected_end), result.unwrap());
+        }
+    }
+
+    /// 年をまたぐ12月の範囲が、夏時間を含めて正しく計算されることを確認する。
+    #[rstest]
+    #[case::tokyo(
+        chrono_tz::Asia::Tokyo,
+        "2023-12-31T15:00:00+00:00",
+        "2024-01-31T15:00:00+00:00"
+    )]
+    #[case::sydney(
+        chrono_tz::Australia::Sydney,
+        "2023-12-31T13:00:00+00:00",
+        "2024-01-31T13:00:00+00:00"
+    )]
+    #[case::new_york(
+        chrono_tz::America::New_York,
+        "2024-12-01T05:00:00+00:00",
+        "2025-01-01T05:00:00+00:00"
+    )]
+    fn test_month_year_boundary(
+        #[case] tz: chrono_tz::Tz,
+        #[case] date: &str,
+        #[case] expected_end: &str,
+    ) {
+        let date = DateTime::parse_from_rfc3339(date).unwrap().to_utc();
+
+        let (start_at, end_at) = month_range(&date, &tz).unwrap();
+
+        assert_eq!(1, start_at.with_timezone(&tz).day());
+        assert_eq!(expected_end, end_at.to_rfc3339());
+    }
+
+    /// 週の始まりの曜日に従って、日付を含む週の最初の日付を返すことを確認する。
+    #[rstest]
+    #[case::monday_from_monday(WeekStart::Mon, (2024, 1, 29), (2024, 1, 29))]
+    #[case::monday_from_sunday(WeekStart::Mon, (2024, 2, 4), (2024, 1, 29))]
+    #[case::monday_across_year(WeekStart::Mon, (2025, 1, 1), (2024, 12, 30))]
+    #[case::sunday_from_sunday(WeekStart::Sun, (2024, 2, 4), (2024, 2, 4))]
+    #[case::sunday_from_saturday(WeekStart::Sun, (2024, 2, 3), (2024, 1, 28))]
+    #[case::sunday_from_monday(WeekStart::Sun, (2024, 1, 29), (2024, 1, 28))]
+    fn test_first_day_of_week(
+        #[case] week_start: WeekStart,
+        #[case] date: (i32, u32, u32),
+        #[case] expected: (i32, u32, u32),
+    ) {
+        let date = NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap();
+
+        let result = week_start.first_day_of_week(&date);
+
+        assert_eq!(
+            NaiveDate::from_ymd_opt(expected.0, expected.1, expected.2).unwrap(),
+            result
+        );
+    }
+
+    /// 最初の日と最後の日から、最後の日を含む範囲を作成できることを確認する。
+    #[rstest]
+    #[case::days("2024-01-15", "2024-02-03", Some(("2024-01-15", "2024-02-04")))]
+    #[case::same_day("2024-01-15", "2024-01-15", Some(("2024-01-15", "2024-01-16")))]
+    #[case::year_end("2024-12-01", "2024-12-31", Some(("2024-12-01", "2025-01-01")))]
+    #[case::reversed("2024-02-03", "2024-01-15", None)]
+    fn test_inclusive(
+        #[case] first_day: &str,
+        #[case] last_day: &str,
+        #[case] expected: Option<(&str, &str)>,
+    ) {
+        let result = DateRange::inclusive(&date(first_day), &date(last_day));
+
+        assert_eq!(expected.map(range), result.ok());
+    }
+
+    /// 日付を含む週、四半期、前月の範囲を作成できることを確認する。
+    #[rstest]
+    #[case::week_monday(DateRange::week(&date("2024-02-04"), WeekStart::Mon), ("2024-01-29", "2024-02-05"))]
+    #[case::week_sunday(DateRange::week(&date("2024-02-04"), WeekStart::Sun), ("2024-02-04", "2024-02-11"))]
+    #[case::quarter_first(DateRange::quarter(&date("2024-01-01")), ("2024-01-01", "2024-04-01"))]
+    #[case::quarter_middle(DateRange::quarter(&date("2024-05-15")), ("2024-04-01", "2024-07-01"))]
+    #[case::quarter_last(DateRange::quarter(&date("2024-12-31")), ("2024-10-01", "2025-01-01"))]
+    #[case::previous_month(DateRange::previous_month(&date("2024-03-31")), ("2024-02-01", "2024-03-01"))]
+    #[case::previous_month_january(DateRange::previous_month(&date("2024-01-15")), ("2023-12-01", "2024-01-01"))]
+    #[case::last_days(DateRange::last_days(&date("2024-03-01"), 7), ("2024-02-24", "2024-03-02"))]
+    #[case::last_day(DateRange::last_days(&date("2024-03-01"), 1), ("2024-03-01", "2024-03-02"))]
+    #[case::fiscal_year(DateRange::fiscal_year(2024, 1), ("2024-01-01", "2025-01-01"))]
+    #[case::fiscal_year_april(DateRange::fiscal_year(2024, 4), ("2024-04-01", "2025-04-01"))]
+    #[case::fiscal_quarter(DateRange::fiscal_quarter(2024, 3, 1), ("2024-07-01", "2024-10-01"))]
+    #[case::fiscal_quarter_april(DateRange::fiscal_quarter(2024, 3, 4), ("2024-10-01", "2025-01-01"))]
+    #[case::fiscal_quarter_next_year(DateRange::fiscal_quarter(2024, 4, 4), ("2025-01-01", "2025-04-01"))]
+    #[case::fiscal_quarter_december(DateRange::fiscal_quarter(2024, 1, 12), ("2024-12-01", "2025-03-01"))]
+    fn test_period(#[case] result: Result<DateRange>, #[case] expected: (&str, &str)) {
+        assert_eq!(range(expected), result.unwrap());
+    }
+
+    /// 年度の開始月や四半期が範囲外の場合はエラーとなることを確認する。
+    #[rstest]
+    #[case::start_month_zero(DateRange::fiscal_year(2024, 0))]
+    #[case::start_month_13(DateRange::fiscal_year(2024, 13))]
+    #[case::quarter_zero(DateRange::fiscal_quarter(2024, 0, 1))]
+    #[case::quarter_5(DateRange::fiscal_quarter(2024, 5, 1))]
+    #[case::quarter_start_month_13(DateRange::fiscal_quarter(2024, 1, 13))]
+    fn test_fiscal_period_error(#[case] result: Result<DateRange>) {
+        assert!(result.is_err());
+    }
+
+    /// 日付を含む年度と四半期を、年度の開始月をもとに返すことを確認する。
+    #[rstest]
+    #[case::calendar_first("2024-01-01", 1, (2024, 1))]
+    #[case::calendar_last("2024-12-31", 1, (2024, 4))]
+    #[case::april_before_start("2024-03-31", 4, (2023, 4))]
+    #[case::april_start("2024-04-01", 4, (2024, 1))]
+    #[case::april_third_quarter("2024-11-15", 4, (2024, 3))]
+    #[case::april_next_year("2025-01-15", 4, (2024, 4))]
+    #[case::december_start("2024-12-01", 12, (2024, 1))]
+    #[case::december_last("2024-11-30", 12, (2023, 4))]
+    fn test_fiscal_quarter_of(
+        #[case] day: &str,
+        #[case] start_month: u32,
+        #[case] expected: (i32, u32),
+    ) {
+        let result = fiscal_quarter_of(&date(day), start_month);
+
+        assert_eq!(expected, result.unwrap());
+        assert_eq!(expected.0, fiscal_year_of(&date(day), start_month));
+    }
+
+    /// 範囲を月の境界で分割し、月の途中の開始日や終了日はそのまま残すことを確認する。
+    #[rstest]
+    #[case::year(
+        ("2024-11-01", "2025-02-01"),
+        vec![("2024-11-01", "2024-12-01"), ("2024-12-01", "2025-01-01"), ("2025-01-01", "2025-02-01")]
+    )]
+    #[case::partial(
+        ("2024-01-15", "2024-03-10"),
+        vec![("2024-01-15", "2024-02-01"), ("2024-02-01", "2024-03-01"), ("2024-03-01", "2024-03-10")]
+    )]
+    #[case::within_month(("2024-01-15", "2024-01-20"), vec![("2024-01-15", "2024-01-20")])]
+    fn test_months(#[case] input: (&str, &str), #[case] expected: Vec<(&str, &str)>) {
+        let result = range(input).months();
+
+        let expected: Vec<DateRange> = expected.into_iter().map(range).collect();
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 0日間の範囲はエラーとなることを確認する。
+    #[test]
+    fn test_last_days_zero() {
+        let result = DateRange::last_days(&date("2024-03-01"), 0);
+
+        assert!(result.is_err());
+    }
+
+    /// 範囲に含まれる日付を、終了日を含めずに返すことを確認する。
+    #[test]
+    fn test_dates() {
+        let result = range(("2024-02-28", "2024-03-02")).dates();
+
+        let expected = vec![date("2024-02-28"), date("2024-02-29"), date("2024-03-01")];
+        assert_eq!(expected, result);
+    }
+
+    /// 夏時間の切り替えがある日も、タイムゾーンの日の始まりを境界とすることを確認する。
+    #[rstest]
+    #[case::dst_start(
+        chrono_tz::America::New_York,
+        "2024-03-10",
+        "2024-03-10T05:00:00+00:00",
+        "2024-03-11T04:00:00+00:00"
+    )]
+    #[case::dst_end(
+        chrono_tz::America::New_York,
+        "2024-11-03",
+        "2024-11-03T04:00:00+00:00",
+        "2024-11-04T05:00:00+00:00"
+    )]
+    #[case::no_midnight(
+        chrono_tz::America::Santiago,
+        "2024-09-08",
+        "2024-09-08T04:00:00+00:00",
+        "2024-09-09T03:00:00+00:00"
+    )]
+    fn test_to_utc_range(
+        #[case] tz: chrono_tz::Tz,
+        #[case] day: &str,
+        #[case] expected_start: &str,
+        #[case] expected_end: &str,
+    ) {
+        let result = DateRange::day(&date(day)).unwrap().to_utc_range(&tz);
+
+        let (start_at, end_at) = result.unwrap();
+        assert_eq!(expected_start, start_at.to_rfc3339());
+        assert_eq!(expected_end, end_at.to_rfc3339());
+    }
+
+    /// テスト用にYYYY-MM-DD形式の文字列から日付を作成する。
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    /// テスト用に開始日と終了日の文字列から範囲を作成する。
+    fn range((start, end): (&str, &str)) -> DateRange {
+        DateRange {
+            start: date(start),
+            end: date(end),
+        }
+    }
+}
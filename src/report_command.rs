@@ -0,0 +1,541 @@
+use anyhow::{ensure, Context, Result};
+use chrono::NaiveDate;
+use clap::ArgGroup;
+
+use crate::aggregation::Durations;
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::range::DateRange;
+use crate::summary_args::{summarize_range, SummaryArgs};
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 任意の期間の情報を出力するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+#[clap(group(
+    ArgGroup::new("period")
+        .required(true)
+        .args(&["from", "last", "this-quarter", "last-month"]),
+))]
+pub struct ReportArgs {
+    #[clap(
+        long = "from",
+        help = "Sets the first day of the period such as YYYY-MM-DD, MM-DD or last monday",
+        allow_hyphen_values = true
+    )]
+    from: Option<DateExpr>,
+
+    #[clap(
+        long = "to",
+        help = "Sets the last day of the period such as YYYY-MM-DD or yesterday. Defaults to today",
+        requires = "from",
+        allow_hyphen_values = true
+    )]
+    to: Option<DateExpr>,
+
+    #[clap(
+        long = "last",
+        help = "Sets the period to the last days up to today, such as 7d or 2w",
+        parse(try_from_str = parse_days)
+    )]
+    last: Option<u64>,
+
+    #[clap(long = "this-quarter", help = "Sets the period to the current quarter")]
+    this_quarter: bool,
+
+    #[clap(long = "last-month", help = "Sets the period to the previous month")]
+    last_month: bool,
+
+    #[clap(flatten)]
+    pub summary: SummaryArgs,
+}
+
+impl ReportArgs {
+    /// 指定された期間を、今日の日付をもとに日付の範囲にする。
+    fn date_range(&self, today: &NaiveDate) -> Result<DateRange> {
+        if let Some(from) = self.from {
+            let last_day = match self.to {
+                Some(to) => to.resolve(today)?,
+                None => *today,
+            };
+            return DateRange::inclusive(&from.resolve(today)?, &last_day);
+        }
+        if let Some(days) = self.last {
+            return DateRange::last_days(today, days);
+        }
+        if self.this_quarter {
+            return DateRange::quarter(today);
+        }
+
+        DateRange::previous_month(today)
+    }
+}
+
+pub struct ReportCommand<'a, T: TogglRepository, R: ReportsRepository> {
+    toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository, R: ReportsRepository> ReportCommand<'a, T, R> {
+    /// 新しい`ReportCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを期間の範囲で切り詰めて集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 期間の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `report`サブコマンドの処理を行う。
+    ///
+    /// 設定したタイムゾーンで指定された期間のtime entryを、指定した単位とタグごとに集計する。
+    /// 実行中のタイムエントリーは、`--include-running`を指定した場合のみ現在までの経過時間で集計する。
+    pub async fn run(&self, report: ReportArgs) -> Result<Durations> {
+        let today = now().with_timezone(&self.zone).date_naive();
+        let (start_at, end_at) = report.date_range(&today)?.to_utc_range(&self.zone)?;
+
+        summarize_range(
+            self.toggl_client,
+            self.reports_client,
+            &start_at,
+            &end_at,
+            &report.summary,
+            self.clip_to_range,
+            &self.zone,
+        )
+        .await
+    }
+}
+
+/// `7d`や`2w`の形式の日数をパースする。
+fn parse_days(s: &str) -> Result<u64> {
+    let (number, days_per_unit) = match s.char_indices().last() {
+        Some((index, 'd')) => (&s[..index], 1),
+        Some((index, 'w')) => (&s[..index], 7),
+        _ => anyhow::bail!("Failed to parse days: {}. Use the format 7d or 2w", s),
+    };
+    let number: u64 = number
+        .parse()
+        .with_context(|| format!("Failed to parse days: {}", s))?;
+    ensure!(number > 0, "The number of days must be positive: {}", s);
+
+    number
+        .checked_mul(days_per_unit)
+        .with_context(|| format!("The number of days is too large: {}", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+    use clap::Parser;
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::parse_days;
+    use super::ReportArgs;
+    use super::ReportCommand;
+    use crate::aggregation::{GroupDurations, CLIP_LOOKBACK_HOURS};
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::source::Source;
+    use crate::summary_args::SummaryArgs;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository, SummaryGroup, SummaryGrouping};
+
+    /// テスト用にサブコマンドの引数だけをパースするためのパーサー。
+    #[derive(Debug, Parser)]
+    struct TestParser {
+        #[clap(flatten)]
+        report: ReportArgs,
+    }
+
+    /// `7d`や`2w`の形式の日数をパースできることを確認する。
+    #[rstest]
+    #[case::days("7d", Some(7))]
+    #[case::weeks("2w", Some(14))]
+    #[case::one_day("1d", Some(1))]
+    #[case::zero("0d", None)]
+    #[case::no_unit("7", None)]
+    #[case::unknown_unit("1m", None)]
+    #[case::no_number("d", None)]
+    #[case::negative("-1d", None)]
+    #[case::empty("", None)]
+    #[case::max_days("18446744073709551615d", Some(u64::MAX))]
+    #[case::overflow("18446744073709551615w", None)]
+    fn test_parse_days(#[case] input: &str, #[case] expected: Option<u64>) {
+        let result = parse_days(input);
+
+        assert_eq!(expected, result.ok());
+    }
+
+    /// 期間を指定するオプションは、いずれか一つだけ指定できることを確認する。
+    #[rstest]
+    #[case::from(&["--from", "2024-05-01"], true)]
+    #[case::from_to(&["--from", "2024-05-01", "--to", "2024-05-10"], true)]
+    #[case::from_days_from_today(&["--from", "-7", "--to", "-1"], true)]
+    #[case::last(&["--last", "7d"], true)]
+    #[case::this_quarter(&["--this-quarter"], true)]
+    #[case::last_month(&["--last-month"], true)]
+    #[case::none(&[], false)]
+    #[case::to_without_from(&["--to", "2024-05-10"], false)]
+    #[case::from_and_last(&["--from", "2024-05-01", "--last", "7d"], false)]
+    #[case::this_quarter_and_last_month(&["--this-quarter", "--last-month"], false)]
+    fn test_period_args(#[case] args: &[&str], #[case] expected: bool) {
+        let result =
+            TestParser::try_parse_from(std::iter::once("report").chain(args.iter().copied()));
+
+        assert_eq!(expected, result.is_ok());
+    }
+
+    /// 指定した期間の最初の日から最後の日の翌日までのタイムエントリーを取得することを確認する。
+    ///
+    /// 今日は2024-05-15として期間を解決する。
+    #[tokio::test]
+    #[rstest]
+    #[case::from_to(&["--from", "2024-05-01", "--to", "2024-05-10"], (2024, 5, 1), (2024, 5, 11))]
+    #[case::from_to_today(&["--from", "2024-05-01"], (2024, 5, 1), (2024, 5, 16))]
+    #[case::relative(&["--from", "last monday", "--to", "yesterday"], (2024, 5, 13), (2024, 5, 15))]
+    #[case::month_day(&["--from", "05-01", "--to", "05-10"], (2024, 5, 1), (2024, 5, 11))]
+    #[case::days_from_today(&["--from", "-7", "--to", "-1"], (2024, 5, 8), (2024, 5, 15))]
+    #[case::same_day(&["--from", "2024-05-15", "--to", "2024-05-15"], (2024, 5, 15), (2024, 5, 16))]
+    #[case::last_days(&["--last", "7d"], (2024, 5, 9), (2024, 5, 16))]
+    #[case::last_weeks(&["--last", "2w"], (2024, 5, 2), (2024, 5, 16))]
+    #[case::this_quarter(&["--this-quarter"], (2024, 4, 1), (2024, 7, 1))]
+    #[case::last_month(&["--last-month"], (2024, 4, 1), (2024, 5, 1))]
+    async fn test_run_period(
+        #[case] args: &[&str],
+        #[case] expected_start: (i32, u32, u32),
+        #[case] expected_end: (i32, u32, u32),
+    ) {
+        mock_datetime::set_mock_time(local_datetime(2024, 5, 15, 12));
+        let start_at = local_datetime(expected_start.0, expected_start.1, expected_start.2, 0);
+        let end_at = local_datetime(expected_end.0, expected_end.1, expected_end.2, 0);
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(end_at),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let args =
+            TestParser::try_parse_from(std::iter::once("report").chain(args.iter().copied()))
+                .unwrap()
+                .report;
+        let reports = MockReportsRepository::new();
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        assert!(result.unwrap().is_empty());
+    }
+
+    /// 指定したタイムゾーンの今日と日の境界で、ホストのタイムゾーンによらず期間を解決することを確認する。
+    ///
+    /// 現在はUTCで2024-05-14 20:00とし、東京では2024-05-15となる。
+    #[tokio::test]
+    #[rstest]
+    #[case::tokyo(chrono_tz::Asia::Tokyo, (2024, 5, 14, 15), (2024, 5, 15, 15))]
+    #[case::new_york(chrono_tz::America::New_York, (2024, 5, 14, 4), (2024, 5, 15, 4))]
+    async fn test_run_time_zone(
+        #[case] tz: chrono_tz::Tz,
+        #[case] expected_start: (i32, u32, u32, u32),
+        #[case] expected_end: (i32, u32, u32, u32),
+    ) {
+        mock_datetime::set_mock_time(Utc.with_ymd_and_hms(2024, 5, 14, 20, 0, 0).unwrap());
+        let utc = |(year, month, day, hour): (i32, u32, u32, u32)| {
+            Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+        };
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(utc(expected_start) - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(utc(expected_end)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let args = ReportArgs {
+            last: Some(1),
+            ..default_args()
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = ReportCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(tz))
+            .run(args)
+            .await;
+        mock_datetime::clear_mock_time();
+
+        assert!(result.unwrap().is_empty());
+    }
+
+    /// 最後の日が最初の日より前の場合はエラーになることを確認する。
+    #[tokio::test]
+    async fn test_run_invalid_period() {
+        let toggl = MockTogglRepository::new();
+        let args = ReportArgs {
+            from: Some("2024-05-10".parse().unwrap()),
+            to: Some("2024-05-01".parse().unwrap()),
+            ..default_args()
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+
+        assert!(result.is_err());
+    }
+
+    /// 期間内のタイムエントリーをプロジェクトごとに集計し、期間の外の部分は切り詰めることを確認する。
+    ///
+    /// 切り詰めない場合は、開始が期間の前のタイムエントリーも全体を集計する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, 3600)]
+    #[case::no_clip(true, 2 * 3600)]
+    async fn test_run_durations(#[case] no_clip: bool, #[case] expected_project_2: i64) {
+        let entries = vec![
+            dummy_entry(1, local_datetime(2024, 5, 1, 9), 3600, "project 1"),
+            dummy_entry(2, local_datetime(2024, 5, 3, 9), 1800, "project 1"),
+            // 期間の開始前から続くタイムエントリー
+            dummy_entry(3, local_datetime(2024, 4, 30, 23), 2 * 3600, "project 2"),
+        ];
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let args = ReportArgs {
+            from: Some("2024-05-01".parse().unwrap()),
+            to: Some("2024-05-07".parse().unwrap()),
+            summary: SummaryArgs {
+                no_clip,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+
+        let durations = result.unwrap();
+        assert_eq!(2, durations.len());
+        assert_eq!(
+            GroupDurations {
+                total: 3600 + 1800,
+                ..Default::default()
+            },
+            durations["project 1"]
+        );
+        assert_eq!(expected_project_2, durations["project 2"].total);
+    }
+
+    /// `--include-running`を指定した場合に、実行中のタイムエントリーを現在までの経過時間で集計することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::include(true, 3600 + 1800)]
+    #[case::exclude(false, 3600)]
+    async fn test_run_include_running(#[case] include_running: bool, #[case] expected: i64) {
+        let start = local_datetime(2024, 5, 3, 9);
+        mock_datetime::set_mock_time(start + TimeDelta::minutes(30));
+        let entries = vec![
+            dummy_entry(1, local_datetime(2024, 5, 1, 9), 3600, "project 1"),
+            TimeEntry {
+                stop: None,
+                // Toggl APIでは実行中のタイムエントリーは負の値となる
+                duration: -start.timestamp(),
+                ..dummy_entry(2, start, 0, "project 1")
+            },
+        ];
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let reports = MockReportsRepository::new();
+        let args = ReportArgs {
+            from: Some("2024-05-01".parse().unwrap()),
+            to: Some("2024-05-07".parse().unwrap()),
+            summary: SummaryArgs {
+                include_running,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        let durations = result.unwrap();
+        assert_eq!(expected, durations["project 1"].total);
+        assert_eq!(include_running, durations["project 1"].in_progress);
+    }
+
+    /// Reports APIを指定した場合に、期間のサマリーレポートの集計結果を利用することを確認する。
+    #[tokio::test]
+    async fn test_run_reports_summary() {
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entries().never();
+        toggl
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10]));
+        let mut reports = MockReportsRepository::new();
+        reports
+            .expect_read_summary()
+            .with(
+                predicate::eq(10),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()),
+                predicate::eq(NaiveDate::from_ymd_opt(2024, 5, 7).unwrap()),
+                predicate::eq(SummaryGrouping::Projects),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(vec![SummaryGroup {
+                    name: "project 1".to_string(),
+                    seconds: 5400,
+                    untagged: 1800,
+                    tags: HashMap::from([("tag 1".to_string(), 3600)]),
+                }])
+            });
+        let args = ReportArgs {
+            from: Some("2024-05-01".parse().unwrap()),
+            to: Some("2024-05-07".parse().unwrap()),
+            summary: SummaryArgs {
+                source: Source::Reports,
+                no_clip: true,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(
+            GroupDurations {
+                total: 5400,
+                tags: HashMap::from([("tag 1".to_string(), 3600)]),
+                ..Default::default()
+            },
+            result.unwrap()["project 1"]
+        );
+    }
+
+    /// Reports APIを指定して期間で切り詰める場合は、詳細レポートのタイムエントリーから集計することを確認する。
+    #[tokio::test]
+    async fn test_run_reports_clip() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10]));
+        let mut reports = MockReportsRepository::new();
+        reports.expect_read_summary().never();
+        reports
+            .expect_read_detailed_time_entries()
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(vec![dummy_entry(
+                    1,
+                    local_datetime(2024, 4, 30, 23),
+                    7200,
+                    "project 1",
+                )])
+            });
+        let args = ReportArgs {
+            from: Some("2024-05-01".parse().unwrap()),
+            to: Some("2024-05-07".parse().unwrap()),
+            summary: SummaryArgs {
+                source: Source::Reports,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+
+        assert_eq!(3600, result.unwrap()["project 1"].total);
+    }
+
+    /// Reports APIは実行中のタイムエントリーを返さないため、`--include-running`と同時に指定するとエラーになることを確認する。
+    #[tokio::test]
+    async fn test_run_reports_include_running() {
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_workspace_ids().never();
+        let mut reports = MockReportsRepository::new();
+        reports.expect_read_summary().never();
+        reports.expect_read_detailed_time_entries().never();
+        let args = ReportArgs {
+            from: Some("2024-05-01".parse().unwrap()),
+            to: Some("2024-05-07".parse().unwrap()),
+            summary: SummaryArgs {
+                include_running: true,
+                source: Source::Reports,
+                ..Default::default()
+            },
+            ..default_args()
+        };
+
+        let result = ReportCommand::new(&toggl, &reports).run(args).await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Running time entries cannot be counted with --source reports"));
+    }
+
+    /// テスト用にデフォルトの引数を作成する。
+    fn default_args() -> ReportArgs {
+        ReportArgs {
+            from: None,
+            to: None,
+            last: None,
+            this_quarter: false,
+            last_month: false,
+            summary: SummaryArgs::default(),
+        }
+    }
+
+    /// テスト用にLocalタイムゾーンの日時を作成する。
+    fn local_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(id: i64, start: DateTime<Utc>, duration: i64, project: &str) -> TimeEntry {
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + TimeDelta::seconds(duration)),
+            duration,
+            description: format!("entry{}", id),
+            project: Some(project.to_string()),
+            ..Default::default()
+        }
+    }
+}
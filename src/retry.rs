@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode};
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+use crate::config::ApiConfig;
+
+/// リクエストを再送する際の方針。
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// 最初の送信を含めた最大試行回数
+    pub max_attempts: u32,
+    /// 1回目の再送までの待機時間
+    pub initial_backoff: Duration,
+    /// 再送までの待機時間の上限
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// 設定から`RetryPolicy`を作成する。
+    pub fn from_config(config: &ApiConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+
+    /// 再送しない`RetryPolicy`を返す。
+    #[cfg(test)]
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// 失敗した試行の次に再送するまでの待機時間を返す。
+    ///
+    /// サーバーから待機時間が指定されている場合はそれを優先し、指定がない場合は指数的に待機時間を増やす。
+    /// いずれの場合も上限を超えない。
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - 失敗した試行の回数(1始まり)
+    /// * `retry_after` - `Retry-After`ヘッダーで指定された待機時間
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = retry_after.unwrap_or_else(|| {
+            let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+            self.initial_backoff.saturating_mul(factor)
+        });
+
+        backoff.min(self.max_backoff)
+    }
+}
+
+/// 再送によって成功する可能性があるステータスコードかを返す。
+///
+/// サーバーエラー(5xx)はリクエストが処理された可能性があるため、再送しても安全なリクエストの場合のみ再送する。
+///
+/// # Arguments
+///
+/// * `status` - レスポンスのステータスコード
+/// * `retry_safe` - 複数回送信しても結果が変わらないリクエストか
+pub fn is_retryable_status(status: StatusCode, retry_safe: bool) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || (status.is_server_error() && retry_safe)
+}
+
+/// 同じリクエストを複数回送信しても結果が変わらないメソッドかを返す。
+///
+/// RFC 9110に従い、PATCHは冪等なメソッドとして扱わない。
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE
+    )
+}
+
+/// `Retry-After`ヘッダーの値から待機時間を返す。
+///
+/// 秒数とHTTP日付の形式に対応し、解釈できない場合は`None`を返す。
+/// 過去の日付が指定された場合は待機しない。
+///
+/// # Arguments
+///
+/// * `value` - `Retry-After`ヘッダーの値
+/// * `now` - HTTP日付からの待機時間の計算に利用する現在時刻
+pub fn parse_retry_after(value: &str, now: &DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let datetime = DateTime::parse_from_rfc2822(value).ok()?.to_utc();
+    Some((datetime - *now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// 1秒あたりのリクエスト数を制限するためのリミッター。
+///
+/// 複数のリクエストから共有され、リクエストの送信間隔が一定以上になるように待機させる。
+pub struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// 新しい`RateLimiter`を返す。
+    ///
+    /// # Arguments
+    ///
+    /// * `requests_per_second` - 1秒あたりの最大リクエスト数。0の場合は制限しない
+    pub fn new(requests_per_second: u32) -> Self {
+        let interval =
+            (requests_per_second > 0).then(|| Duration::from_secs(1) / requests_per_second);
+
+        Self {
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// リクエストを送信してよい時刻まで待機する。
+    pub async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        // 送信枠の予約のみをロック中に行い、待機はロックを解放してから行う
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + interval;
+            slot
+        };
+        sleep_until(slot).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+    use reqwest::{Method, StatusCode};
+    use rstest::rstest;
+    use tokio::time::Instant;
+
+    use super::is_idempotent;
+    use super::is_retryable_status;
+    use super::parse_retry_after;
+    use super::RateLimiter;
+    use super::RetryPolicy;
+
+    /// 待機時間が指数的に増え、上限を超えないことを確認する。
+    #[rstest]
+    #[case::first(1, None, 100)]
+    #[case::second(2, None, 200)]
+    #[case::third(3, None, 400)]
+    #[case::capped(10, None, 1000)]
+    #[case::retry_after(1, Some(300), 300)]
+    #[case::retry_after_capped(1, Some(5000), 1000)]
+    fn test_delay(
+        #[case] attempt: u32,
+        #[case] retry_after_ms: Option<u64>,
+        #[case] expected_ms: u64,
+    ) {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+        };
+
+        let result = policy.delay(attempt, retry_after_ms.map(Duration::from_millis));
+
+        assert_eq!(Duration::from_millis(expected_ms), result);
+    }
+
+    /// 再送対象のステータスコードを、再送しても安全なリクエストかに応じて判定できることを確認する。
+    #[rstest]
+    #[case::too_many_requests(StatusCode::TOO_MANY_REQUESTS, true, true)]
+    #[case::internal_server_error(StatusCode::INTERNAL_SERVER_ERROR, true, true)]
+    #[case::service_unavailable(StatusCode::SERVICE_UNAVAILABLE, true, true)]
+    #[case::too_many_requests_unsafe(StatusCode::TOO_MANY_REQUESTS, false, true)]
+    #[case::service_unavailable_unsafe(StatusCode::SERVICE_UNAVAILABLE, false, false)]
+    #[case::ok(StatusCode::OK, true, false)]
+    #[case::bad_request(StatusCode::BAD_REQUEST, true, false)]
+    #[case::unauthorized(StatusCode::UNAUTHORIZED, true, false)]
+    #[case::not_found(StatusCode::NOT_FOUND, true, false)]
+    fn test_is_retryable_status(
+        #[case] status: StatusCode,
+        #[case] retry_safe: bool,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, is_retryable_status(status, retry_safe));
+    }
+
+    /// 冪等なメソッドを判定できることを確認する。
+    #[rstest]
+    #[case::get(Method::GET, true)]
+    #[case::put(Method::PUT, true)]
+    #[case::delete(Method::DELETE, true)]
+    #[case::post(Method::POST, false)]
+    #[case::patch(Method::PATCH, false)]
+    fn test_is_idempotent(#[case] method: Method, #[case] expected: bool) {
+        assert_eq!(expected, is_idempotent(&method));
+    }
+
+    /// `Retry-After`ヘッダーの値を解釈できることを確認する。
+    #[rstest]
+    #[case::seconds("120", Some(120))]
+    #[case::seconds_with_space(" 3 ", Some(3))]
+    #[case::http_date("Mon, 01 Jan 2024 00:00:30 GMT", Some(30))]
+    #[case::past_http_date("Sun, 31 Dec 2023 23:59:00 GMT", Some(0))]
+    #[case::invalid("soon", None)]
+    #[case::negative("-1", None)]
+    fn test_parse_retry_after(#[case] value: &str, #[case] expected_secs: Option<u64>) {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let result = parse_retry_after(value, &now);
+
+        assert_eq!(expected_secs.map(Duration::from_secs), result);
+    }
+
+    /// 並行して送信枠を取得した場合に、送信間隔が制限されることを確認する。
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let limiter = RateLimiter::new(20);
+        let started = Instant::now();
+
+        tokio::join!(
+            limiter.acquire(),
+            limiter.acquire(),
+            limiter.acquire(),
+            limiter.acquire()
+        );
+
+        // 1回目は即時、以降は50msずつ間隔が空く
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+
+    /// 制限しない場合は待機しないことを確認する。
+    #[tokio::test]
+    async fn test_rate_limiter_unlimited() {
+        let limiter = RateLimiter::new(0);
+        let started = Instant::now();
+
+        for _ in 0..100 {
+            limiter.acquire().await;
+        }
+
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{ensure, Context, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, TimeZone, Utc};
+use log::info;
+
+use crate::time_entry::TimeEntry;
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 集計に利用するタイムエントリーの取得元。
+#[derive(Debug, Clone, Copy, PartialEq, clap::ArgEnum)]
+pub enum Source {
+    /// Toggl APIのタイムエントリー一覧
+    TimeEntries,
+    /// Toggl Reports API
+    Reports,
+}
+
+/// 取得元を指定して、期間内に開始したタイムエントリーを取得する。
+///
+/// Reports APIの場合は、ユーザーが参加している全てのワークスペースの詳細レポートから取得する。
+/// Reports APIは実行中のタイムエントリーを返さないため、実行中のタイムエントリーを集計する場合はエラーを返す。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+/// * `source` - タイムエントリーの取得元
+/// * `include_running` - 実行中のタイムエントリーを集計するか
+/// * `start_at` - 取得する期間の開始日時
+/// * `end_at` - 取得する期間の終了日時(この日時を含まない)
+/// * `tz` - Reports APIに指定する日付を決めるタイムゾーン
+pub async fn read_time_entries<T: TogglRepository, R: ReportsRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    reports_client: &R,
+    source: Source,
+    include_running: bool,
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Vec<TimeEntry>> {
+    ensure_running_supported(source, include_running)?;
+
+    match source {
+        Source::TimeEntries => toggl_client.read_time_entries(start_at, end_at).await,
+        Source::Reports => {
+            let workspace_ids = toggl_client
+                .read_workspace_ids()
+                .await
+                .context("Failed to get workspaces")?;
+            // Reports APIはユーザーのプロフィールのタイムゾーンで日付を解釈するため、
+            // 指定したタイムゾーンとの差を吸収できるよう前後に1日ずつ広げて取得し、期間内に絞り込む
+            let (start_date, end_date) = report_date_range(start_at, end_at, tz);
+            let start_date = start_date - TimeDelta::days(1);
+            let end_date = end_date + TimeDelta::days(1);
+            info!("Start date: {}, End date: {}", start_date, end_date);
+            let mut time_entries = Vec::new();
+            for workspace_id in workspace_ids {
+                time_entries.extend(
+                    reports_client
+                        .read_detailed_time_entries(workspace_id, &start_date, &end_date)
+                        .await?,
+                );
+            }
+
+            Ok(time_entries
+                .into_iter()
+                .filter(|entry| *start_at <= entry.start && entry.start < *end_at)
+                .collect())
+        }
+    }
+}
+
+/// 実行中のタイムエントリーを集計する場合に、取得元が実行中のタイムエントリーを返すことを確認する。
+///
+/// Reports APIは実行中のタイムエントリーを返さないため、集計する場合はエラーを返す。
+///
+/// # Arguments
+///
+/// * `source` - タイムエントリーの取得元
+/// * `include_running` - 実行中のタイムエントリーを集計するか
+pub fn ensure_running_supported(source: Source, include_running: bool) -> Result<()> {
+    ensure!(
+        !(include_running && source == Source::Reports),
+        "Running time entries cannot be counted with --source reports because the Reports API does not return them"
+    );
+
+    Ok(())
+}
+
+/// 期間をReports APIに指定する、指定したタイムゾーンの日付の範囲に変換する。
+///
+/// 終了日はReports APIの指定に合わせ、期間に含まれる最後の日とする。
+///
+/// # Arguments
+///
+/// * `start_at` - 期間の開始日時
+/// * `end_at` - 期間の終了日時(この日時を含まない)
+/// * `tz` - 日付に変換するタイムゾーン
+pub fn report_date_range<Tz: TimeZone>(
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+    tz: &Tz,
+) -> (NaiveDate, NaiveDate) {
+    let start_date = start_at.with_timezone(tz).date_naive();
+    let end_date = (*end_at - TimeDelta::nanoseconds(1))
+        .with_timezone(tz)
+        .date_naive();
+
+    (start_date, end_date.max(start_date))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Asia::Tokyo;
+    use mockall::predicate;
+
+    use super::read_time_entries;
+    use super::report_date_range;
+    use super::Source;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// 期間を指定したタイムゾーンの日付の範囲に変換できることを確認する。
+    #[test]
+    fn test_report_date_range() {
+        let start_at = Tokyo
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let end_at = Tokyo
+            .with_ymd_and_hms(2024, 2, 1, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+
+        let result = report_date_range(&start_at, &end_at, &Tokyo);
+
+        assert_eq!(
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+            ),
+            result
+        );
+    }
+
+    /// タイムエントリー一覧から取得する場合は、Toggl APIのリポジトリを利用することを確認する。
+    #[tokio::test]
+    async fn test_read_time_entries_from_time_entries() {
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        let entries = vec![dummy_entry(1, start_at)];
+        let mut toggl_client = MockTogglRepository::new();
+        let returned = entries.clone();
+        toggl_client
+            .expect_read_time_entries()
+            .with(predicate::eq(start_at), predicate::eq(end_at))
+            .times(1)
+            .returning(move |_, _| Ok(returned.clone()));
+        let mut reports_client = MockReportsRepository::new();
+        reports_client.expect_read_detailed_time_entries().never();
+
+        let result = read_time_entries(
+            &toggl_client,
+            &reports_client,
+            Source::TimeEntries,
+            true,
+            &start_at,
+            &end_at,
+            &Tokyo,
+        )
+        .await;
+
+        assert_eq!(entries, result.unwrap());
+    }
+
+    /// Reports APIから取得する場合は、全てのワークスペースから前後に広げた日付で取得し、期間内に開始したものに絞り込むことを確認する。
+    #[tokio::test]
+    async fn test_read_time_entries_from_reports() {
+        let start_at = Tokyo
+            .with_ymd_and_hms(2024, 1, 10, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let end_at = Tokyo
+            .with_ymd_and_hms(2024, 1, 11, 0, 0, 0)
+            .unwrap()
+            .to_utc();
+        let entries = vec![
+            dummy_entry(1, start_at - TimeDelta::seconds(1)),
+            dummy_entry(2, start_at),
+            dummy_entry(3, end_at - TimeDelta::seconds(1)),
+        ];
+        let other_entries = vec![dummy_entry(4, end_at), dummy_entry(5, start_at)];
+        let mut toggl_client = MockTogglRepository::new();
+        toggl_client.expect_read_time_entries().never();
+        toggl_client
+            .expect_read_workspace_ids()
+            .times(1)
+            .returning(|| Ok(vec![10, 20]));
+        let mut reports_client = MockReportsRepository::new();
+        for (workspace_id, entries) in [(10, entries), (20, other_entries)] {
+            reports_client
+                .expect_read_detailed_time_entries()
+                .with(
+                    predicate::eq(workspace_id),
+                    predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 9).unwrap()),
+                    predicate::eq(NaiveDate::from_ymd_opt(2024, 1, 11).unwrap()),
+                )
+                .times(1)
+                .returning(move |_, _, _| Ok(entries.clone()));
+        }
+
+        let result = read_time_entries(
+            &toggl_client,
+            &reports_client,
+            Source::Reports,
+            false,
+            &start_at,
+            &end_at,
+            &Tokyo,
+        )
+        .await;
+
+        let ids: Vec<i64> = result.unwrap().iter().map(|entry| entry.id).collect();
+        assert_eq!(vec![2, 3, 5], ids);
+    }
+
+    /// Reports APIから取得する場合は、実行中のタイムエントリーを集計する指定をするとエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_read_time_entries_from_reports_include_running() {
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        let mut toggl_client = MockTogglRepository::new();
+        toggl_client.expect_read_workspace_ids().never();
+        let mut reports_client = MockReportsRepository::new();
+        reports_client.expect_read_detailed_time_entries().never();
+
+        let result = read_time_entries(
+            &toggl_client,
+            &reports_client,
+            Source::Reports,
+            true,
+            &start_at,
+            &end_at,
+            &Tokyo,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(id: i64, start: chrono::DateTime<Utc>) -> TimeEntry {
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + TimeDelta::minutes(30)),
+            duration: 1800,
+            description: format!("entry{}", id),
+            ..Default::default()
+        }
+    }
+}
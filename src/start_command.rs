@@ -0,0 +1,279 @@
+use anyhow::{Context, Result};
+use log::info;
+
+use crate::datetime::now;
+use crate::project::find_project_by_name;
+use crate::time_entry::{NewTimeEntry, TimeEntry};
+use crate::toggl::TogglRepository;
+
+/// タイムエントリーを開始するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct StartArgs {
+    #[clap(help = "Description of the time entry", default_value = "")]
+    description: String,
+
+    #[clap(short = 'p', long = "project", help = "Project name of the time entry")]
+    project: Option<String>,
+
+    #[clap(
+        short = 't',
+        long = "tag",
+        help = "Tag of the time entry. Can be specified multiple times"
+    )]
+    tags: Vec<String>,
+}
+
+pub struct StartCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+}
+
+impl<'a, T: TogglRepository> StartCommand<'a, T> {
+    /// 新しい`StartCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self { toggl_client }
+    }
+
+    /// `start`サブコマンドの処理を行う。
+    ///
+    /// 現在時刻から新しいタイムエントリーを開始し、開始したタイムエントリーを返す。
+    /// プロジェクトが指定された場合はプロジェクトのワークスペースに、指定されていない場合はデフォルトワークスペースに作成する。
+    pub async fn run(&self, start: StartArgs) -> Result<TimeEntry> {
+        let (workspace_id, project_id) = match start.project {
+            Some(name) => {
+                let projects = self
+                    .toggl_client
+                    .read_projects()
+                    .await
+                    .context("Failed to retrieve projects")?;
+                let project = find_project_by_name(&projects, &name)?;
+                (project.workspace_id, Some(project.id))
+            }
+            None => {
+                let workspace_id = self
+                    .toggl_client
+                    .read_default_workspace_id()
+                    .await
+                    .context("Failed to retrieve default workspace")?;
+                (workspace_id, None)
+            }
+        };
+
+        let new_entry = NewTimeEntry {
+            workspace_id,
+            start: now(),
+            stop: None,
+            description: start.description,
+            project_id,
+            tags: start.tags,
+        };
+
+        start_time_entry(self.toggl_client, &new_entry).await
+    }
+}
+
+/// 実行中のタイムエントリーを停止してから、新しいタイムエントリーを開始する。
+///
+/// # Arguments
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `new_entry` - 開始するタイムエントリー
+pub async fn start_time_entry<T: TogglRepository>(
+    toggl_client: &T,
+    new_entry: &NewTimeEntry,
+) -> Result<TimeEntry> {
+    let current = toggl_client
+        .read_current_time_entry()
+        .await
+        .context("Failed to retrieve current time entry")?;
+    if let Some(current) = current {
+        toggl_client
+            .stop_time_entry(current.workspace_id, current.id)
+            .await
+            .context("Failed to stop current time entry")?;
+        info!("Stopped current time entry: {}", current.id);
+    }
+
+    let entry = toggl_client
+        .create_time_entry(new_entry)
+        .await
+        .context("Failed to start time entry")?;
+    info!("Started time entry: {}", entry.id);
+
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::StartArgs;
+    use super::StartCommand;
+    use crate::datetime::mock_datetime;
+    use crate::project::Project;
+    use crate::time_entry::{NewTimeEntry, TimeEntry};
+    use crate::toggl::MockTogglRepository;
+
+    /// 実行中のタイムエントリーの有無に関わらず、新しいタイムエントリーを開始できることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::no_running(None)]
+    #[case::running(Some(dummy_entry(1, None)))]
+    async fn test_start_command_with_project(#[case] current: Option<TimeEntry>) {
+        let now = DateTime::parse_from_rfc3339("2024-01-02T03:04:05+00:00")
+            .unwrap()
+            .to_utc();
+        mock_datetime::set_mock_time(now);
+        let args = StartArgs {
+            description: "new entry".to_string(),
+            project: Some("project 2".to_string()),
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+        };
+        let expected_new_entry = NewTimeEntry {
+            workspace_id: 20,
+            start: now,
+            stop: None,
+            description: "new entry".to_string(),
+            project_id: Some(2),
+            tags: vec!["tag 1".to_string(), "tag 2".to_string()],
+        };
+        let created = dummy_entry(2, Some(now));
+        let expected = created.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_projects()
+            .times(1)
+            .returning(|| Ok(dummy_projects()));
+        toggl.expect_read_default_workspace_id().never();
+        let stop_times = if current.is_some() { 1 } else { 0 };
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(move || Ok(current.clone()));
+        toggl
+            .expect_stop_time_entry()
+            .with(predicate::eq(10), predicate::eq(1))
+            .times(stop_times)
+            .returning(|_, _| Ok(dummy_entry(1, None)));
+        toggl
+            .expect_create_time_entry()
+            .with(predicate::eq(expected_new_entry))
+            .times(1)
+            .returning(move |_| Ok(created.clone()));
+
+        let command = StartCommand::new(&toggl);
+        let result = command.run(args).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// プロジェクトを指定しない場合はデフォルトワークスペースに作成することを確認する。
+    #[tokio::test]
+    async fn test_start_command_without_project() {
+        let now = DateTime::parse_from_rfc3339("2024-01-02T03:04:05+00:00")
+            .unwrap()
+            .to_utc();
+        mock_datetime::set_mock_time(now);
+        let args = StartArgs {
+            description: "".to_string(),
+            project: None,
+            tags: vec![],
+        };
+        let expected_new_entry = NewTimeEntry {
+            workspace_id: 30,
+            start: now,
+            stop: None,
+            description: "".to_string(),
+            project_id: None,
+            tags: vec![],
+        };
+
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_projects().never();
+        toggl
+            .expect_read_default_workspace_id()
+            .times(1)
+            .returning(|| Ok(30));
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(|| Ok(None));
+        toggl
+            .expect_create_time_entry()
+            .with(predicate::eq(expected_new_entry))
+            .times(1)
+            .returning(move |_| Ok(dummy_entry(2, Some(now))));
+
+        let command = StartCommand::new(&toggl);
+        let result = command.run(args).await;
+
+        assert!(result.is_ok());
+    }
+
+    /// プロジェクト名が解決できない場合はタイムエントリーを作成せずにエラーとなることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::unknown("project 9")]
+    #[case::ambiguous("project 1")]
+    async fn test_start_command_invalid_project(#[case] project: &str) {
+        let args = StartArgs {
+            description: "new entry".to_string(),
+            project: Some(project.to_string()),
+            tags: vec![],
+        };
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_projects()
+            .times(1)
+            .returning(|| Ok(dummy_projects()));
+        toggl.expect_read_current_time_entry().never();
+        toggl.expect_create_time_entry().never();
+
+        let command = StartCommand::new(&toggl);
+        let result = command.run(args).await;
+
+        assert!(result.is_err());
+    }
+
+    /// テスト用のタイムエントリーを作成する。
+    fn dummy_entry(id: i64, start: Option<DateTime<Utc>>) -> TimeEntry {
+        TimeEntry {
+            id,
+            workspace_id: 10,
+            start: start.unwrap_or_default(),
+            stop: None,
+            duration: -1,
+            description: format!("entry {}", id),
+            project_id: None,
+            project: None,
+            tags: vec![],
+            ..Default::default()
+        }
+    }
+
+    /// テスト用のプロジェクト一覧を作成する。
+    fn dummy_projects() -> Vec<Project> {
+        vec![
+            Project {
+                id: 1,
+                workspace_id: 10,
+                name: "project 1".to_string(),
+            },
+            Project {
+                id: 2,
+                workspace_id: 20,
+                name: "project 2".to_string(),
+            },
+            Project {
+                id: 3,
+                workspace_id: 20,
+                name: "project 1".to_string(),
+            },
+        ]
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use log::info;
+
+use crate::time_entry::TimeEntry;
+use crate::toggl::TogglRepository;
+
+/// 実行中のタイムエントリーを停止するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct StopArgs {}
+
+pub struct StopCommand<'a, T: TogglRepository> {
+    toggl_client: &'a T,
+}
+
+impl<'a, T: TogglRepository> StopCommand<'a, T> {
+    /// 新しい`StopCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T) -> Self {
+        Self { toggl_client }
+    }
+
+    /// `stop`サブコマンドの処理を行う。
+    ///
+    /// 実行中のタイムエントリーを停止し、停止したタイムエントリーを返す。
+    /// 実行中のタイムエントリーが存在しない場合はエラーを返す。
+    pub async fn run(&self, _stop: StopArgs) -> Result<TimeEntry> {
+        let current = self
+            .toggl_client
+            .read_current_time_entry()
+            .await
+            .context("Failed to retrieve current time entry")?
+            .context("No time entry is running")?;
+
+        let entry = self
+            .toggl_client
+            .stop_time_entry(current.workspace_id, current.id)
+            .await
+            .context("Failed to stop time entry")?;
+        info!("Stopped time entry: {}", entry.id);
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mockall::predicate;
+
+    use super::StopArgs;
+    use super::StopCommand;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::MockTogglRepository;
+
+    /// 実行中のタイムエントリーを停止できることを確認する。
+    #[tokio::test]
+    async fn test_stop_command() {
+        let running = TimeEntry {
+            id: 1,
+            workspace_id: 10,
+            start: Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap(),
+            stop: None,
+            duration: -1,
+            description: "entry 1".to_string(),
+            project_id: None,
+            project: None,
+            tags: vec![],
+            ..Default::default()
+        };
+        let stopped = TimeEntry {
+            stop: Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap()),
+            duration: 3600,
+            ..running.clone()
+        };
+        let expected = stopped.clone();
+
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(move || Ok(Some(running.clone())));
+        toggl
+            .expect_stop_time_entry()
+            .with(predicate::eq(10), predicate::eq(1))
+            .times(1)
+            .returning(move |_, _| Ok(stopped.clone()));
+
+        let command = StopCommand::new(&toggl);
+        let result = command.run(StopArgs {}).await;
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    /// 実行中のタイムエントリーがない場合にエラーとなることを確認する。
+    #[tokio::test]
+    async fn test_stop_command_no_running_entry() {
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_current_time_entry()
+            .times(1)
+            .returning(|| Ok(None));
+        toggl.expect_stop_time_entry().never();
+
+        let command = StopCommand::new(&toggl);
+        let result = command.run(StopArgs {}).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use log::{info, warn};
+
+use crate::aggregation::{
+    calc_project_tag_duration, Durations, GroupBy, GroupDurations, MonthlyDurations, SortBy,
+    TagMode, UNTAGGED_KEY,
+};
+use crate::datetime::now;
+use crate::fetch::{read_range_time_entries, FetchOptions};
+use crate::range::DateRange;
+use crate::source::{ensure_running_supported, report_date_range, Source};
+use crate::toggl::{ReportsRepository, SummaryGroup, SummaryGrouping, TogglRepository};
+
+/// 期間のタイムエントリーを集計するサブコマンドで共通の引数。
+#[derive(Debug, clap::Args)]
+pub struct SummaryArgs {
+    #[clap(
+        short = 'g',
+        long = "group-by",
+        help = "Groups durations by the given key before tags",
+        arg_enum,
+        default_value = "project"
+    )]
+    pub group_by: GroupBy,
+
+    #[clap(
+        long = "tag-mode",
+        help = "Sets how durations are assigned to tags. per-tag adds the whole duration to each tag, untagged also adds untagged time to \"(no tag)\", and split divides the duration evenly across the tags",
+        arg_enum,
+        default_value = "per-tag"
+    )]
+    pub tag_mode: TagMode,
+
+    #[clap(
+        long = "sort",
+        help = "Sorts projects and tags by name, by duration in descending order, or by the project order in the config file",
+        arg_enum,
+        default_value = "name"
+    )]
+    pub sort: SortBy,
+
+    #[clap(
+        long = "no-clip",
+        help = "Counts whole time entries by their start instead of only the part inside the period, each month or each day. With --source reports, the summary aggregated on the server is used"
+    )]
+    pub no_clip: bool,
+
+    #[clap(
+        long = "include-running",
+        help = "Counts running time entries up to now. Cannot be used with --source reports"
+    )]
+    pub include_running: bool,
+
+    #[clap(
+        long = "source",
+        help = "Fetches data from the time entries API or the Reports API. The Reports API covers all workspaces of the user, interprets dates in the timezone of the Toggl profile and does not return running time entries",
+        arg_enum,
+        default_value = "time-entries"
+    )]
+    pub source: Source,
+}
+
+impl Default for SummaryArgs {
+    fn default() -> Self {
+        Self {
+            group_by: GroupBy::Project,
+            tag_mode: TagMode::PerTag,
+            sort: SortBy::Name,
+            no_clip: false,
+            include_running: false,
+            source: Source::TimeEntries,
+        }
+    }
+}
+
+/// 期間のtime entryを取得し、指定した単位とタグごとに集計する。
+///
+/// Reports APIで切り詰めずに集計できる場合は、全てのワークスペースのサマリーレポートを合算した結果を利用する。
+/// Reports APIは実行中のタイムエントリーを返さないため、`--include-running`と同時には指定できない。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+/// * `start_at` - 期間の開始日時
+/// * `end_at` - 期間の終了日時(この日時を含まない)
+/// * `summary` - 集計方法を指定する引数
+/// * `clip_to_range` - タイムエントリーを期間の範囲で切り詰めて集計するか
+/// * `tz` - Reports APIに指定する日付を決めるタイムゾーン
+pub async fn summarize_range<T: TogglRepository, R: ReportsRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    reports_client: &R,
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+    summary: &SummaryArgs,
+    clip_to_range: bool,
+    tz: &Tz,
+) -> Result<Durations> {
+    info!("Start at: {}, End at: {}", start_at, end_at);
+    ensure_running_supported(summary.source, summary.include_running)?;
+
+    // サーバー側ではタイムエントリーを期間で切り詰めたり、タグごとの時間を等分したりできないため、
+    // その場合は詳細レポートのタイムエントリーから集計する
+    let clip = clip_to_range && !summary.no_clip;
+    if let (Source::Reports, false, Some(grouping), false) = (
+        summary.source,
+        clip,
+        summary_grouping(summary.group_by),
+        summary.tag_mode == TagMode::Split,
+    ) {
+        let workspace_ids = toggl_client
+            .read_workspace_ids()
+            .await
+            .context("Failed to get workspaces")?;
+        let (start_date, end_date) = report_date_range(start_at, end_at, tz);
+        let mut groups = Vec::new();
+        for workspace_id in workspace_ids {
+            groups.extend(
+                reports_client
+                    .read_summary(workspace_id, &start_date, &end_date, grouping)
+                    .await
+                    .context("Failed to retrieve summary report")?,
+            );
+        }
+
+        return Ok(to_durations(groups, summary.tag_mode));
+    }
+
+    let options = FetchOptions {
+        source: summary.source,
+        include_running: summary.include_running,
+        clip,
+    };
+    let time_entries =
+        read_range_time_entries(toggl_client, reports_client, &options, start_at, end_at, tz)
+            .await?;
+
+    Ok(calc_project_tag_duration(
+        &time_entries,
+        summary.group_by,
+        summary.tag_mode,
+    ))
+}
+
+/// 期間のtime entryを月ごとに取得し、月ごとに指定した単位とタグごとに集計する。
+///
+/// APIの制限を超えないよう、1ヶ月ずつ取得する。現在より後に始まる月は取得せず、空の集計結果とする。
+/// タイムエントリー一覧から取得する場合、取得できる過去の期間より前に終わる月も取得せず、警告を出力して空の集計結果とする。
+///
+/// # Arguments
+///
+/// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+/// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+/// * `range` - 集計する期間
+/// * `summary` - 集計方法を指定する引数
+/// * `clip_to_range` - タイムエントリーを月の範囲で切り詰めて集計するか
+/// * `tz` - 月の境界とするタイムゾーン
+pub async fn summarize_by_month<T: TogglRepository, R: ReportsRepository, Tz: TimeZone>(
+    toggl_client: &T,
+    reports_client: &R,
+    range: &DateRange,
+    summary: &SummaryArgs,
+    clip_to_range: bool,
+    tz: &Tz,
+) -> Result<MonthlyDurations> {
+    let history_start = match summary.source {
+        Source::TimeEntries => toggl_client.history_start(),
+        Source::Reports => None,
+    };
+    let mut months = vec![];
+    let mut skipped_months = vec![];
+    for month in range.months()? {
+        let (start_at, end_at) = month.to_utc_range(tz)?;
+        if start_at > now() {
+            months.push((month.start, Durations::new()));
+            continue;
+        }
+        if history_start.is_some_and(|history_start| end_at <= history_start) {
+            skipped_months.push(month.start);
+            months.push((month.start, Durations::new()));
+            continue;
+        }
+
+        let durations = summarize_range(
+            toggl_client,
+            reports_client,
+            &start_at,
+            &end_at,
+            summary,
+            clip_to_range,
+            tz,
+        )
+        .await
+        .with_context(|| format!("Failed to summarize time entries of {}", month.start))?;
+        months.push((month.start, durations));
+    }
+    if let (Some(history_start), false) = (history_start, skipped_months.is_empty()) {
+        warn!(
+            "Toggl API only returns time entries since {}; {} are not included. Use --source reports to aggregate older months",
+            history_start,
+            skipped_months
+                .iter()
+                .map(|month| month.format("%Y-%m").to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(MonthlyDurations::new(months).skipped(skipped_months))
+}
+
+/// Reports APIのサマリーレポートで集計できる場合は、その単位を返す。
+fn summary_grouping(group_by: GroupBy) -> Option<SummaryGrouping> {
+    match group_by {
+        GroupBy::Project => Some(SummaryGrouping::Projects),
+        GroupBy::Client => Some(SummaryGrouping::Clients),
+        GroupBy::Workspace | GroupBy::Task => None,
+    }
+}
+
+/// サマリーレポートの集計結果を、集計単位の名前ごとにまとめる。
+///
+/// タグのない時間は、集計方法が`TagMode::Untagged`の場合のみ`(no tag)`として含める。
+fn to_durations(summary: Vec<SummaryGroup>, tag_mode: TagMode) -> Durations {
+    summary.into_iter().fold(HashMap::new(), |mut acc, group| {
+        let durations: &mut GroupDurations = acc.entry(group.name).or_default();
+        durations.total += group.seconds;
+        let untagged = (tag_mode != TagMode::PerTag && group.untagged > 0)
+            .then(|| (UNTAGGED_KEY.to_string(), group.untagged));
+        group
+            .tags
+            .into_iter()
+            .chain(untagged)
+            .for_each(|(tag, duration)| {
+                *durations.tags.entry(tag).or_insert(0) += duration;
+            });
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Asia::Tokyo;
+    use rstest::rstest;
+
+    use super::summarize_by_month;
+    use super::SummaryArgs;
+    use crate::range::DateRange;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// 月ごとに集計し、月をまたぐタイムエントリーは月の境界で分割することを確認する。
+    ///
+    /// 切り詰めない場合は、開始した月にタイムエントリーの全体を集計する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, [3600 + 1800, 1800, 0])]
+    #[case::no_clip(true, [3600 + 3600, 0, 0])]
+    async fn test_summarize_by_month(#[case] no_clip: bool, #[case] expected: [i64; 3]) {
+        let entries = vec![
+            dummy_entry(1, tokyo_datetime(2024, 1, 10, 9), 3600, "project 1"),
+            dummy_entry(
+                2,
+                tokyo_datetime(2024, 1, 31, 23) + TimeDelta::minutes(30),
+                3600,
+                "project 1",
+            ),
+        ];
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_history_start().returning(|| None);
+        // 実際のAPIと同じく、取得する範囲に開始日時が含まれるタイムエントリーのみを返す
+        toggl
+            .expect_read_time_entries()
+            .returning(move |start_at, end_at| {
+                Ok(entries
+                    .iter()
+                    .filter(|entry| *start_at <= entry.start && entry.start < *end_at)
+                    .cloned()
+                    .collect())
+            });
+        let summary = SummaryArgs {
+            no_clip,
+            ..Default::default()
+        };
+        let reports = MockReportsRepository::new();
+        let range = DateRange::fiscal_year(2024, 1).unwrap();
+
+        let result = summarize_by_month(&toggl, &reports, &range, &summary, true, &Tokyo).await;
+
+        let monthly_durations = result.unwrap();
+        let totals: Vec<i64> = monthly_durations
+            .months
+            .iter()
+            .take(3)
+            .map(|(_, durations)| durations.get("project 1").map_or(0, |group| group.total))
+            .collect();
+        assert_eq!(expected.to_vec(), totals);
+        assert_eq!(
+            expected.iter().sum::<i64>(),
+            monthly_durations.total["project 1"].total
+        );
+    }
+
+    /// テスト用に東京の日時を作成する。
+    fn tokyo_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Tokyo
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(id: i64, start: DateTime<Utc>, duration: i64, project: &str) -> TimeEntry {
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + TimeDelta::seconds(duration)),
+            duration,
+            description: format!("entry{}", id),
+            project: Some(project.to_string()),
+            ..Default::default()
+        }
+    }
+}
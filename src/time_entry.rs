@@ -1,12 +1,62 @@
 use chrono::{DateTime, Utc};
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Default)]
 pub struct TimeEntry {
+    pub id: i64,
+    pub workspace_id: i64,
+    pub workspace: Option<String>,
+    pub user_id: i64,
     pub start: DateTime<Utc>,
     pub stop: Option<DateTime<Utc>>,
     pub duration: i64,
     pub description: String,
+    pub billable: bool,
+    /// 最終更新日時
+    pub at: DateTime<Utc>,
 
+    pub project_id: Option<i64>,
     pub project: Option<String>,
+    /// プロジェクトの表示色(`#rrggbb`形式)
+    pub project_color: Option<String>,
+    pub client_id: Option<i64>,
+    pub client: Option<String>,
+    pub task_id: Option<i64>,
+    pub task: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// タイムエントリーを開始日時の昇順に並べ替えたものを返す。
+///
+/// 開始日時が同じ場合は元の順序を維持する。
+pub fn sorted_by_start(time_entries: &[TimeEntry]) -> Vec<TimeEntry> {
+    let mut sorted_entries = time_entries.to_vec();
+    sorted_entries.sort_by_key(|entry| entry.start);
+
+    sorted_entries
+}
+
+/// 新規に作成するタイムエントリー。
+///
+/// `stop`が`None`の場合は実行中のタイムエントリーとして作成する。
+#[derive(Clone, PartialEq, Debug)]
+pub struct NewTimeEntry {
+    pub workspace_id: i64,
+    pub start: DateTime<Utc>,
+    pub stop: Option<DateTime<Utc>>,
+    pub description: String,
+    pub project_id: Option<i64>,
     pub tags: Vec<String>,
 }
+
+/// タイムエントリーの更新内容。
+///
+/// `None`の項目は更新しない。
+/// `project_id`を`Some(None)`にした場合は、プロジェクトを外す。
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TimeEntryUpdate {
+    pub start: Option<DateTime<Utc>>,
+    pub stop: Option<DateTime<Utc>>,
+    pub description: Option<String>,
+    pub project_id: Option<Option<i64>>,
+    pub tags: Option<Vec<String>>,
+}
@@ -1,21 +1,52 @@
-use std::{collections::HashMap, env};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    time::Duration,
+};
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use log::warn;
 #[cfg(test)]
 use mockall::automock;
-use reqwest::{header::CONTENT_TYPE, Client};
-use serde::Deserialize;
+use reqwest::{
+    header::{CONTENT_TYPE, RETRY_AFTER},
+    Certificate, Client, Method, Proxy, RequestBuilder, Response,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+use crate::config::ApiConfig;
+use crate::datetime;
+use crate::notice::notice;
+use crate::project::Project;
+use crate::retry::{
+    is_idempotent, is_retryable_status, parse_retry_after, RateLimiter, RetryPolicy,
+};
+use crate::time_entry::{NewTimeEntry, TimeEntry, TimeEntryUpdate};
+use crate::toggl_error::TogglError;
+
+mod reports;
+
 #[cfg(test)]
-use serde::Serialize;
+pub use reports::MockReportsRepository;
+pub use reports::{ReportsRepository, SummaryGroup, SummaryGrouping};
+
+/// タイムエントリー作成時に`created_with`として送信するアプリケーション名。
+const CREATED_WITH: &str = env!("CARGO_PKG_NAME");
 
-use crate::time_entry::TimeEntry;
+/// タイムエントリーの一覧取得で、1回のリクエストで返される最大件数。
+///
+/// この件数に達した場合は、返された最も古いタイムエントリーより前を続けて取得する。
+const TIME_ENTRIES_PAGE_LIMIT: usize = 1000;
 
 #[cfg_attr(test, automock)]
 /// Toggl APIと通信するためのリポジトリ。
 pub trait TogglRepository {
     /// 指定された日付のタイムエントリーを取得する。
     ///
+    /// 期間が長い場合は分割して取得し、IDで重複を除いた結果を返す。
+    ///
     /// # Arguments
     ///
     /// * `start_at` - 取得するタイムエントリーの開始日時
@@ -25,26 +56,139 @@ pub trait TogglRepository {
         start_at: &DateTime<Utc>,
         end_at: &DateTime<Utc>,
     ) -> Result<Vec<TimeEntry>>;
+
+    /// タイムエントリーの一覧を取得できる最も古い日時を返す。
+    ///
+    /// 取得できる過去の期間に制限がない場合は`None`を返す。
+    fn history_start(&self) -> Option<DateTime<Utc>>;
+
+    /// 実行中のタイムエントリーを取得する。
+    ///
+    /// 実行中のタイムエントリーが存在しない場合は`None`を返す。
+    async fn read_current_time_entry(&self) -> Result<Option<TimeEntry>>;
+
+    /// IDを指定してタイムエントリーを取得する。
+    ///
+    /// # Arguments
+    ///
+    /// * `time_entry_id` - 取得するタイムエントリーのID
+    async fn read_time_entry(&self, time_entry_id: i64) -> Result<TimeEntry>;
+
+    /// ユーザーが参加しているプロジェクトの一覧を取得する。
+    async fn read_projects(&self) -> Result<Vec<Project>>;
+
+    /// ユーザーのデフォルトワークスペースのIDを取得する。
+    async fn read_default_workspace_id(&self) -> Result<i64>;
+
+    /// ユーザーが参加している全てのワークスペースのIDを取得する。
+    async fn read_workspace_ids(&self) -> Result<Vec<i64>>;
+
+    /// タイムエントリーを作成する。
+    ///
+    /// `stop`が指定されていない場合は、実行中のタイムエントリーとして開始する。
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - 作成するタイムエントリー
+    async fn create_time_entry(&self, entry: &NewTimeEntry) -> Result<TimeEntry>;
+
+    /// 実行中のタイムエントリーを停止する。
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - タイムエントリーが所属するワークスペースのID
+    /// * `time_entry_id` - 停止するタイムエントリーのID
+    async fn stop_time_entry(&self, workspace_id: i64, time_entry_id: i64) -> Result<TimeEntry>;
+
+    /// タイムエントリーを更新する。
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - タイムエントリーが所属するワークスペースのID
+    /// * `time_entry_id` - 更新するタイムエントリーのID
+    /// * `update` - 更新内容
+    async fn update_time_entry(
+        &self,
+        workspace_id: i64,
+        time_entry_id: i64,
+        update: &TimeEntryUpdate,
+    ) -> Result<TimeEntry>;
+
+    /// タイムエントリーを削除する。
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - タイムエントリーが所属するワークスペースのID
+    /// * `time_entry_id` - 削除するタイムエントリーのID
+    async fn delete_time_entry(&self, workspace_id: i64, time_entry_id: i64) -> Result<()>;
 }
 
 /// Toggl APIと通信するためのクライアント。
 pub struct TogglClient {
     client: Client,
     api_url: String,
+    reports_url: String,
     api_token: String,
+    retry_policy: RetryPolicy,
+    // 並行して送信されるリクエストで共有する
+    rate_limiter: RateLimiter,
+    strict: bool,
+    // タイムエントリーの一覧を1回のリクエストで取得する最大の期間
+    max_range: TimeDelta,
+    // タイムエントリーの一覧を取得できる過去の期間
+    history_window: Option<TimeDelta>,
+    page_limit: usize,
+    // 名前解決に利用するリソースは、クライアントごとに1度だけ取得して使い回す
+    resources: OnceCell<TogglResources>,
 }
 
 impl TogglClient {
     /// 新しい`TogglClient`を返す。
     ///
     /// 環境変数`TOGGL_API_TOKEN`が設定されていない場合はエラーを返す。
-    pub fn new() -> Result<Self> {
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Toggl APIとの通信に関する設定
+    pub fn new(config: &ApiConfig) -> Result<Self> {
         let api_token = env::var("TOGGL_API_TOKEN").context("TOGGL_API_TOKEN must be set")?;
 
+        let mut builder = Client::builder();
+        if let Some(timeout_secs) = config.timeout_secs {
+            builder = builder.timeout(Duration::from_secs(timeout_secs));
+        }
+        if let Some(proxy) = &config.proxy {
+            let proxy =
+                Proxy::all(proxy).with_context(|| format!("Invalid proxy URL: {}", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(ca_cert) = &config.ca_cert {
+            let pem = std::fs::read(ca_cert).with_context(|| {
+                format!(
+                    "Failed to read CA certificate: {}",
+                    ca_cert.to_string_lossy()
+                )
+            })?;
+            let certificate = Certificate::from_pem(&pem).with_context(|| {
+                format!("Invalid CA certificate: {}", ca_cert.to_string_lossy())
+            })?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        let client = builder.build().context("Failed to build HTTP client")?;
+
         Ok(Self {
-            client: Client::new(),
-            api_url: "https://api.track.toggl.com/api/v9".to_string(),
+            client,
+            api_url: config.base_url.trim_end_matches('/').to_string(),
+            reports_url: config.reports_base_url.trim_end_matches('/').to_string(),
             api_token: api_token.to_string(),
+            retry_policy: RetryPolicy::from_config(config),
+            rate_limiter: RateLimiter::new(config.requests_per_second),
+            strict: config.strict,
+            max_range: TimeDelta::days(config.max_range_days.max(1).into()),
+            history_window: (config.history_days > 0)
+                .then(|| TimeDelta::days(config.history_days.into())),
+            page_limit: TIME_ENTRIES_PAGE_LIMIT,
+            resources: OnceCell::new(),
         })
     }
 }
@@ -55,45 +199,158 @@ impl TogglRepository for TogglClient {
         start_at: &DateTime<Utc>,
         end_at: &DateTime<Utc>,
     ) -> Result<Vec<TimeEntry>> {
-        let (request_entries, request_projects) = tokio::join!(
+        let (request_entries, request_resources) = tokio::join!(
             self.read_toggl_time_entries(start_at, end_at),
-            self.read_projects()
+            self.read_resources()
         );
         let toggl_time_entries =
             request_entries.context("Failed to get time entries from toggl")?;
-        let toggl_projects = request_projects.context("Failed to get project list from toggl")?;
-        // 複数回の検索を行う前提で、hashによる高速検索を行う
-        let toggl_projects_map: HashMap<i64, TogglProject> = toggl_projects
+        let resources = request_resources?;
+
+        Ok(to_time_entries(toggl_time_entries, resources, self.strict)?)
+    }
+
+    fn history_start(&self) -> Option<DateTime<Utc>> {
+        self.history_window
+            .map(|history_window| datetime::now() - history_window)
+    }
+
+    async fn read_current_time_entry(&self) -> Result<Option<TimeEntry>> {
+        let request = self.request(Method::GET, "/me/time_entries/current");
+
+        self.send_optional_time_entry(request)
+            .await
+            .context("Failed to get current time entry")
+    }
+
+    async fn read_time_entry(&self, time_entry_id: i64) -> Result<TimeEntry> {
+        let request = self.request(Method::GET, &format!("/me/time_entries/{}", time_entry_id));
+
+        self.send_time_entry(request)
+            .await
+            .with_context(|| format!("Failed to get time entry: {}", time_entry_id))
+    }
+
+    async fn read_projects(&self) -> Result<Vec<Project>> {
+        let projects = self
+            .read_toggl_projects()
+            .await
+            .context("Failed to get project list from toggl")?
             .into_iter()
-            .map(|project| (project.id, project))
+            .map(|project| Project {
+                id: project.id,
+                workspace_id: project.workspace_id,
+                name: project.name,
+            })
             .collect();
 
-        let time_entries = toggl_time_entries
+        Ok(projects)
+    }
+
+    async fn read_workspace_ids(&self) -> Result<Vec<i64>> {
+        let workspaces = self.read_list::<TogglWorkspace>("/me/workspaces").await?;
+
+        Ok(workspaces
             .into_iter()
-            .map(|entry| {
-                let start = DateTime::parse_from_rfc3339(&entry.start).unwrap().to_utc();
-                let stop = entry
-                    .stop
-                    .map(|stop| DateTime::parse_from_rfc3339(&stop).unwrap().to_utc());
-                let project = match entry.project_id {
-                    Some(project_id) => toggl_projects_map
-                        .get(&project_id)
-                        .map(|project| project.name.clone()),
-                    None => None,
-                };
+            .map(|workspace| workspace.id)
+            .collect())
+    }
 
-                TimeEntry {
-                    start,
-                    stop,
-                    duration: entry.duration,
-                    description: entry.description,
-                    project,
-                    tags: entry.tags,
-                }
-            })
-            .collect();
+    async fn read_default_workspace_id(&self) -> Result<i64> {
+        let me = self
+            .send_json::<TogglMe>(self.request(Method::GET, "/me"), &self.api_url)
+            .await?;
+
+        Ok(me.default_workspace_id)
+    }
+
+    async fn create_time_entry(&self, entry: &NewTimeEntry) -> Result<TimeEntry> {
+        let body = TogglNewTimeEntry {
+            created_with: CREATED_WITH,
+            workspace_id: entry.workspace_id,
+            description: &entry.description,
+            project_id: entry.project_id,
+            tags: &entry.tags,
+            start: to_toggl_datetime(&entry.start),
+            stop: entry.stop.as_ref().map(to_toggl_datetime),
+            // 実行中のタイムエントリーは負の値を指定する
+            duration: entry
+                .stop
+                .map(|stop| (stop - entry.start).num_seconds())
+                .unwrap_or(-1),
+        };
+        let request = self
+            .request(
+                Method::POST,
+                &format!("/workspaces/{}/time_entries", entry.workspace_id),
+            )
+            .json(&body);
+
+        self.send_time_entry(request)
+            .await
+            .context("Failed to create time entry")
+    }
+
+    async fn stop_time_entry(&self, workspace_id: i64, time_entry_id: i64) -> Result<TimeEntry> {
+        let request = self.request(
+            Method::PATCH,
+            &format!(
+                "/workspaces/{}/time_entries/{}/stop",
+                workspace_id, time_entry_id
+            ),
+        );
+
+        self.send_time_entry(request)
+            .await
+            .with_context(|| format!("Failed to stop time entry: {}", time_entry_id))
+    }
+
+    async fn update_time_entry(
+        &self,
+        workspace_id: i64,
+        time_entry_id: i64,
+        update: &TimeEntryUpdate,
+    ) -> Result<TimeEntry> {
+        let body = TogglTimeEntryUpdate {
+            description: update.description.as_deref(),
+            project_id: update.project_id,
+            tags: update.tags.as_deref(),
+            start: update.start.as_ref().map(to_toggl_datetime),
+            stop: update.stop.as_ref().map(to_toggl_datetime),
+            // 開始と終了が両方指定されている場合は、経過時間も合わせて更新する
+            duration: update
+                .start
+                .zip(update.stop)
+                .map(|(start, stop)| (stop - start).num_seconds()),
+        };
+        let request = self
+            .request(
+                Method::PUT,
+                &format!(
+                    "/workspaces/{}/time_entries/{}",
+                    workspace_id, time_entry_id
+                ),
+            )
+            .json(&body);
+
+        self.send_time_entry(request)
+            .await
+            .with_context(|| format!("Failed to update time entry: {}", time_entry_id))
+    }
+
+    async fn delete_time_entry(&self, workspace_id: i64, time_entry_id: i64) -> Result<()> {
+        let request = self.request(
+            Method::DELETE,
+            &format!(
+                "/workspaces/{}/time_entries/{}",
+                workspace_id, time_entry_id
+            ),
+        );
+        self.send(request, &self.api_url)
+            .await
+            .with_context(|| format!("Failed to delete time entry: {}", time_entry_id))?;
 
-        Ok(time_entries)
+        Ok(())
     }
 }
 
@@ -101,112 +358,712 @@ impl TogglRepository for TogglClient {
 #[derive(Debug, Deserialize)]
 #[cfg_attr(test, derive(Serialize))]
 struct TogglTimeEntry {
+    id: i64,
+    workspace_id: i64,
+    user_id: i64,
     description: String,
     project_id: Option<i64>,
+    task_id: Option<i64>,
+    billable: bool,
     start: String,
     stop: Option<String>,
     duration: i64,
+    at: String,
     tags: Vec<String>,
+    #[serde(default)]
+    tag_ids: Vec<i64>,
 }
 
 /// Toggl APIのプロジェクト情報をデシリアライズするための構造体。
 #[derive(Debug, Deserialize)]
 #[cfg_attr(test, derive(Serialize))]
 struct TogglProject {
+    id: i64,
+    workspace_id: i64,
+    client_id: Option<i64>,
+    name: String,
+    color: String,
+}
+
+/// Toggl APIのクライアント情報をデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TogglClientInfo {
+    id: i64,
+    name: String,
+}
+
+/// Toggl APIのワークスペース情報をデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TogglWorkspace {
+    id: i64,
+    name: String,
+}
+
+/// Toggl APIのタグ情報をデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TogglTag {
     id: i64,
     name: String,
 }
 
+/// Toggl APIのタスク情報をデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TogglTask {
+    id: i64,
+    name: String,
+}
+
+/// タイムエントリーの名前解決に利用するToggl APIのリソース。
+#[derive(Debug, Default)]
+struct TogglResources {
+    projects: Vec<TogglProject>,
+    clients: Vec<TogglClientInfo>,
+    workspaces: Vec<TogglWorkspace>,
+    tags: Vec<TogglTag>,
+    tasks: Vec<TogglTask>,
+}
+
+/// Toggl APIのユーザー情報をデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct TogglMe {
+    default_workspace_id: i64,
+}
+
+/// タイムエントリー作成時のリクエストボディ。
+#[derive(Debug, Serialize)]
+struct TogglNewTimeEntry<'a> {
+    created_with: &'a str,
+    workspace_id: i64,
+    description: &'a str,
+    project_id: Option<i64>,
+    tags: &'a [String],
+    start: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<String>,
+    duration: i64,
+}
+
+/// タイムエントリー更新時のリクエストボディ。
+///
+/// 指定されていない項目は送信しない。
+/// プロジェクトを外す場合は、`project_id`に`null`を送信する。
+#[derive(Debug, Serialize)]
+struct TogglTimeEntryUpdate<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_id: Option<Option<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<i64>,
+}
+
 impl TogglClient {
+    /// 認証情報を設定したリクエストを作成する。
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.request_url(method, format!("{}{}", self.api_url, path))
+    }
+
+    /// URLを指定して、認証情報を設定したリクエストを作成する。
+    fn request_url(&self, method: Method, url: String) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .basic_auth(&self.api_token, Some("api_token"))
+            .header(CONTENT_TYPE, "application/json")
+    }
+
+    /// リクエストを送信し、成功したレスポンスを返す。
+    ///
+    /// サーバーエラー(5xx)やタイムアウトの場合は、冪等なメソッドのみ再送する。
+    async fn send(&self, request: RequestBuilder, base_url: &str) -> Result<Response, TogglError> {
+        self.send_with_retry(request, base_url, false).await
+    }
+
+    /// 読み取りのみのリクエストを送信し、成功したレスポンスを返す。
+    ///
+    /// 検索などでPOSTを利用する読み取りのリクエストも、サーバーエラー(5xx)やタイムアウトの場合に再送する。
+    async fn send_read(
+        &self,
+        request: RequestBuilder,
+        base_url: &str,
+    ) -> Result<Response, TogglError> {
+        self.send_with_retry(request, base_url, true).await
+    }
+
+    /// リクエストを送信し、成功したレスポンスを返す。
+    ///
+    /// レート制限(429)や接続の失敗の場合は、待機してから再送する。
+    /// サーバーエラー(5xx)やタイムアウトの場合は、リクエストが処理された可能性があるため、
+    /// 冪等なメソッドか再送しても安全と指定されたリクエストのみ再送する。
+    /// 待機時間は`Retry-After`ヘッダーがあればそれに従い、なければ指数的に増やす。
+    /// 通信に失敗した場合のエラーには、リクエストを送信したAPIのベースURLを含める。
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - 送信するリクエスト
+    /// * `base_url` - リクエストを送信するAPIのベースURL
+    /// * `retry_safe` - メソッドによらず、複数回送信しても結果が変わらないリクエストか
+    async fn send_with_retry(
+        &self,
+        request: RequestBuilder,
+        base_url: &str,
+        retry_safe: bool,
+    ) -> Result<Response, TogglError> {
+        let request = request.build().map_err(|source| TogglError::Transport {
+            url: base_url.to_string(),
+            source,
+        })?;
+        let retry_safe = retry_safe || is_idempotent(request.method());
+        let mut attempt = 1;
+        loop {
+            // ストリームをボディに持つなど複製できないリクエストは再送しない
+            let Some(current_request) = request.try_clone() else {
+                self.rate_limiter.acquire().await;
+                return self
+                    .check_response(self.client.execute(request).await, base_url)
+                    .await;
+            };
+            self.rate_limiter.acquire().await;
+            let result = self.client.execute(current_request).await;
+
+            let (retryable, retry_after) = match &result {
+                Ok(response) => (
+                    is_retryable_status(response.status(), retry_safe),
+                    response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| parse_retry_after(value, &datetime::now())),
+                ),
+                Err(err) => (err.is_connect() || (err.is_timeout() && retry_safe), None),
+            };
+            if !retryable || attempt >= self.retry_policy.max_attempts {
+                return self.check_response(result, base_url).await;
+            }
+
+            let delay = self.retry_policy.delay(attempt, retry_after);
+            match &result {
+                Ok(response) => warn!(
+                    "Toggl API returned {}, retrying in {:?} (attempt {}/{})",
+                    response.status(),
+                    delay,
+                    attempt,
+                    self.retry_policy.max_attempts
+                ),
+                Err(err) => warn!(
+                    "Failed to send request to Toggl API: {}, retrying in {:?} (attempt {}/{})",
+                    err, delay, attempt, self.retry_policy.max_attempts
+                ),
+            }
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// レスポンスのステータスを確認し、失敗した場合は原因に応じたエラーを返す。
+    async fn check_response(
+        &self,
+        result: reqwest::Result<Response>,
+        base_url: &str,
+    ) -> Result<Response, TogglError> {
+        let response = result.map_err(|source| TogglError::Transport {
+            url: base_url.to_string(),
+            source,
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        // 原因を調べられるよう、エラーにレスポンスのボディを含める
+        let body = response.text().await.unwrap_or_default();
+        Err(TogglError::from_status(status, body))
+    }
+
+    /// リクエストを送信し、レスポンスのボディをデシリアライズして返す。
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        base_url: &str,
+    ) -> Result<T, TogglError> {
+        let response = self.send(request, base_url).await?;
+        self.read_json(response, base_url).await
+    }
+
+    /// 読み取りのみのリクエストを送信し、レスポンスのボディをデシリアライズして返す。
+    async fn send_read_json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+        base_url: &str,
+    ) -> Result<T, TogglError> {
+        let response = self.send_read(request, base_url).await?;
+        self.read_json(response, base_url).await
+    }
+
+    /// レスポンスのボディをデシリアライズして返す。
+    async fn read_json<T: DeserializeOwned>(
+        &self,
+        response: Response,
+        base_url: &str,
+    ) -> Result<T, TogglError> {
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|source| TogglError::Transport {
+                url: base_url.to_string(),
+                source,
+            })?;
+
+        serde_json::from_str(&body).map_err(|source| TogglError::Decode {
+            status,
+            body,
+            source,
+        })
+    }
+
+    /// 単一のタイムエントリーを返すリクエストを送信し、プロジェクト名を解決したタイムエントリーを返す。
+    async fn send_time_entry(&self, request: RequestBuilder) -> Result<TimeEntry> {
+        self.send_optional_time_entry(request)
+            .await?
+            .context("Toggl API returned no time entry")
+    }
+
+    /// 単一のタイムエントリーを返すリクエストを送信する。
+    ///
+    /// レスポンスが`null`の場合は、名前を解決せずに`None`を返す。
+    /// 応答を速くするため、名前解決に利用するリソースを取得済みでなければ、プロジェクトのみを取得して解決する。
+    async fn send_optional_time_entry(&self, request: RequestBuilder) -> Result<Option<TimeEntry>> {
+        let Some(toggl_time_entry) = self
+            .send_json::<Option<TogglTimeEntry>>(request, &self.api_url)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let projects_only;
+        let resources = match self.resources.get() {
+            Some(resources) => resources,
+            None => {
+                projects_only = TogglResources {
+                    projects: self
+                        .read_toggl_projects()
+                        .await
+                        .context("Failed to get project list from toggl")?,
+                    ..Default::default()
+                };
+                &projects_only
+            }
+        };
+
+        // 明示的に取得したタイムエントリーは読み飛ばさず、変換できない場合はエラーとする
+        Ok(to_time_entries(vec![toggl_time_entry], resources, true)?.pop())
+    }
+
     // Time entryを取得する。
+    //
+    // 取得できる過去の期間に収まるよう開始日時を調整し、最大の期間ごとに分割して取得する。
     async fn read_toggl_time_entries(
         &self,
         start_at: &DateTime<Utc>,
         end_at: &DateTime<Utc>,
     ) -> Result<Vec<TogglTimeEntry>> {
-        let entries = self
-            .client
-            .get(format!("{}/me/time_entries", self.api_url))
-            .basic_auth(&self.api_token, Some("api_token"))
-            .header(CONTENT_TYPE, "application/json")
-            .query(&[
-                ("start_date", start_at.to_rfc3339()),
-                ("end_date", end_at.to_rfc3339()),
-            ])
-            .send()
-            .await
-            .with_context(|| format!("Failed to send request to Toggl API at {}", self.api_url))?
-            .error_for_status()
-            .context("Request returned an error status")?
-            .json::<Vec<TogglTimeEntry>>()
-            .await
-            .context("Failed to deserialize response")?;
+        let start_at = match self.excluded_by_history_window(start_at, end_at) {
+            Some((excluded_start, excluded_end)) => {
+                // 集計結果が不足するため、ログレベルによらず表示する
+                notice(&format!(
+                    "Toggl API only returns time entries from the last {} days; entries from {} to {} are not included. Use --source reports to aggregate older periods, or change history_days in the config file",
+                    self.history_window.unwrap_or_default().num_days(),
+                    excluded_start,
+                    excluded_end
+                ));
+                excluded_end
+            }
+            None => *start_at,
+        };
+
+        let mut ids = HashSet::new();
+        let mut entries = Vec::new();
+        for (chunk_start, chunk_end) in split_range(&start_at, end_at, self.max_range) {
+            let chunk = self
+                .read_toggl_time_entries_pages(&chunk_start, &chunk_end)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to get time entries from {} to {}",
+                        chunk_start, chunk_end
+                    )
+                })?;
+            // 分割やページの境界にあるタイムエントリーは重複して取得されるため、IDで重複を除く
+            entries.extend(chunk.into_iter().filter(|entry| ids.insert(entry.id)));
+        }
 
         Ok(entries)
     }
 
+    /// 期間内のタイムエントリーを、件数の上限に達しなくなるまで古い方向へ続けて取得する。
+    async fn read_toggl_time_entries_pages(
+        &self,
+        start_at: &DateTime<Utc>,
+        end_at: &DateTime<Utc>,
+    ) -> Result<Vec<TogglTimeEntry>> {
+        let mut entries = Vec::new();
+        let mut page_end = *end_at;
+        loop {
+            let request = self.request(Method::GET, "/me/time_entries").query(&[
+                ("start_date", start_at.to_rfc3339()),
+                ("end_date", page_end.to_rfc3339()),
+            ]);
+            let page = self
+                .send_json::<Vec<TogglTimeEntry>>(request, &self.api_url)
+                .await?;
+            let is_full = page.len() >= self.page_limit;
+            let earliest_start = page
+                .iter()
+                .filter_map(|entry| DateTime::parse_from_rfc3339(&entry.start).ok())
+                .map(|start| start.to_utc())
+                .min();
+            entries.extend(page);
+            if !is_full {
+                return Ok(entries);
+            }
+
+            // 同じ開始日時のタイムエントリーを取りこぼさないよう、最も古い開始日時を含めて次を取得する
+            let next_end = earliest_start.map(|start| start + TimeDelta::seconds(1));
+            match next_end {
+                Some(next_end) if next_end < page_end => page_end = next_end,
+                _ => {
+                    warn!(
+                        "Time entries from {} to {} may be incomplete: more than {} entries were returned",
+                        start_at, page_end, self.page_limit
+                    );
+                    return Ok(entries);
+                }
+            }
+        }
+    }
+
+    /// 期間のうち、取得できる過去の期間より前のため取得できない部分を返す。
+    ///
+    /// 期間の全体が取得できる過去の期間に収まる場合は`None`を返す。
+    fn excluded_by_history_window(
+        &self,
+        start_at: &DateTime<Utc>,
+        end_at: &DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let oldest = self.history_start()?;
+        if *start_at >= oldest {
+            return None;
+        }
+
+        Some((*start_at, oldest.min(*end_at)))
+    }
+
     /// プロジェクト情報を取得する。
-    async fn read_projects(&self) -> Result<Vec<TogglProject>> {
-        let projects = self
-            .client
-            .get(format!("{}/me/projects", self.api_url))
-            .basic_auth(&self.api_token, Some("api_token"))
-            .header(CONTENT_TYPE, "application/json")
-            .send()
-            .await
-            .with_context(|| format!("Failed to send request to Toggl API at {}", self.api_url))?
-            .error_for_status()
-            .context("Request returned an error status")?
-            .json::<Vec<TogglProject>>()
+    async fn read_toggl_projects(&self) -> Result<Vec<TogglProject>> {
+        self.read_list("/me/projects").await
+    }
+
+    /// タイムエントリーの名前解決に利用するリソースをまとめて取得する。
+    ///
+    /// 2回目以降は、最初に取得したリソースを返す。
+    async fn read_resources(&self) -> Result<&TogglResources> {
+        self.resources
+            .get_or_try_init(|| self.fetch_resources())
             .await
-            .context("Failed to deserialize response")?;
+    }
 
-        Ok(projects)
+    /// タイムエントリーの名前解決に利用するリソースをToggl APIから取得する。
+    async fn fetch_resources(&self) -> Result<TogglResources> {
+        let (projects, clients, workspaces, tags, tasks) = tokio::join!(
+            self.read_toggl_projects(),
+            self.read_list::<TogglClientInfo>("/me/clients"),
+            self.read_list::<TogglWorkspace>("/me/workspaces"),
+            self.read_list::<TogglTag>("/me/tags"),
+            self.read_list::<TogglTask>("/me/tasks"),
+        );
+
+        Ok(TogglResources {
+            projects: projects.context("Failed to get project list from toggl")?,
+            clients: clients.context("Failed to get client list from toggl")?,
+            workspaces: workspaces.context("Failed to get workspace list from toggl")?,
+            tags: tags.context("Failed to get tag list from toggl")?,
+            tasks: tasks.context("Failed to get task list from toggl")?,
+        })
+    }
+
+    /// 一覧を返すリソースを取得する。
+    ///
+    /// Toggl APIは一覧が空の場合に`null`を返すことがあるため、空の一覧として扱う。
+    async fn read_list<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>> {
+        let list = self
+            .send_json::<Option<Vec<T>>>(self.request(Method::GET, path), &self.api_url)
+            .await?;
+
+        Ok(list.unwrap_or_default())
+    }
+}
+
+/// Toggl APIのタイムエントリーを、名前を解決した`TimeEntry`に変換する。
+///
+/// 変換できないタイムエントリーがある場合、`strict`が`true`であればエラーを返し、`false`であれば警告を出力して読み飛ばす。
+fn to_time_entries(
+    toggl_time_entries: Vec<TogglTimeEntry>,
+    resources: &TogglResources,
+    strict: bool,
+) -> Result<Vec<TimeEntry>, TogglError> {
+    // 複数回の検索を行う前提で、hashによる高速検索を行う
+    let projects_map: HashMap<i64, &TogglProject> = resources
+        .projects
+        .iter()
+        .map(|project| (project.id, project))
+        .collect();
+    let clients_map: HashMap<i64, &str> = resources
+        .clients
+        .iter()
+        .map(|client| (client.id, client.name.as_str()))
+        .collect();
+    let workspaces_map: HashMap<i64, &str> = resources
+        .workspaces
+        .iter()
+        .map(|workspace| (workspace.id, workspace.name.as_str()))
+        .collect();
+    let tags_map: HashMap<i64, &str> = resources
+        .tags
+        .iter()
+        .map(|tag| (tag.id, tag.name.as_str()))
+        .collect();
+    let tasks_map: HashMap<i64, &str> = resources
+        .tasks
+        .iter()
+        .map(|task| (task.id, task.name.as_str()))
+        .collect();
+
+    let mut time_entries = Vec::with_capacity(toggl_time_entries.len());
+    for mut entry in toggl_time_entries {
+        let tag_ids = std::mem::take(&mut entry.tag_ids);
+        let mut time_entry = match TimeEntry::try_from(entry) {
+            Ok(time_entry) => time_entry,
+            Err(err) if strict => return Err(err),
+            Err(err) => {
+                warn!("Skipped invalid time entry: {}", err);
+                continue;
+            }
+        };
+
+        let project = time_entry
+            .project_id
+            .and_then(|project_id| projects_map.get(&project_id));
+        time_entry.workspace = workspaces_map
+            .get(&time_entry.workspace_id)
+            .map(|name| name.to_string());
+        time_entry.project = project.map(|project| project.name.clone());
+        time_entry.project_color = project.map(|project| project.color.clone());
+        time_entry.client_id = project.and_then(|project| project.client_id);
+        time_entry.client = time_entry
+            .client_id
+            .and_then(|client_id| clients_map.get(&client_id))
+            .map(|name| name.to_string());
+        time_entry.task = time_entry
+            .task_id
+            .and_then(|task_id| tasks_map.get(&task_id))
+            .map(|name| name.to_string());
+        // タグ名の変更に追従するため、全てのタグIDが解決できる場合はIDから名前を解決する
+        let tag_names: Option<Vec<String>> = tag_ids
+            .iter()
+            .map(|tag_id| tags_map.get(tag_id).map(|name| name.to_string()))
+            .collect();
+        if let Some(tag_names) = tag_names.filter(|tag_names| !tag_names.is_empty()) {
+            time_entry.tags = tag_names;
+        }
+
+        time_entries.push(time_entry);
     }
+
+    Ok(time_entries)
+}
+
+impl TryFrom<TogglTimeEntry> for TimeEntry {
+    type Error = TogglError;
+
+    /// Toggl APIのタイムエントリーを、名前を解決していない`TimeEntry`に変換する。
+    ///
+    /// 日時を解釈できない場合は、タイムエントリーのIDを含むエラーを返す。
+    fn try_from(entry: TogglTimeEntry) -> Result<Self, Self::Error> {
+        let parse = |field: &'static str, value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|datetime| datetime.to_utc())
+                .map_err(|source| TogglError::InvalidTimeEntry {
+                    id: entry.id,
+                    field,
+                    value: value.to_string(),
+                    source,
+                })
+        };
+        let start = parse("start", &entry.start)?;
+        let stop = entry
+            .stop
+            .as_deref()
+            .map(|stop| parse("stop", stop))
+            .transpose()?;
+        let at = parse("at", &entry.at)?;
+
+        Ok(TimeEntry {
+            id: entry.id,
+            workspace_id: entry.workspace_id,
+            user_id: entry.user_id,
+            start,
+            stop,
+            duration: entry.duration,
+            description: entry.description,
+            billable: entry.billable,
+            at,
+            project_id: entry.project_id,
+            task_id: entry.task_id,
+            tags: entry.tags,
+            ..Default::default()
+        })
+    }
+}
+
+/// 期間を最大の長さごとに分割する。
+///
+/// 開始日時が終了日時以降の場合は空の一覧を返す。
+fn split_range(
+    start_at: &DateTime<Utc>,
+    end_at: &DateTime<Utc>,
+    max_range: TimeDelta,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut ranges = Vec::new();
+    let mut chunk_start = *start_at;
+    while chunk_start < *end_at {
+        let chunk_end = (chunk_start + max_range).min(*end_at);
+        ranges.push((chunk_start, chunk_end));
+        chunk_start = chunk_end;
+    }
+
+    ranges
+}
+
+/// Toggl APIに送信する日時の文字列に変換する。
+fn to_toggl_datetime(datetime: &DateTime<Utc>) -> String {
+    datetime.to_rfc3339_opts(SecondsFormat::Secs, true)
 }
 
 #[cfg(test)]
 mod tests {
     use std::env;
+    use std::path::PathBuf;
     use std::sync::Mutex;
     use std::vec;
 
+    use super::split_range;
     use super::TogglClient;
     use super::TogglProject;
     use super::TogglRepository;
     use super::TogglTimeEntry;
-    use crate::time_entry::TimeEntry;
+    use super::TIME_ENTRIES_PAGE_LIMIT;
+    use crate::config::ApiConfig;
+    use crate::datetime::mock_datetime;
+    use crate::notice::mock_notice;
+    use crate::project::Project;
+    use crate::retry::{RateLimiter, RetryPolicy};
+    use crate::time_entry::{NewTimeEntry, TimeEntry, TimeEntryUpdate};
+    use crate::toggl_error::TogglError;
     use anyhow::Result;
     use base64::prelude::*;
-    use chrono::DateTime;
-    use mockito::Server;
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use mockito::{Mock, Server, ServerGuard};
     use once_cell::sync::Lazy;
     use rstest::rstest;
+    use tokio::sync::OnceCell;
 
     // 環境変数を書き換えるときに並行処理した場合用のmutex
     static ENV_MUTEX: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 
     impl TogglClient {
-        fn new_test(url: &str, api_token: &str) -> Result<Self> {
+        pub(super) fn new_test(url: &str, api_token: &str) -> Result<Self> {
             Ok(Self {
                 client: reqwest::Client::new(),
                 api_url: url.to_string(),
+                reports_url: url.to_string(),
                 api_token: api_token.to_string(),
+                retry_policy: RetryPolicy::no_retry(),
+                rate_limiter: RateLimiter::new(0),
+                strict: false,
+                max_range: TimeDelta::days(31),
+                history_window: None,
+                page_limit: TIME_ENTRIES_PAGE_LIMIT,
+                resources: OnceCell::new(),
             })
         }
+
+        /// 再送の方針を指定したテスト用のクライアントを作成する。
+        pub(super) fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+            Self {
+                retry_policy,
+                ..self
+            }
+        }
     }
 
     // clientを新規作成した場合に正常に作成できることを確認するテスト
     #[test]
     fn test_new_toggl_client() {
-        let client = with_env_var("TOGGL_API_TOKEN", Some("test_token"), TogglClient::new);
+        let client = with_env_var("TOGGL_API_TOKEN", Some("test_token"), || {
+            TogglClient::new(&ApiConfig::default())
+        });
         assert!(client.is_ok());
     }
 
+    // 設定に応じてclientを新規作成できることを確認するテスト
+    #[rstest]
+    #[case::custom_url(ApiConfig {
+        base_url: "http://localhost:8080/api/v9/".to_string(),
+        timeout_secs: Some(5),
+        proxy: Some("http://proxy.example.com:3128".to_string()),
+        ca_cert: None,
+        ..Default::default()
+    })]
+    fn test_new_toggl_client_with_config(#[case] config: ApiConfig) {
+        let client = with_env_var("TOGGL_API_TOKEN", Some("test_token"), || {
+            TogglClient::new(&config)
+        });
+        assert_eq!("http://localhost:8080/api/v9", client.unwrap().api_url);
+    }
+
+    // 設定が不正な場合にclientの作成がエラーすることを確認するテスト
+    #[rstest]
+    #[case::invalid_proxy(ApiConfig {
+        proxy: Some("not a url".to_string()),
+        ..Default::default()
+    })]
+    #[case::missing_ca_cert(ApiConfig {
+        ca_cert: Some(PathBuf::from("/path/to/missing/ca.pem")),
+        ..Default::default()
+    })]
+    fn test_new_toggl_client_invalid_config(#[case] config: ApiConfig) {
+        let client = with_env_var("TOGGL_API_TOKEN", Some("test_token"), || {
+            TogglClient::new(&config)
+        });
+        assert!(client.is_err());
+    }
+
     // clientを新規作成したときに環境変数が設定されていなくてエラーすることを確認するテスト
     #[test]
     fn test_new_toggl_client_error() {
-        let client = with_env_var("TOGGL_API_TOKEN", None, TogglClient::new);
+        let client = with_env_var("TOGGL_API_TOKEN", None, || {
+            TogglClient::new(&ApiConfig::default())
+        });
         assert!(client.is_err());
     }
 
@@ -238,6 +1095,7 @@ mod tests {
         // モックサーバーの起動
         let mut server = Server::new_async().await;
         let url = server.url();
+        let _resources = mock_resources(&mut server).await;
         let authorization = format!(
             "Basic {}",
             BASE64_STANDARD.encode(format!("{}:api_token", api_token))
@@ -353,10 +1211,1321 @@ mod tests {
         assert!(result.is_err());
     }
 
-    // 環境変数を一時的に変更するヘルパー関数
-    fn with_env_var<T>(key: &str, value: Option<&str>, test: impl FnOnce() -> T) -> T {
-        let _lock = ENV_MUTEX.lock().unwrap();
-        let original_value = env::var(key).ok();
+    // 一時的なエラーの後に再送して成功する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::server_error(503, 2, None)]
+    #[case::rate_limited(429, 2, Some("1"))]
+    #[case::rate_limited_without_retry_after(429, 1, None)]
+    #[case::max_attempts(500, 2, None)]
+    async fn test_retry_then_succeed(
+        #[case] error_code: usize,
+        #[case] failures: usize,
+        #[case] retry_after: Option<&str>,
+    ) {
+        // モックサーバーの起動
+        // 先に作成したモックが期待回数に達するまで優先される
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let mut failure = server.mock("GET", "/me").with_status(error_code);
+        if let Some(retry_after) = retry_after {
+            failure = failure.with_header("Retry-After", retry_after);
+        }
+        let failure = failure.expect(failures).create_async().await;
+        let success = server
+            .mock("GET", "/me")
+            .with_status(200)
+            .with_body(r#"{"default_workspace_id": 10}"#)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test")
+            .unwrap()
+            .with_retry_policy(test_retry_policy(3));
+        let result = client.read_default_workspace_id().await;
+        failure.assert_async().await;
+        success.assert_async().await;
+        assert_eq!(10, result.unwrap());
+    }
+
+    // 再送しても失敗し続ける場合は、最大試行回数で諦めることを確認するテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::server_error(503, 3, 3)]
+    #[case::rate_limited(429, 2, 2)]
+    #[case::no_retry(503, 1, 1)]
+    #[case::client_error(400, 3, 1)]
+    #[case::unauthorized(401, 3, 1)]
+    async fn test_retry_exhausted(
+        #[case] error_code: usize,
+        #[case] max_attempts: u32,
+        #[case] expected_requests: usize,
+    ) {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock("GET", "/me")
+            .with_status(error_code)
+            .expect(expected_requests)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test")
+            .unwrap()
+            .with_retry_policy(test_retry_policy(max_attempts));
+        let result = client.read_default_workspace_id().await;
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // タイムエントリーの作成がサーバーエラーとなった場合は、二重に作成しないよう再送しないことを確認するテスト
+    #[tokio::test]
+    async fn test_create_time_entry_not_retried_on_server_error() {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("POST", "/workspaces/10/time_entries")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test")
+            .unwrap()
+            .with_retry_policy(test_retry_policy(3));
+        let new_entry = NewTimeEntry {
+            workspace_id: 10,
+            start: Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap(),
+            stop: None,
+            description: "test".to_string(),
+            project_id: None,
+            tags: vec![],
+        };
+        let result = client.create_time_entry(&new_entry).await;
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // タイムエントリーの停止がサーバーエラーとなった場合は、停止日時を変えないよう再送しないことを確認するテスト
+    #[tokio::test]
+    async fn test_stop_time_entry_not_retried_on_server_error() {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("PATCH", "/workspaces/10/time_entries/1/stop")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test")
+            .unwrap()
+            .with_retry_policy(test_retry_policy(3));
+        let result = client.stop_time_entry(10, 1).await;
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // エラーステータスの場合に、ステータスとボディを含む種類に応じたエラーとなることを確認するテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::unauthorized(401, 3)]
+    #[case::forbidden(403, 4)]
+    #[case::not_found(404, 5)]
+    #[case::rate_limited(429, 6)]
+    #[case::server_error(500, 7)]
+    #[case::bad_request(400, 8)]
+    async fn test_typed_error_status(#[case] error_code: usize, #[case] exit_code: u8) {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock("GET", "/me")
+            .with_status(error_code)
+            .with_body("error detail")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client.read_default_workspace_id().await;
+        m1.assert_async().await;
+        let error = result.unwrap_err();
+        let toggl_error = error.downcast_ref::<TogglError>().unwrap();
+        assert_eq!(exit_code, toggl_error.exit_code());
+        assert!(toggl_error.to_string().contains(&error_code.to_string()));
+        assert!(toggl_error.to_string().contains("error detail"));
+    }
+
+    // レスポンスの形式が想定と異なる場合に、デシリアライズのエラーとなることを確認するテスト
+    #[tokio::test]
+    async fn test_typed_error_decode() {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock("GET", "/me")
+            .with_status(200)
+            .with_body(r#"{"default_workspace": "10"}"#)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client.read_default_workspace_id().await;
+        m1.assert_async().await;
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TogglError>(),
+            Some(TogglError::Decode { body, .. }) if body.contains("default_workspace")
+        ));
+    }
+
+    // 接続できない場合に、通信のエラーとなることを確認するテスト
+    #[tokio::test]
+    async fn test_typed_error_transport() {
+        // 接続を受け付けないポートを利用する
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client.read_default_workspace_id().await;
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TogglError>(),
+            Some(TogglError::Transport { .. })
+        ));
+    }
+
+    // 名前解決のリソース取得に失敗した場合も、文脈を付与したエラーから種類を判別できることを確認するテスト
+    #[tokio::test]
+    async fn test_typed_error_with_context() {
+        let start_at = DateTime::parse_from_rfc3339("2024-01-02T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let end_at = DateTime::parse_from_rfc3339("2024-01-03T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _m1 = server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .create_async()
+            .await;
+        let _resources = mock_resources(&mut server).await;
+        let _m2 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client.read_time_entries(&start_at, &end_at).await;
+        let error = result.unwrap_err();
+        let toggl_error = error
+            .chain()
+            .find_map(|e| e.downcast_ref::<TogglError>())
+            .unwrap();
+        assert!(matches!(toggl_error, TogglError::Forbidden { .. }));
+    }
+
+    // Toggl Time EntryからTime Entryに変換できることを確認するテスト
+    #[rstest]
+    #[case::normal(dummy_time_entry(1))]
+    #[case::running(dummy_time_entry(3))]
+    fn test_try_from_time_entry(#[case] entry: TogglTimeEntry) {
+        let expected = TimeEntry {
+            project: None,
+            project_color: None,
+            client_id: None,
+            client: None,
+            workspace: None,
+            task: None,
+            ..to_time_entry(&entry, &[])
+        };
+
+        let result = TimeEntry::try_from(entry);
+
+        assert_eq!(expected, result.unwrap());
+    }
+
+    // 日時を解釈できない場合に、IDと項目を含むエラーとなることを確認するテスト
+    #[rstest]
+    #[case::start(TogglTimeEntry { start: "2024-01-02 01:02:03".to_string(), ..dummy_time_entry(1) }, "start")]
+    #[case::stop(TogglTimeEntry { stop: Some("".to_string()), ..dummy_time_entry(1) }, "stop")]
+    #[case::at(TogglTimeEntry { at: "yesterday".to_string(), ..dummy_time_entry(1) }, "at")]
+    fn test_try_from_time_entry_invalid(
+        #[case] entry: TogglTimeEntry,
+        #[case] expected_field: &str,
+    ) {
+        let result = TimeEntry::try_from(entry);
+
+        let error = result.unwrap_err();
+        assert!(
+            matches!(&error, TogglError::InvalidTimeEntry { id: 1, field, .. } if *field == expected_field)
+        );
+        assert!(error.to_string().contains("time entry 1"));
+    }
+
+    // 解釈できないタイムエントリーを含む場合に、strictの設定に応じて読み飛ばすかエラーとするかを確認するテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::lenient(false)]
+    #[case::strict(true)]
+    async fn test_read_time_entries_invalid_entry(#[case] strict: bool) {
+        // テストデータの作成
+        let start_at = DateTime::parse_from_rfc3339("2024-01-02T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let end_at = DateTime::parse_from_rfc3339("2024-01-03T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let invalid_entry = TogglTimeEntry {
+            id: 2,
+            start: "invalid".to_string(),
+            ..dummy_time_entry(2)
+        };
+        let time_entries = [dummy_time_entry(1), invalid_entry];
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let _m1 = server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(serde_json::to_string(&time_entries).unwrap())
+            .create_async()
+            .await;
+        let _m2 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body(serde_json::to_string(&[dummy_projects(1)]).unwrap())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient {
+            strict,
+            ..TogglClient::new_test(&url, "test").unwrap()
+        };
+        let result = client.read_time_entries(&start_at, &end_at).await;
+        if strict {
+            let error = result.unwrap_err();
+            assert!(matches!(
+                error.downcast_ref::<TogglError>(),
+                Some(TogglError::InvalidTimeEntry { id: 2, .. })
+            ));
+        } else {
+            let expected = vec![to_time_entry(&dummy_time_entry(1), &[dummy_projects(1)])];
+            assert_eq!(expected, result.unwrap());
+        }
+    }
+
+    // 期間を最大の長さごとに分割できることを確認するテスト
+    #[rstest]
+    #[case::within(1, 2, vec![(1, 2)])]
+    #[case::exact(1, 3, vec![(1, 3)])]
+    #[case::split(1, 7, vec![(1, 3), (3, 5), (5, 7)])]
+    #[case::remainder(1, 6, vec![(1, 3), (3, 5), (5, 6)])]
+    #[case::empty(3, 3, vec![])]
+    #[case::reversed(5, 3, vec![])]
+    fn test_split_range(
+        #[case] start_day: u32,
+        #[case] end_day: u32,
+        #[case] expected: Vec<(u32, u32)>,
+    ) {
+        let day = |day: u32| Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(start, end)| (day(start), day(end)))
+            .collect();
+
+        let result = split_range(&day(start_day), &day(end_day), TimeDelta::days(2));
+
+        assert_eq!(expected, result);
+    }
+
+    // 長い期間を分割して取得し、重複を除いて結合することを確認するテスト
+    #[tokio::test]
+    async fn test_read_time_entries_chunked() {
+        // テストデータの作成
+        let day = |day: u32| Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+        let expected_entries: Vec<TimeEntry> = [1, 2, 3]
+            .into_iter()
+            .map(|pattern| to_time_entry(&dummy_time_entry(pattern), &[]))
+            .collect();
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let _projects = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let mut chunks = Vec::new();
+        for (start, end, entries) in [
+            (1, 2, [dummy_time_entry(2), dummy_time_entry(1)]),
+            // 境界をまたぐタイムエントリーは両方の期間で返される
+            (2, 3, [dummy_time_entry(3), dummy_time_entry(1)]),
+        ] {
+            let mock = server
+                .mock("GET", "/me/time_entries")
+                .match_query(mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::UrlEncoded("start_date".into(), day(start).to_rfc3339()),
+                    mockito::Matcher::UrlEncoded("end_date".into(), day(end).to_rfc3339()),
+                ]))
+                .with_status(200)
+                .with_body(serde_json::to_string(&entries).unwrap())
+                .create_async()
+                .await;
+            chunks.push(mock);
+        }
+
+        // テストの実行
+        let client = TogglClient {
+            max_range: TimeDelta::days(1),
+            ..TogglClient::new_test(&url, "test").unwrap()
+        };
+        let mut time_entries = client.read_time_entries(&day(1), &day(3)).await.unwrap();
+        for chunk in chunks {
+            chunk.assert_async().await;
+        }
+        time_entries.sort_by_key(|entry| entry.id);
+        assert_eq!(expected_entries, time_entries);
+    }
+
+    // 件数の上限に達した場合に、最も古い開始日時より前を続けて取得することを確認するテスト
+    #[tokio::test]
+    async fn test_read_time_entries_paginated() {
+        // テストデータの作成
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+        // dummy_time_entry(1)の開始日時の1秒後
+        let next_end_at = Utc.with_ymd_and_hms(2024, 1, 1, 16, 2, 4).unwrap();
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let _projects = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let mut pages = Vec::new();
+        for (end, entries) in [
+            (end_at, vec![dummy_time_entry(2), dummy_time_entry(1)]),
+            (next_end_at, vec![dummy_time_entry(1)]),
+        ] {
+            let mock = server
+                .mock("GET", "/me/time_entries")
+                .match_query(mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::UrlEncoded("start_date".into(), start_at.to_rfc3339()),
+                    mockito::Matcher::UrlEncoded("end_date".into(), end.to_rfc3339()),
+                ]))
+                .with_status(200)
+                .with_body(serde_json::to_string(&entries).unwrap())
+                .create_async()
+                .await;
+            pages.push(mock);
+        }
+
+        // テストの実行
+        let client = TogglClient {
+            page_limit: 2,
+            ..TogglClient::new_test(&url, "test").unwrap()
+        };
+        let time_entries = client.read_time_entries(&start_at, &end_at).await.unwrap();
+        for page in pages {
+            page.assert_async().await;
+        }
+        let ids: Vec<i64> = time_entries.iter().map(|entry| entry.id).collect();
+        assert_eq!(vec![2, 1], ids);
+    }
+
+    // 取得できる過去の期間より前を指定した場合に、期間内のみを取得して注意を表示することを確認するテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::within(day(2), day(10), Some(day(2)), false)]
+    #[case::partially_before(
+        Utc.with_ymd_and_hms(2023, 12, 25, 0, 0, 0).unwrap(),
+        day(10),
+        Some(day(2)),
+        true
+    )]
+    #[case::entirely_before(
+        Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 12, 2, 0, 0, 0).unwrap(),
+        None,
+        true
+    )]
+    async fn test_read_time_entries_history_window(
+        #[case] start_at: DateTime<Utc>,
+        #[case] end_at: DateTime<Utc>,
+        #[case] expected_start_at: Option<DateTime<Utc>>,
+        #[case] expected_notice: bool,
+    ) {
+        // テストデータの作成
+        let response = vec![dummy_time_entry(1)];
+        let projects = [dummy_projects(1)];
+        let expected = expected_start_at
+            .map(|_| vec![to_time_entry(&response[0], &projects)])
+            .unwrap_or_default();
+        mock_datetime::set_mock_time(day(11));
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let _projects = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .create_async()
+            .await;
+        let m1 = server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded(
+                    "start_date".into(),
+                    expected_start_at.unwrap_or(start_at).to_rfc3339(),
+                ),
+                mockito::Matcher::UrlEncoded("end_date".into(), end_at.to_rfc3339()),
+            ]))
+            .with_status(200)
+            .with_body(serde_json::to_string(&response).unwrap())
+            .expect(usize::from(expected_start_at.is_some()))
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient {
+            history_window: Some(TimeDelta::days(9)),
+            ..TogglClient::new_test(&url, "test").unwrap()
+        };
+        mock_notice::take_notices();
+        let result = client.read_time_entries(&start_at, &end_at).await;
+        mock_datetime::clear_mock_time();
+        let notices = mock_notice::take_notices();
+        m1.assert_async().await;
+        assert_eq!(expected, result.unwrap());
+        // 取得しない期間がある場合は、ログレベルによらず注意を表示する
+        assert_eq!(usize::from(expected_notice), notices.len());
+        assert!(
+            notices
+                .iter()
+                .all(|notice| notice.contains("last 9 days")
+                    && notice.contains(&start_at.to_string()))
+        );
+    }
+
+    // 取得できる過去の期間より前を指定した場合に、警告する取得できない期間を返すことを確認するテスト
+    #[rstest]
+    #[case::within(day(2), day(10), None)]
+    #[case::partially_before(day(1), day(10), Some((day(1), day(2))))]
+    #[case::entirely_before(
+        Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2023, 12, 2, 0, 0, 0).unwrap(),
+        Some((
+            Utc.with_ymd_and_hms(2023, 12, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 12, 2, 0, 0, 0).unwrap(),
+        ))
+    )]
+    fn test_excluded_by_history_window(
+        #[case] start_at: DateTime<Utc>,
+        #[case] end_at: DateTime<Utc>,
+        #[case] expected: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) {
+        mock_datetime::set_mock_time(day(11));
+        let client = TogglClient {
+            history_window: Some(TimeDelta::days(9)),
+            ..TogglClient::new_test("http://localhost", "test").unwrap()
+        };
+
+        let result = client.excluded_by_history_window(&start_at, &end_at);
+        mock_datetime::clear_mock_time();
+
+        assert_eq!(expected, result);
+    }
+
+    /// テスト用に2024年1月の日付を作成する。
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()
+    }
+
+    // 実行中のタイムエントリーを取得する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::running(Some(dummy_time_entry(3)))]
+    #[case::not_running(None)]
+    async fn test_read_current_time_entry(#[case] current: Option<TogglTimeEntry>) {
+        // テストデータの作成
+        let api_token = "test";
+        let projects = [dummy_projects(1)];
+        let expected_entry = current
+            .as_ref()
+            .map(|entry| to_project_time_entry(entry, &projects));
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("GET", "/me/time_entries/current")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&current).unwrap())
+            .create_async()
+            .await;
+        // 実行中のタイムエントリーがない場合は、名前を解決しない
+        let m2 = server
+            .mock("GET", "/me/projects")
+            .match_header("Authorization", authorization.as_str())
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .expect(usize::from(current.is_some()))
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let time_entry = client.read_current_time_entry().await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected_entry, time_entry);
+    }
+
+    // 名前解決に利用するリソースは1度だけ取得し、単一のタイムエントリーの名前解決にも利用する場合のテスト
+    #[tokio::test]
+    async fn test_read_resources_once() {
+        // テストデータの作成
+        let api_token = "test";
+        let projects = [dummy_projects(1)];
+        let current = dummy_time_entry(1);
+        let expected_entry = to_time_entry(&current, &projects);
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("GET", "/me/time_entries/current")
+            .with_status(200)
+            .with_body(serde_json::to_string(&current).unwrap())
+            .create_async()
+            .await;
+        let m3 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .expect(1)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        client.read_time_entries(&start_at, &end_at).await.unwrap();
+        client.read_time_entries(&start_at, &end_at).await.unwrap();
+        let time_entry = client.read_current_time_entry().await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        m3.assert_async().await;
+        for mock in resources {
+            mock.assert_async().await;
+        }
+        assert_eq!(Some(expected_entry), time_entry);
+    }
+
+    // IDを指定してタイムエントリーを取得する場合のテスト
+    #[tokio::test]
+    async fn test_read_time_entry() {
+        // テストデータの作成
+        let api_token = "test";
+        let response = dummy_time_entry(1);
+        let projects = [dummy_projects(1)];
+        let expected_entry = to_project_time_entry(&response, &projects);
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("GET", "/me/time_entries/1")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&response).unwrap())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let time_entry = client.read_time_entry(1).await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected_entry, time_entry);
+    }
+
+    // プロジェクト一覧を取得する場合のテスト
+    #[tokio::test]
+    async fn test_read_projects() {
+        // テストデータの作成
+        let api_token = "test";
+        let projects = [dummy_projects(1), dummy_projects(2)];
+        let expected = vec![
+            Project {
+                id: 1,
+                workspace_id: 10,
+                name: "project 1".to_string(),
+            },
+            Project {
+                id: 2,
+                workspace_id: 10,
+                name: "project 2".to_string(),
+            },
+        ];
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("GET", "/me/projects")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let result = client.read_projects().await.unwrap();
+        m1.assert_async().await;
+        assert_eq!(expected, result);
+    }
+
+    // デフォルトワークスペースを取得する場合のテスト
+    #[tokio::test]
+    async fn test_read_default_workspace_id() {
+        // テストデータの作成
+        let api_token = "test";
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("GET", "/me")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(r#"{"id": 100, "default_workspace_id": 10, "email": "test@example.com"}"#)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let result = client.read_default_workspace_id().await.unwrap();
+        m1.assert_async().await;
+        assert_eq!(10, result);
+    }
+
+    // 参加している全てのワークスペースを取得する場合のテスト
+    #[tokio::test]
+    async fn test_read_workspace_ids() {
+        // テストデータの作成
+        let api_token = "test";
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("GET", "/me/workspaces")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(r#"[{"id": 10, "name": "workspace 1"}, {"id": 20, "name": "workspace 2"}]"#)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let result = client.read_workspace_ids().await.unwrap();
+        m1.assert_async().await;
+        assert_eq!(vec![10, 20], result);
+    }
+
+    // 読み込み系の操作に失敗する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::current_code403("/me/time_entries/current", 403)]
+    #[case::current_code500("/me/time_entries/current", 500)]
+    #[case::entry_code404("/me/time_entries/1", 404)]
+    #[case::entry_code500("/me/time_entries/1", 500)]
+    #[case::projects_code403("/me/projects", 403)]
+    #[case::projects_code500("/me/projects", 500)]
+    #[case::me_code403("/me", 403)]
+    #[case::me_code500("/me", 500)]
+    async fn test_read_error(#[case] path: &str, #[case] error_code: usize) {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock("GET", path)
+            .with_status(error_code)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = match path {
+            "/me/time_entries/current" => client.read_current_time_entry().await.map(|_| ()),
+            "/me/time_entries/1" => client.read_time_entry(1).await.map(|_| ()),
+            "/me/projects" => client.read_projects().await.map(|_| ()),
+            "/me" => client.read_default_workspace_id().await.map(|_| ()),
+            _ => panic!("Invalid path: {}", path),
+        };
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // タイムエントリーの作成に成功する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::running(
+        None,
+        serde_json::json!({
+            "created_with": "tooggls",
+            "workspace_id": 10,
+            "description": "entry 1",
+            "project_id": 1,
+            "tags": ["tag 1"],
+            "start": "2024-01-01T16:02:03Z",
+            "duration": -1,
+        }),
+    )]
+    #[case::stopped(
+        Some(DateTime::parse_from_rfc3339("2024-01-02T01:02:04+09:00").unwrap().to_utc()),
+        serde_json::json!({
+            "created_with": "tooggls",
+            "workspace_id": 10,
+            "description": "entry 1",
+            "project_id": 1,
+            "tags": ["tag 1"],
+            "start": "2024-01-01T16:02:03Z",
+            "stop": "2024-01-01T16:02:04Z",
+            "duration": 1,
+        }),
+    )]
+    async fn test_create_time_entry(
+        #[case] stop: Option<DateTime<Utc>>,
+        #[case] expected_body: serde_json::Value,
+    ) {
+        // テストデータの作成
+        let api_token = "test";
+        let new_entry = NewTimeEntry {
+            workspace_id: 10,
+            start: DateTime::parse_from_rfc3339("2024-01-02T01:02:03+09:00")
+                .unwrap()
+                .to_utc(),
+            stop,
+            description: "entry 1".to_string(),
+            project_id: Some(1),
+            tags: vec!["tag 1".to_string()],
+        };
+        let response = dummy_time_entry(1);
+        let projects = [dummy_projects(1)];
+        let expected_entry = to_project_time_entry(&response, &projects);
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("POST", "/workspaces/10/time_entries")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(expected_body))
+            .with_status(200)
+            .with_body(serde_json::to_string(&response).unwrap())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("GET", "/me/projects")
+            .match_header("Authorization", authorization.as_str())
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let time_entry = client.create_time_entry(&new_entry).await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected_entry, time_entry);
+    }
+
+    // タイムエントリーの停止に成功する場合のテスト
+    #[tokio::test]
+    async fn test_stop_time_entry() {
+        // テストデータの作成
+        let api_token = "test";
+        let response = dummy_time_entry(2);
+        let expected_entry = to_project_time_entry(&response, &[]);
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("PATCH", "/workspaces/10/time_entries/2/stop")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .with_body(serde_json::to_string(&response).unwrap())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let time_entry = client.stop_time_entry(10, 2).await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected_entry, time_entry);
+    }
+
+    // タイムエントリーの更新に成功する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::description(
+        TimeEntryUpdate {
+            description: Some("entry 1".to_string()),
+            ..Default::default()
+        },
+        serde_json::json!({"description": "entry 1"}),
+    )]
+    #[case::project_and_tags(
+        TimeEntryUpdate {
+            project_id: Some(Some(1)),
+            tags: Some(vec!["tag 1".to_string()]),
+            ..Default::default()
+        },
+        serde_json::json!({"project_id": 1, "tags": ["tag 1"]}),
+    )]
+    #[case::clear_project(
+        TimeEntryUpdate {
+            project_id: Some(None),
+            ..Default::default()
+        },
+        serde_json::json!({"project_id": null}),
+    )]
+    #[case::stop_only(
+        TimeEntryUpdate {
+            stop: Some(DateTime::parse_from_rfc3339("2024-01-02T01:02:04+09:00").unwrap().to_utc()),
+            ..Default::default()
+        },
+        serde_json::json!({"stop": "2024-01-01T16:02:04Z"}),
+    )]
+    #[case::start_and_stop(
+        TimeEntryUpdate {
+            start: Some(DateTime::parse_from_rfc3339("2024-01-02T01:02:03+09:00").unwrap().to_utc()),
+            stop: Some(DateTime::parse_from_rfc3339("2024-01-02T01:02:04+09:00").unwrap().to_utc()),
+            ..Default::default()
+        },
+        serde_json::json!({
+            "start": "2024-01-01T16:02:03Z",
+            "stop": "2024-01-01T16:02:04Z",
+            "duration": 1,
+        }),
+    )]
+    async fn test_update_time_entry(
+        #[case] update: TimeEntryUpdate,
+        #[case] expected_body: serde_json::Value,
+    ) {
+        // テストデータの作成
+        let api_token = "test";
+        let response = dummy_time_entry(1);
+        let projects = [dummy_projects(1)];
+        let expected_entry = to_project_time_entry(&response, &projects);
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("PUT", "/workspaces/10/time_entries/1")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(expected_body))
+            .with_status(200)
+            .with_body(serde_json::to_string(&response).unwrap())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body(serde_json::to_string(&projects).unwrap())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let time_entry = client.update_time_entry(10, 1, &update).await.unwrap();
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected_entry, time_entry);
+    }
+
+    // タイムエントリーの削除に成功する場合のテスト
+    #[tokio::test]
+    async fn test_delete_time_entry() {
+        // テストデータの作成
+        let api_token = "test";
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let authorization = format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("{}:api_token", api_token))
+        );
+        let m1 = server
+            .mock("DELETE", "/workspaces/10/time_entries/1")
+            .match_header("Authorization", authorization.as_str())
+            .match_header("content-type", "application/json")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, api_token).unwrap();
+        let result = client.delete_time_entry(10, 1).await;
+        m1.assert_async().await;
+        assert!(result.is_ok());
+    }
+
+    // タイムエントリーの書き込み系の操作に失敗する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::create_code400("POST", "/workspaces/10/time_entries", 400)]
+    #[case::create_code500("POST", "/workspaces/10/time_entries", 500)]
+    #[case::stop_code404("PATCH", "/workspaces/10/time_entries/1/stop", 404)]
+    #[case::stop_code500("PATCH", "/workspaces/10/time_entries/1/stop", 500)]
+    #[case::update_code403("PUT", "/workspaces/10/time_entries/1", 403)]
+    #[case::update_code500("PUT", "/workspaces/10/time_entries/1", 500)]
+    #[case::delete_code404("DELETE", "/workspaces/10/time_entries/1", 404)]
+    #[case::delete_code500("DELETE", "/workspaces/10/time_entries/1", 500)]
+    async fn test_write_time_entry_error(
+        #[case] method: &str,
+        #[case] path: &str,
+        #[case] error_code: usize,
+    ) {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock(method, path)
+            .with_status(error_code)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = match method {
+            "POST" => client
+                .create_time_entry(&NewTimeEntry {
+                    workspace_id: 10,
+                    start: DateTime::parse_from_rfc3339("2024-01-02T01:02:03+09:00")
+                        .unwrap()
+                        .to_utc(),
+                    stop: None,
+                    description: "entry 1".to_string(),
+                    project_id: None,
+                    tags: vec![],
+                })
+                .await
+                .map(|_| ()),
+            "PATCH" => client.stop_time_entry(10, 1).await.map(|_| ()),
+            "PUT" => client
+                .update_time_entry(10, 1, &TimeEntryUpdate::default())
+                .await
+                .map(|_| ()),
+            "DELETE" => client.delete_time_entry(10, 1).await,
+            _ => panic!("Invalid method: {}", method),
+        };
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // タグIDからタグ名を解決する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::resolved(vec![1], vec!["old name"], vec!["tag 1"])]
+    #[case::unknown_id(vec![1, 9], vec!["old name", "tag 9"], vec!["old name", "tag 9"])]
+    #[case::no_ids(vec![], vec!["tag 2"], vec!["tag 2"])]
+    async fn test_read_time_entries_tag_names(
+        #[case] tag_ids: Vec<i64>,
+        #[case] tags: Vec<&str>,
+        #[case] expected: Vec<&str>,
+    ) {
+        let start_at = DateTime::parse_from_rfc3339("2024-01-02T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let end_at = DateTime::parse_from_rfc3339("2024-01-03T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let entry = TogglTimeEntry {
+            tag_ids,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..dummy_time_entry(1)
+        };
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(serde_json::to_string(&[entry]).unwrap())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let time_entries = client.read_time_entries(&start_at, &end_at).await.unwrap();
+        assert_eq!(expected, time_entries[0].tags);
+    }
+
+    // 一覧が空の場合にnullを返すリソースを空の一覧として扱うテスト
+    #[tokio::test]
+    async fn test_read_time_entries_null_resources() {
+        let start_at = DateTime::parse_from_rfc3339("2024-01-02T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let end_at = DateTime::parse_from_rfc3339("2024-01-03T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let entry = dummy_time_entry(1);
+        let mut expected = to_time_entry(&entry, &[]);
+        expected.workspace = None;
+        expected.client = None;
+        expected.task = None;
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(serde_json::to_string(&[entry]).unwrap())
+            .create_async()
+            .await;
+        let mut mocks = Vec::new();
+        for path in [
+            "/me/projects",
+            "/me/clients",
+            "/me/workspaces",
+            "/me/tags",
+            "/me/tasks",
+        ] {
+            let mock = server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body("null")
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let time_entries = client.read_time_entries(&start_at, &end_at).await.unwrap();
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        assert_eq!(vec![expected], time_entries);
+    }
+
+    // 名前解決に利用するリソースの取得に失敗する場合のテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::clients("/me/clients")]
+    #[case::workspaces("/me/workspaces")]
+    #[case::tags("/me/tags")]
+    #[case::tasks("/me/tasks")]
+    async fn test_read_time_entries_error_resources(#[case] path: &str) {
+        let start_at = DateTime::parse_from_rfc3339("2024-01-02T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+        let end_at = DateTime::parse_from_rfc3339("2024-01-03T00:00:00+09:00")
+            .unwrap()
+            .to_utc();
+
+        // モックサーバーの起動
+        // 先に作成したモックが優先されるため、失敗するモックを先に作成する
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let m1 = server
+            .mock("GET", path)
+            .with_status(500)
+            .create_async()
+            .await;
+        let _resources = mock_resources(&mut server).await;
+        server
+            .mock("GET", "/me/time_entries")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/me/projects")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client.read_time_entries(&start_at, &end_at).await;
+        m1.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    // テスト用に待機時間を短くした再送の方針を作成するヘルパー関数
+    fn test_retry_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: std::time::Duration::from_millis(1),
+            max_backoff: std::time::Duration::from_millis(10),
+        }
+    }
+
+    // プロジェクト以外の名前解決に利用するリソースのモックを作成するヘルパー関数
+    //
+    // モックはdropされると削除されるため、テスト中は戻り値を保持する必要がある
+    async fn mock_resources(server: &mut ServerGuard) -> Vec<Mock> {
+        let resources = [
+            (
+                "/me/clients",
+                r#"[{"id": 1, "name": "client 1", "wid": 10}]"#,
+            ),
+            ("/me/workspaces", r#"[{"id": 10, "name": "workspace 1"}]"#),
+            (
+                "/me/tags",
+                r#"[{"id": 1, "name": "tag 1", "workspace_id": 10}]"#,
+            ),
+            (
+                "/me/tasks",
+                r#"[{"id": 1000, "name": "task 1", "project_id": 1, "workspace_id": 10}]"#,
+            ),
+        ];
+        let mut mocks = Vec::new();
+        for (path, body) in resources {
+            let mock = server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        mocks
+    }
+
+    // 環境変数を一時的に変更するヘルパー関数
+    fn with_env_var<T>(key: &str, value: Option<&str>, test: impl FnOnce() -> T) -> T {
+        let _lock = ENV_MUTEX.lock().unwrap();
+        let original_value = env::var(key).ok();
 
         match value {
             Some(new_value) => env::set_var(key, new_value),
@@ -379,21 +2548,51 @@ mod tests {
         match pattern {
             // 基本的な設定
             1 => TogglTimeEntry {
+                id: 1,
+                workspace_id: 10,
+                user_id: 100,
                 description: "entry 1".to_string(),
                 project_id: Some(1),
+                task_id: Some(1000),
+                billable: true,
                 start: "2024-01-02T01:02:03+09:00".to_string(),
                 stop: Some("2024-01-02T01:02:04+09:00".to_string()),
                 duration: 1,
+                at: "2024-01-02T01:02:05+09:00".to_string(),
                 tags: vec!["tag 1".to_string()],
+                tag_ids: vec![],
             },
             // no project, no tags
             2 => TogglTimeEntry {
+                id: 2,
+                workspace_id: 10,
+                user_id: 100,
                 description: "entry 2".to_string(),
                 project_id: None,
+                task_id: None,
+                billable: false,
                 start: "2024-01-02T01:03:00+09:00".to_string(),
                 stop: Some("2024-01-02T01:04:00+09:00".to_string()),
                 duration: 60,
+                at: "2024-01-02T01:04:00+09:00".to_string(),
                 tags: vec![],
+                tag_ids: vec![],
+            },
+            // 実行中
+            3 => TogglTimeEntry {
+                id: 3,
+                workspace_id: 10,
+                user_id: 100,
+                description: "entry 3".to_string(),
+                project_id: Some(1),
+                task_id: None,
+                billable: false,
+                start: "2024-01-02T02:00:00+09:00".to_string(),
+                stop: None,
+                duration: -1704128400,
+                at: "2024-01-02T02:00:00+09:00".to_string(),
+                tags: vec![],
+                tag_ids: vec![],
             },
             _ => panic!("Invalid pattern: {}", pattern),
         }
@@ -404,11 +2603,17 @@ mod tests {
         match pattern {
             1 => TogglProject {
                 id: 1,
+                workspace_id: 10,
+                client_id: Some(1),
                 name: "project 1".to_string(),
+                color: "#06aaf5".to_string(),
             },
             2 => TogglProject {
                 id: 2,
+                workspace_id: 10,
+                client_id: None,
                 name: "project 2".to_string(),
+                color: "#c56bff".to_string(),
             },
             _ => panic!("Invalid pattern: {}", pattern),
         }
@@ -417,28 +2622,54 @@ mod tests {
     // Toggl Time EntryからTime Entryに変換する。
     //
     // 期待値の計算のため、計算時間を考慮せず、naiveな実装としている
+    /// プロジェクトのみを解決した、単一のタイムエントリーの期待値を作成する。
+    fn to_project_time_entry(entry: &TogglTimeEntry, projects: &[TogglProject]) -> TimeEntry {
+        TimeEntry {
+            client: None,
+            workspace: None,
+            task: None,
+            ..to_time_entry(entry, projects)
+        }
+    }
+
     fn to_time_entry(entry: &TogglTimeEntry, projects: &[TogglProject]) -> TimeEntry {
         let start = DateTime::parse_from_rfc3339(&entry.start).unwrap().to_utc();
         let stop = entry
             .stop
             .clone()
             .map(|stop| DateTime::parse_from_rfc3339(&stop).unwrap().to_utc());
+        let at = DateTime::parse_from_rfc3339(&entry.at).unwrap().to_utc();
         let project = entry
             .project_id
-            .map(|id| {
-                projects
-                    .iter()
-                    .find(|project| project.id == id)
-                    .map(|project| project.name.clone())
-            })
-            .unwrap_or_default();
+            .and_then(|id| projects.iter().find(|project| project.id == id));
 
         crate::time_entry::TimeEntry {
+            id: entry.id,
+            workspace_id: entry.workspace_id,
+            user_id: entry.user_id,
             start,
             stop,
             duration: entry.duration,
             description: entry.description.clone(),
-            project,
+            billable: entry.billable,
+            at,
+            project_id: entry.project_id,
+            project: project.map(|project| project.name.clone()),
+            project_color: project.map(|project| project.color.clone()),
+            client_id: project.and_then(|project| project.client_id),
+            // `mock_resources`で作成するリソースに合わせて名前を解決する
+            client: project
+                .and_then(|project| project.client_id)
+                .filter(|id| *id == 1)
+                .map(|_| "client 1".to_string()),
+            workspace: Some(entry.workspace_id)
+                .filter(|id| *id == 10)
+                .map(|_| "workspace 1".to_string()),
+            task_id: entry.task_id,
+            task: entry
+                .task_id
+                .filter(|id| *id == 1000)
+                .map(|_| "task 1".to_string()),
             tags: entry.tags.clone(),
         }
     }
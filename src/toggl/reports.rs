@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+#[cfg(test)]
+use mockall::automock;
+use reqwest::{header::HeaderMap, Method};
+use serde::{Deserialize, Serialize};
+
+use super::{to_time_entries, TogglClient, TogglTimeEntry};
+use crate::time_entry::TimeEntry;
+
+/// Reports APIの詳細レポートで、1回のリクエストで取得する件数。
+const DETAILED_PAGE_SIZE: usize = 50;
+
+#[cfg_attr(test, automock)]
+/// Toggl Reports APIと通信するためのリポジトリ。
+///
+/// 集計をサーバー側で行うため、タイムエントリーの一覧取得より長い期間を扱える。
+pub trait ReportsRepository {
+    /// 指定した単位とタグごとに集計したサマリーレポートを取得する。
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - 集計するワークスペースのID
+    /// * `start_date` - 集計する期間の開始日
+    /// * `end_date` - 集計する期間の終了日(この日を含む)
+    /// * `grouping` - 集計する単位
+    async fn read_summary(
+        &self,
+        workspace_id: i64,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        grouping: SummaryGrouping,
+    ) -> Result<Vec<SummaryGroup>>;
+
+    /// 詳細レポートから、名前を解決したタイムエントリーを取得する。
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace_id` - 取得するワークスペースのID
+    /// * `start_date` - 取得する期間の開始日
+    /// * `end_date` - 取得する期間の終了日(この日を含む)
+    async fn read_detailed_time_entries(
+        &self,
+        workspace_id: i64,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<Vec<TimeEntry>>;
+}
+
+/// サマリーレポートで集計する単位。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SummaryGrouping {
+    Projects,
+    Clients,
+}
+
+impl SummaryGrouping {
+    /// Reports APIに指定する値を返す。
+    fn as_str(&self) -> &'static str {
+        match self {
+            SummaryGrouping::Projects => "projects",
+            SummaryGrouping::Clients => "clients",
+        }
+    }
+}
+
+/// サマリーレポートの集計結果。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryGroup {
+    /// 集計単位の名前。該当する名前がない場合は空文字とする。
+    pub name: String,
+    /// 記録された時間の合計秒数。
+    pub seconds: i64,
+    /// タグのないタイムエントリーの合計秒数。
+    pub untagged: i64,
+    /// タグ名ごとの合計秒数。タグのないタイムエントリーは含まない。
+    pub tags: HashMap<String, i64>,
+}
+
+impl ReportsRepository for TogglClient {
+    async fn read_summary(
+        &self,
+        workspace_id: i64,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+        grouping: SummaryGrouping,
+    ) -> Result<Vec<SummaryGroup>> {
+        let request = |sub_grouping| {
+            let body = ReportsSummaryRequest {
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+                grouping: grouping.as_str(),
+                sub_grouping,
+            };
+            self.reports_request(
+                Method::POST,
+                &format!("/workspace/{}/summary/time_entries", workspace_id),
+            )
+            .json(&body)
+        };
+        // 複数のタグがあるタイムエントリーはタグごとに集計されるため、合計は別の集計から求める
+        let (summary, total_summary, resources) = tokio::join!(
+            self.send_read_json::<ReportsSummary>(request("tags"), &self.reports_url),
+            self.send_read_json::<ReportsSummary>(request("time_entries"), &self.reports_url),
+            self.read_resources()
+        );
+        let summary = summary.context("Failed to get summary report from toggl")?;
+        let total_summary = total_summary.context("Failed to get summary report from toggl")?;
+        let resources = resources?;
+
+        // 集計単位とタグのIDから名前を解決する
+        let names: HashMap<i64, &str> = match grouping {
+            SummaryGrouping::Projects => resources
+                .projects
+                .iter()
+                .map(|project| (project.id, project.name.as_str()))
+                .collect(),
+            SummaryGrouping::Clients => resources
+                .clients
+                .iter()
+                .map(|client| (client.id, client.name.as_str()))
+                .collect(),
+        };
+        let tag_names: HashMap<i64, &str> = resources
+            .tags
+            .iter()
+            .map(|tag| (tag.id, tag.name.as_str()))
+            .collect();
+        let totals: HashMap<Option<i64>, i64> = total_summary
+            .groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(|group| {
+                let seconds = group
+                    .sub_groups
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|sub_group| sub_group.seconds)
+                    .sum();
+                (group.id, seconds)
+            })
+            .collect();
+        let groups = summary
+            .groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(|group| {
+                let (untagged, tagged): (Vec<_>, Vec<_>) = group
+                    .sub_groups
+                    .unwrap_or_default()
+                    .into_iter()
+                    .partition(|sub_group| sub_group.id.is_none() && sub_group.title.is_none());
+                let tags = tagged
+                    .into_iter()
+                    .filter_map(|sub_group| {
+                        let name = sub_group.title.or_else(|| {
+                            sub_group
+                                .id
+                                .and_then(|id| tag_names.get(&id))
+                                .map(|name| name.to_string())
+                        })?;
+                        Some((name, sub_group.seconds))
+                    })
+                    .fold(HashMap::new(), |mut acc, (name, seconds)| {
+                        *acc.entry(name).or_insert(0) += seconds;
+                        acc
+                    });
+
+                SummaryGroup {
+                    name: group
+                        .id
+                        .and_then(|id| names.get(&id))
+                        .map(|name| name.to_string())
+                        .unwrap_or_default(),
+                    seconds: totals.get(&group.id).copied().unwrap_or_default(),
+                    untagged: untagged.iter().map(|sub_group| sub_group.seconds).sum(),
+                    tags,
+                }
+            })
+            .collect();
+
+        Ok(groups)
+    }
+
+    async fn read_detailed_time_entries(
+        &self,
+        workspace_id: i64,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<Vec<TimeEntry>> {
+        let (rows, resources) = tokio::join!(
+            self.read_detailed_rows(workspace_id, start_date, end_date),
+            self.read_resources()
+        );
+        let rows = rows.context("Failed to get detailed report from toggl")?;
+        let resources = resources?;
+
+        let toggl_time_entries = rows
+            .into_iter()
+            .flat_map(|row| {
+                row.time_entries
+                    .into_iter()
+                    .map(move |entry| TogglTimeEntry {
+                        id: entry.id,
+                        workspace_id,
+                        user_id: row.user_id,
+                        description: row.description.clone().unwrap_or_default(),
+                        project_id: row.project_id,
+                        task_id: row.task_id,
+                        billable: row.billable,
+                        start: entry.start,
+                        stop: entry.stop,
+                        duration: entry.seconds,
+                        at: entry.at,
+                        // タグ名はIDから解決する
+                        tags: vec![],
+                        tag_ids: row.tag_ids.clone().unwrap_or_default(),
+                    })
+            })
+            .collect();
+
+        Ok(to_time_entries(toggl_time_entries, resources, self.strict)?)
+    }
+}
+
+impl TogglClient {
+    /// Reports APIへの認証情報を設定したリクエストを作成する。
+    fn reports_request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.request_url(method, format!("{}{}", self.reports_url, path))
+    }
+
+    /// 詳細レポートの全ての行を、ページを辿って取得する。
+    async fn read_detailed_rows(
+        &self,
+        workspace_id: i64,
+        start_date: &NaiveDate,
+        end_date: &NaiveDate,
+    ) -> Result<Vec<ReportsDetailedRow>> {
+        let mut rows = Vec::new();
+        let mut cursor = None;
+        loop {
+            let body = ReportsDetailedRequest {
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+                page_size: DETAILED_PAGE_SIZE,
+                first_id: cursor.map(|(id, _)| id),
+                first_row_number: cursor.map(|(_, row_number)| row_number),
+            };
+            let request = self
+                .reports_request(
+                    Method::POST,
+                    &format!("/workspace/{}/search/time_entries", workspace_id),
+                )
+                .json(&body);
+            let response = self.send_read(request, &self.reports_url).await?;
+            // 続きがある場合は、次のページの開始位置がヘッダーで返される
+            cursor = next_cursor(response.headers());
+            let page = self
+                .read_json::<Option<Vec<ReportsDetailedRow>>>(response, &self.reports_url)
+                .await?;
+            rows.extend(page.unwrap_or_default());
+            if cursor.is_none() {
+                return Ok(rows);
+            }
+        }
+    }
+}
+
+/// 詳細レポートのレスポンスヘッダーから、次のページの開始位置を返す。
+///
+/// 続きがない場合は`None`を返す。
+fn next_cursor(headers: &HeaderMap) -> Option<(i64, i64)> {
+    let value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<i64>().ok())
+    };
+
+    value("X-Next-ID").zip(value("X-Next-Row-Number"))
+}
+
+/// サマリーレポートのリクエストボディ。
+#[derive(Debug, Serialize)]
+struct ReportsSummaryRequest {
+    start_date: String,
+    end_date: String,
+    grouping: &'static str,
+    sub_grouping: &'static str,
+}
+
+/// サマリーレポートのレスポンスをデシリアライズするための構造体。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ReportsSummary {
+    groups: Option<Vec<ReportsSummaryGroup>>,
+}
+
+/// サマリーレポートの集計単位ごとの結果。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ReportsSummaryGroup {
+    id: Option<i64>,
+    sub_groups: Option<Vec<ReportsSummarySubGroup>>,
+}
+
+/// サマリーレポートの下位の単位ごとの結果。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ReportsSummarySubGroup {
+    id: Option<i64>,
+    title: Option<String>,
+    seconds: i64,
+}
+
+/// 詳細レポートのリクエストボディ。
+#[derive(Debug, Serialize)]
+struct ReportsDetailedRequest {
+    start_date: String,
+    end_date: String,
+    page_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_row_number: Option<i64>,
+}
+
+/// 詳細レポートの行をデシリアライズするための構造体。
+///
+/// 説明やプロジェクトなどが同じタイムエントリーは1行にまとめられる。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ReportsDetailedRow {
+    user_id: i64,
+    description: Option<String>,
+    project_id: Option<i64>,
+    task_id: Option<i64>,
+    billable: bool,
+    tag_ids: Option<Vec<i64>>,
+    time_entries: Vec<ReportsTimeEntry>,
+}
+
+/// 詳細レポートの行に含まれるタイムエントリー。
+#[derive(Debug, Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct ReportsTimeEntry {
+    id: i64,
+    seconds: i64,
+    start: String,
+    stop: Option<String>,
+    at: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use chrono::{DateTime, NaiveDate};
+    use mockito::{Matcher, Mock, Server, ServerGuard};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::next_cursor;
+    use super::ReportsRepository;
+    use super::SummaryGroup;
+    use super::SummaryGrouping;
+    use crate::retry::RetryPolicy;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::TogglClient;
+    use crate::toggl_error::TogglError;
+
+    // サマリーレポートを指定した単位とタグで集計して取得できることを確認するテスト
+    #[tokio::test]
+    #[rstest]
+    #[case::projects(SummaryGrouping::Projects, "projects", "project 1")]
+    #[case::clients(SummaryGrouping::Clients, "clients", "client 1")]
+    async fn test_read_summary(
+        #[case] grouping: SummaryGrouping,
+        #[case] expected_grouping: &str,
+        #[case] expected_name: &str,
+    ) {
+        // テストデータの作成
+        let expected_body = json!({
+            "start_date": "2024-01-01",
+            "end_date": "2024-01-31",
+            "grouping": expected_grouping,
+            "sub_grouping": "tags",
+        });
+        let mut expected_total_body = expected_body.clone();
+        expected_total_body["sub_grouping"] = json!("time_entries");
+        let response = json!({
+            "groups": [
+                {
+                    "id": 1,
+                    "sub_groups": [
+                        {"id": 1, "title": null, "seconds": 3600},
+                        // タグのないタイムエントリーはタグとは別に集計する
+                        {"id": null, "title": null, "seconds": 600},
+                    ],
+                },
+                {
+                    "id": null,
+                    "sub_groups": [{"id": 2, "title": "tag 2", "seconds": 1200}],
+                },
+            ],
+        });
+        // 複数のタグがあるタイムエントリーも1回だけ集計される
+        let total_response = json!({
+            "groups": [
+                {
+                    "id": 1,
+                    "sub_groups": [
+                        {"id": null, "title": "entry 1", "seconds": 1800},
+                        {"id": null, "title": "entry 2", "seconds": 600},
+                    ],
+                },
+                {
+                    "id": null,
+                    "sub_groups": [{"id": null, "title": "entry 3", "seconds": 1200}],
+                },
+            ],
+        });
+        let expected = vec![
+            SummaryGroup {
+                name: expected_name.to_string(),
+                seconds: 2400,
+                untagged: 600,
+                tags: HashMap::from([("tag 1".to_string(), 3600)]),
+            },
+            SummaryGroup {
+                name: "".to_string(),
+                seconds: 1200,
+                untagged: 0,
+                tags: HashMap::from([("tag 2".to_string(), 1200)]),
+            },
+        ];
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("POST", "/workspace/10/summary/time_entries")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(expected_body))
+            .with_status(200)
+            .with_body(response.to_string())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("POST", "/workspace/10/summary/time_entries")
+            .match_header("content-type", "application/json")
+            .match_body(Matcher::Json(expected_total_body))
+            .with_status(200)
+            .with_body(total_response.to_string())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client
+            .read_summary(
+                10,
+                &NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                &NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                grouping,
+            )
+            .await;
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected, result.unwrap());
+    }
+
+    // サマリーレポートが空の場合のテスト
+    #[tokio::test]
+    async fn test_read_summary_empty() {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("POST", "/workspace/10/summary/time_entries")
+            .with_status(200)
+            .with_body(r#"{"groups": null}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let result = client
+            .read_summary(10, &date, &date, SummaryGrouping::Projects)
+            .await;
+        m1.assert_async().await;
+        assert!(result.unwrap().is_empty());
+    }
+
+    // POSTで送信するレポートの取得は、読み取りのみのためサーバーエラーの後に再送することを確認するテスト
+    #[tokio::test]
+    async fn test_read_reports_retried_on_server_error() {
+        // モックサーバーの起動
+        // 先に作成したモックが期待回数に達するまで優先される
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let summary_failure = server
+            .mock("POST", "/workspace/10/summary/time_entries")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let summary_success = server
+            .mock("POST", "/workspace/10/summary/time_entries")
+            .with_status(200)
+            .with_body(r#"{"groups": null}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let search_failure = server
+            .mock("POST", "/workspace/10/search/time_entries")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let search_success = server
+            .mock("POST", "/workspace/10/search/time_entries")
+            .with_status(200)
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test")
+            .unwrap()
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+            });
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let summary = client
+            .read_summary(10, &date, &date, SummaryGrouping::Projects)
+            .await;
+        let detailed = client.read_detailed_time_entries(10, &date, &date).await;
+        summary_failure.assert_async().await;
+        summary_success.assert_async().await;
+        search_failure.assert_async().await;
+        search_success.assert_async().await;
+        assert!(summary.unwrap().is_empty());
+        assert!(detailed.unwrap().is_empty());
+    }
+
+    // Reports APIに接続できない場合に、Reports APIのURLを含む通信のエラーとなることを確認するテスト
+    #[tokio::test]
+    async fn test_read_detailed_time_entries_transport_error() {
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        // 接続を受け付けないポートを利用する
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let reports_url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        // テストの実行
+        let client = TogglClient {
+            reports_url: reports_url.clone(),
+            ..TogglClient::new_test(&url, "test").unwrap()
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let result = client.read_detailed_time_entries(10, &date, &date).await;
+        let error = result.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<TogglError>(),
+            Some(TogglError::Transport { url, .. }) if *url == reports_url
+        ));
+    }
+
+    // 詳細レポートのページを辿り、名前を解決したタイムエントリーを取得できることを確認するテスト
+    #[tokio::test]
+    async fn test_read_detailed_time_entries() {
+        // テストデータの作成
+        let first_page = json!([{
+            "user_id": 100,
+            "description": "entry 1",
+            "project_id": 1,
+            "task_id": 1000,
+            "billable": true,
+            "tag_ids": [1],
+            "time_entries": [
+                {"id": 1, "seconds": 60, "start": "2024-01-02T01:00:00+09:00", "stop": "2024-01-02T01:01:00+09:00", "at": "2024-01-02T01:01:00+09:00"},
+                {"id": 2, "seconds": 120, "start": "2024-01-02T02:00:00+09:00", "stop": "2024-01-02T02:02:00+09:00", "at": "2024-01-02T02:02:00+09:00"},
+            ],
+        }]);
+        let second_page = json!([{
+            "user_id": 100,
+            "description": null,
+            "project_id": null,
+            "task_id": null,
+            "billable": false,
+            "tag_ids": null,
+            "time_entries": [
+                {"id": 3, "seconds": 180, "start": "2024-01-03T01:00:00+09:00", "stop": "2024-01-03T01:03:00+09:00", "at": "2024-01-03T01:03:00+09:00"},
+            ],
+        }]);
+        let datetime = |value: &str| DateTime::parse_from_rfc3339(value).unwrap().to_utc();
+        let first = TimeEntry {
+            id: 1,
+            workspace_id: 10,
+            workspace: Some("workspace 1".to_string()),
+            user_id: 100,
+            start: datetime("2024-01-02T01:00:00+09:00"),
+            stop: Some(datetime("2024-01-02T01:01:00+09:00")),
+            duration: 60,
+            description: "entry 1".to_string(),
+            billable: true,
+            at: datetime("2024-01-02T01:01:00+09:00"),
+            project_id: Some(1),
+            project: Some("project 1".to_string()),
+            project_color: Some("#06aaf5".to_string()),
+            client_id: Some(1),
+            client: Some("client 1".to_string()),
+            task_id: Some(1000),
+            task: Some("task 1".to_string()),
+            tags: vec!["tag 1".to_string()],
+        };
+        let expected = vec![
+            first.clone(),
+            // 同じ行のタイムエントリーは、行の情報を共有する
+            TimeEntry {
+                id: 2,
+                start: datetime("2024-01-02T02:00:00+09:00"),
+                stop: Some(datetime("2024-01-02T02:02:00+09:00")),
+                duration: 120,
+                at: datetime("2024-01-02T02:02:00+09:00"),
+                ..first
+            },
+            TimeEntry {
+                id: 3,
+                workspace_id: 10,
+                workspace: Some("workspace 1".to_string()),
+                user_id: 100,
+                start: datetime("2024-01-03T01:00:00+09:00"),
+                stop: Some(datetime("2024-01-03T01:03:00+09:00")),
+                duration: 180,
+                at: datetime("2024-01-03T01:03:00+09:00"),
+                ..Default::default()
+            },
+        ];
+
+        // モックサーバーの起動
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let _resources = mock_resources(&mut server).await;
+        let m1 = server
+            .mock("POST", "/workspace/10/search/time_entries")
+            .match_body(Matcher::Json(json!({
+                "start_date": "2024-01-01",
+                "end_date": "2024-01-31",
+                "page_size": 50,
+            })))
+            .with_status(200)
+            .with_header("X-Next-ID", "3")
+            .with_header("X-Next-Row-Number", "2")
+            .with_body(first_page.to_string())
+            .create_async()
+            .await;
+        let m2 = server
+            .mock("POST", "/workspace/10/search/time_entries")
+            .match_body(Matcher::Json(json!({
+                "start_date": "2024-01-01",
+                "end_date": "2024-01-31",
+                "page_size": 50,
+                "first_id": 3,
+                "first_row_number": 2,
+            })))
+            .with_status(200)
+            .with_body(second_page.to_string())
+            .create_async()
+            .await;
+
+        // テストの実行
+        let client = TogglClient::new_test(&url, "test").unwrap();
+        let result = client
+            .read_detailed_time_entries(
+                10,
+                &NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                &NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            )
+            .await;
+        m1.assert_async().await;
+        m2.assert_async().await;
+        assert_eq!(expected, result.unwrap());
+    }
+
+    // 次のページの開始位置をヘッダーから取得できることを確認するテスト
+    #[rstest]
+    #[case::next(Some("10"), Some("51"), Some((10, 51)))]
+    #[case::last_page(None, None, None)]
+    #[case::missing_row_number(Some("10"), None, None)]
+    #[case::invalid(Some("ten"), Some("51"), None)]
+    fn test_next_cursor(
+        #[case] next_id: Option<&str>,
+        #[case] next_row_number: Option<&str>,
+        #[case] expected: Option<(i64, i64)>,
+    ) {
+        let mut headers = HeaderMap::new();
+        if let Some(next_id) = next_id {
+            headers.insert("X-Next-ID", HeaderValue::from_str(next_id).unwrap());
+        }
+        if let Some(next_row_number) = next_row_number {
+            headers.insert(
+                "X-Next-Row-Number",
+                HeaderValue::from_str(next_row_number).unwrap(),
+            );
+        }
+
+        assert_eq!(expected, next_cursor(&headers));
+    }
+
+    // 名前解決に利用するリソースのモックを作成するヘルパー関数
+    //
+    // モックはdropされると削除されるため、テスト中は戻り値を保持する必要がある
+    async fn mock_resources(server: &mut ServerGuard) -> Vec<Mock> {
+        let resources = [
+            (
+                "/me/projects",
+                json!([{"id": 1, "workspace_id": 10, "client_id": 1, "name": "project 1", "color": "#06aaf5"}]),
+            ),
+            ("/me/clients", json!([{"id": 1, "name": "client 1"}])),
+            ("/me/workspaces", json!([{"id": 10, "name": "workspace 1"}])),
+            ("/me/tags", json!([{"id": 1, "name": "tag 1"}])),
+            ("/me/tasks", json!([{"id": 1000, "name": "task 1"}])),
+        ];
+        let mut mocks = Vec::new();
+        for (path, body) in resources {
+            let mock = server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body.to_string())
+                .create_async()
+                .await;
+            mocks.push(mock);
+        }
+
+        mocks
+    }
+}
@@ -0,0 +1,185 @@
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Toggl APIとの通信で発生するエラー。
+///
+/// 呼び出し元で原因を判別できるよう、HTTPステータスとレスポンスのボディを保持する。
+#[derive(Debug, Error)]
+pub enum TogglError {
+    /// 認証に失敗した(401)。
+    #[error("Toggl API authentication failed ({status}): {body}")]
+    Unauthorized { status: StatusCode, body: String },
+    /// 権限がない(403)。
+    #[error("Toggl API access forbidden ({status}): {body}")]
+    Forbidden { status: StatusCode, body: String },
+    /// リソースが存在しない(404)。
+    #[error("Toggl API resource not found ({status}): {body}")]
+    NotFound { status: StatusCode, body: String },
+    /// レート制限を超えた(429)。
+    #[error("Toggl API rate limit exceeded ({status}): {body}")]
+    RateLimited { status: StatusCode, body: String },
+    /// サーバーエラー(5xx)。
+    #[error("Toggl API server error ({status}): {body}")]
+    Server { status: StatusCode, body: String },
+    /// 上記以外のエラーステータス。
+    #[error("Toggl API returned an error status ({status}): {body}")]
+    Status { status: StatusCode, body: String },
+    /// 通信に失敗した。
+    #[error("Failed to send request to Toggl API at {url}")]
+    Transport {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    /// レスポンスをデシリアライズできなかった。
+    #[error("Failed to deserialize response from Toggl API ({status}): {body}")]
+    Decode {
+        status: StatusCode,
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// タイムエントリーの内容を解釈できなかった。
+    #[error("Invalid {field} of time entry {id}: {value}")]
+    InvalidTimeEntry {
+        id: i64,
+        field: &'static str,
+        value: String,
+        #[source]
+        source: chrono::ParseError,
+    },
+}
+
+impl TogglError {
+    /// エラーステータスのレスポンスから`TogglError`を作成する。
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - レスポンスのステータス
+    /// * `body` - レスポンスのボディ
+    pub fn from_status(status: StatusCode, body: String) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized { status, body },
+            StatusCode::FORBIDDEN => Self::Forbidden { status, body },
+            StatusCode::NOT_FOUND => Self::NotFound { status, body },
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { status, body },
+            status if status.is_server_error() => Self::Server { status, body },
+            status => Self::Status { status, body },
+        }
+    }
+
+    /// プロセスの終了コードを返す。
+    ///
+    /// シェルから原因を判別できるよう、エラーの種類ごとに異なる値を返す。
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Unauthorized { .. } => 3,
+            Self::Forbidden { .. } => 4,
+            Self::NotFound { .. } => 5,
+            Self::RateLimited { .. } => 6,
+            Self::Server { .. } => 7,
+            Self::Status { .. } => 8,
+            Self::Transport { .. } => 9,
+            Self::Decode { .. } => 10,
+            Self::InvalidTimeEntry { .. } => 11,
+        }
+    }
+
+    /// エラーを解消するためのヒントを返す。
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Unauthorized { .. } => Some("Check that TOGGL_API_TOKEN is set to a valid API token."),
+            Self::Forbidden { .. } => Some(
+                "Check TOGGL_API_TOKEN and that the user has access to the workspace or entry.",
+            ),
+            Self::NotFound { .. } => {
+                Some("Check that the time entry or workspace exists and the base_url is correct.")
+            }
+            Self::RateLimited { .. } => Some(
+                "Wait a moment and try again, or lower requests_per_second in the config file.",
+            ),
+            Self::Server { .. } => Some("Toggl may be temporarily unavailable. Try again later."),
+            Self::Status { .. } => None,
+            Self::Transport { .. } => {
+                Some("Check the network connection and the base_url, proxy and ca_cert settings.")
+            }
+            Self::Decode { .. } => Some(
+                "The response format was unexpected. Check that base_url points to the Toggl API v9.",
+            ),
+            Self::InvalidTimeEntry { .. } => Some(
+                "Fix the time entry in Toggl, or set strict = false in the config file to skip it.",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use reqwest::StatusCode;
+    use rstest::rstest;
+
+    use super::TogglError;
+
+    /// ステータスに応じたエラーの種類になることを確認する。
+    #[rstest]
+    #[case::unauthorized(401, "Unauthorized")]
+    #[case::forbidden(403, "Forbidden")]
+    #[case::not_found(404, "NotFound")]
+    #[case::rate_limited(429, "RateLimited")]
+    #[case::internal_server_error(500, "Server")]
+    #[case::service_unavailable(503, "Server")]
+    #[case::bad_request(400, "Status")]
+    #[case::conflict(409, "Status")]
+    fn test_from_status(#[case] status: u16, #[case] expected: &str) {
+        let status = StatusCode::from_u16(status).unwrap();
+
+        let error = TogglError::from_status(status, "body".to_string());
+
+        assert!(format!("{:?}", error).starts_with(expected));
+        assert!(error.to_string().contains(&status.to_string()));
+        assert!(error.to_string().ends_with("body"));
+    }
+
+    /// エラーの種類ごとに異なる終了コードとなることを確認する。
+    #[test]
+    fn test_exit_code_distinct() {
+        let decode_error = serde_json::from_str::<i64>("not a number").unwrap_err();
+        let errors = [401, 403, 404, 429, 500, 400]
+            .into_iter()
+            .map(|status| {
+                TogglError::from_status(StatusCode::from_u16(status).unwrap(), String::new())
+            })
+            .chain([
+                TogglError::Decode {
+                    status: StatusCode::OK,
+                    body: String::new(),
+                    source: decode_error,
+                },
+                TogglError::InvalidTimeEntry {
+                    id: 1,
+                    field: "start",
+                    value: String::new(),
+                    source: chrono::DateTime::parse_from_rfc3339("").unwrap_err(),
+                },
+            ]);
+
+        let exit_codes: Vec<u8> = errors.map(|error| error.exit_code()).collect();
+
+        // 0は成功、1は一般的なエラー、2は引数のエラーに利用されるため使わない
+        assert!(exit_codes.iter().all(|code| *code > 2));
+        assert_eq!(
+            exit_codes.len(),
+            exit_codes.iter().collect::<HashSet<_>>().len()
+        );
+    }
+
+    /// 認証エラーの場合にAPIトークンを確認するヒントが返ることを確認する。
+    #[test]
+    fn test_hint_unauthorized() {
+        let error = TogglError::from_status(StatusCode::UNAUTHORIZED, String::new());
+
+        assert!(error.hint().unwrap().contains("TOGGL_API_TOKEN"));
+    }
+}
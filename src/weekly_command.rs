@@ -0,0 +1,418 @@
+use anyhow::Result;
+use log::info;
+
+use crate::aggregation::{calc_duration_grid, DurationGrid, GroupBy};
+use crate::date_expr::DateExpr;
+use crate::datetime::{now, Zone};
+use crate::fetch::{group_by_day, read_range_time_entries, FetchOptions};
+use crate::range::{DateRange, WeekStart};
+use crate::source::Source;
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 週毎の情報を出力するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct WeeklyArgs {
+    #[clap(
+        short = 'w',
+        long = "week",
+        help = "Sets a custom ISO week in the format YYYY-Www, or the week containing a date such as last week or 2 weeks ago",
+        conflicts_with = "date",
+        allow_hyphen_values = true
+    )]
+    week: Option<DateExpr>,
+
+    #[clap(
+        short = 'd',
+        long = "date",
+        help = "Sets the week containing a date such as YYYY-MM-DD, yesterday or last friday",
+        allow_hyphen_values = true
+    )]
+    date: Option<DateExpr>,
+
+    #[clap(
+        long = "week-start",
+        help = "Sets the first day of the week. With --week, the week starting on Sunday begins the day before the ISO week",
+        arg_enum,
+        default_value = "mon"
+    )]
+    week_start: WeekStart,
+
+    #[clap(
+        long = "include-running",
+        help = "Counts running time entries up to now. Cannot be used with --source reports"
+    )]
+    include_running: bool,
+
+    #[clap(
+        long = "no-clip",
+        help = "Counts whole time entries on the day they start instead of splitting them at midnight"
+    )]
+    no_clip: bool,
+
+    #[clap(
+        long = "source",
+        help = "Fetches time entries from the time entries API or the detailed report of the Reports API, which interprets dates in the timezone of the Toggl profile",
+        arg_enum,
+        default_value = "time-entries"
+    )]
+    source: Source,
+}
+
+pub struct WeeklyCommand<'a, T: TogglRepository, R: ReportsRepository> {
+    toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository, R: ReportsRepository> WeeklyCommand<'a, T, R> {
+    /// 新しい`WeeklyCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを週や日の範囲で切り詰めて集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 週や日の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `weekly`サブコマンドの処理を行う。
+    ///
+    /// 設定したタイムゾーンで指定された週のtime entryを、日付とプロジェクトごとに集計する。
+    /// 週や日付が指定されていない場合は、設定したタイムゾーンで現在の日付を含む週を利用する。
+    /// 実行中のタイムエントリーは、`--include-running`を指定した場合のみ現在までの経過時間で集計する。
+    pub async fn run(&self, weekly: WeeklyArgs) -> Result<DurationGrid> {
+        let today = now().with_timezone(&self.zone).date_naive();
+        let date = match weekly.week.or(weekly.date) {
+            Some(date) => date.resolve(&today)?,
+            None => today,
+        };
+        let range = DateRange::week(&date, weekly.week_start)?;
+        let (start_at, end_at) = range.to_utc_range(&self.zone)?;
+        info!("Start at: {}, End at: {}", start_at, end_at);
+
+        let options = FetchOptions {
+            source: weekly.source,
+            include_running: weekly.include_running,
+            clip: self.clip_to_range && !weekly.no_clip,
+        };
+        let time_entries = read_range_time_entries(
+            self.toggl_client,
+            self.reports_client,
+            &options,
+            &start_at,
+            &end_at,
+            &self.zone,
+        )
+        .await?;
+        let daily_time_entries = group_by_day(time_entries, options.clip, &self.zone)?;
+
+        Ok(calc_duration_grid(
+            &daily_time_entries,
+            &range.dates(),
+            GroupBy::Project,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::WeeklyArgs;
+    use super::WeeklyCommand;
+    use crate::aggregation::{DurationGridRow, CLIP_LOOKBACK_HOURS};
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::range::WeekStart;
+    use crate::source::Source;
+    use crate::time_entry::TimeEntry;
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// 指定した週や日付を含む週の、最初の日から翌週の最初の日までのタイムエントリーを取得することを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::week(Some("2024-W05"), None, WeekStart::Mon, (2024, 1, 29))]
+    #[case::week_from_sunday(Some("2024-W05"), None, WeekStart::Sun, (2024, 1, 28))]
+    #[case::date(None, Some("2024-02-04"), WeekStart::Mon, (2024, 1, 29))]
+    #[case::date_from_sunday(None, Some("2024-02-04"), WeekStart::Sun, (2024, 2, 4))]
+    #[case::current_week(None, None, WeekStart::Mon, (2024, 5, 13))]
+    #[case::last_week(Some("last week"), None, WeekStart::Mon, (2024, 5, 6))]
+    #[case::yesterday(None, Some("yesterday"), WeekStart::Mon, (2024, 5, 13))]
+    async fn test_run_week_range(
+        #[case] week: Option<&str>,
+        #[case] date: Option<&str>,
+        #[case] week_start: WeekStart,
+        #[case] expected_first_day: (i32, u32, u32),
+    ) {
+        mock_datetime::set_mock_time(local_datetime(2024, 5, 15, 12));
+        let first_day = NaiveDate::from_ymd_opt(
+            expected_first_day.0,
+            expected_first_day.1,
+            expected_first_day.2,
+        )
+        .unwrap();
+        let start_at = local_datetime(
+            expected_first_day.0,
+            expected_first_day.1,
+            expected_first_day.2,
+            0,
+        );
+        let end_at = Local
+            .from_local_datetime(
+                &(first_day + TimeDelta::days(7))
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            )
+            .unwrap()
+            .to_utc();
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(end_at),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let args = WeeklyArgs {
+            week: week.map(|week| week.parse().unwrap()),
+            date: date.map(|date| date.parse().unwrap()),
+            week_start,
+            ..default_args()
+        };
+
+        let reports = MockReportsRepository::new();
+        let result = WeeklyCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        let grid = result.unwrap();
+        let expected_dates: Vec<NaiveDate> = first_day.iter_days().take(7).collect();
+        assert_eq!(expected_dates, grid.dates);
+        assert!(grid.rows.is_empty());
+        assert_eq!(vec![0; 7], grid.date_totals);
+    }
+
+    /// 日付とプロジェクトごとに集計し、日付をまたぐタイムエントリーは日ごとに分割することを確認する。
+    ///
+    /// 切り詰めない場合は、開始日にタイムエントリーの全体を集計する。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, [3600, 2 * 3600, 2 * 3600, 0, 0, 0, 0], [3600, 0, 0, 0, 0, 0, 1800])]
+    #[case::no_clip(true, [3600, 4 * 3600, 0, 0, 0, 0, 0], [0, 0, 0, 0, 0, 0, 3600])]
+    async fn test_run_grid(
+        #[case] no_clip: bool,
+        #[case] expected_project_1: [i64; 7],
+        #[case] expected_project_2: [i64; 7],
+    ) {
+        let entries = vec![
+            dummy_entry(1, local_datetime(2024, 1, 29, 9), 3600, "project 1"),
+            dummy_entry(2, local_datetime(2024, 1, 30, 22), 4 * 3600, "project 1"),
+            // 前の週から続くタイムエントリーは、切り詰める場合のみ週に含まれる部分を集計する
+            dummy_entry(3, local_datetime(2024, 1, 28, 23), 2 * 3600, "project 2"),
+            dummy_entry(
+                4,
+                local_datetime(2024, 2, 4, 23) + TimeDelta::minutes(30),
+                3600,
+                "project 2",
+            ),
+        ];
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let args = WeeklyArgs {
+            week: Some("2024-W05".parse().unwrap()),
+            no_clip,
+            ..default_args()
+        };
+
+        let reports = MockReportsRepository::new();
+        let result = WeeklyCommand::new(&toggl, &reports).run(args).await;
+
+        let grid = result.unwrap();
+        let expected_rows = vec![
+            DurationGridRow {
+                name: "project 1".to_string(),
+                durations: expected_project_1.to_vec(),
+                total: expected_project_1.iter().sum(),
+                in_progress: false,
+            },
+            DurationGridRow {
+                name: "project 2".to_string(),
+                durations: expected_project_2.to_vec(),
+                total: expected_project_2.iter().sum(),
+                in_progress: false,
+            },
+        ];
+        let expected_date_totals: Vec<i64> = expected_project_1
+            .iter()
+            .zip(expected_project_2.iter())
+            .map(|(a, b)| a + b)
+            .collect();
+        assert_eq!(expected_rows, grid.rows);
+        assert_eq!(expected_date_totals, grid.date_totals);
+        assert_eq!(expected_date_totals.iter().sum::<i64>(), grid.total);
+    }
+
+    /// 指定したタイムゾーンの週と日の境界で、ホストのタイムゾーンによらず集計することを確認する。
+    ///
+    /// 東京の2024-W05は、UTCで2024-01-28 15:00から始まる。
+    #[tokio::test]
+    #[rstest]
+    #[case::clip(false, [3600, 3600, 0, 0, 0, 0, 0])]
+    #[case::no_clip(true, [2 * 3600, 0, 0, 0, 0, 0, 0])]
+    async fn test_run_time_zone(#[case] no_clip: bool, #[case] expected: [i64; 7]) {
+        // 東京の2024-01-29 23:00から2時間
+        let entry = dummy_entry(
+            1,
+            Utc.with_ymd_and_hms(2024, 1, 29, 14, 0, 0).unwrap(),
+            2 * 3600,
+            "project 1",
+        );
+        let start_at = Utc.with_ymd_and_hms(2024, 1, 28, 15, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 2, 4, 15, 0, 0).unwrap();
+        let fetch_start_at = if no_clip {
+            start_at
+        } else {
+            start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)
+        };
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .with(predicate::eq(fetch_start_at), predicate::eq(end_at))
+            .times(1)
+            .returning(move |_, _| Ok(vec![entry.clone()]));
+        let args = WeeklyArgs {
+            week: Some("2024-W05".parse().unwrap()),
+            no_clip,
+            ..default_args()
+        };
+
+        let reports = MockReportsRepository::new();
+        let result = WeeklyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(chrono_tz::Asia::Tokyo))
+            .run(args)
+            .await;
+
+        let grid = result.unwrap();
+        assert_eq!(expected.to_vec(), grid.rows[0].durations);
+        assert_eq!(2 * 3600, grid.total);
+    }
+
+    /// `--include-running`を指定した場合に、実行中のタイムエントリーを現在までの経過時間で集計し、印を付けることを確認する。
+    #[tokio::test]
+    #[rstest]
+    #[case::include(true, [3600, 1800, 0, 0, 0, 0, 0], true)]
+    #[case::exclude(false, [3600, 0, 0, 0, 0, 0, 0], false)]
+    async fn test_run_include_running(
+        #[case] include_running: bool,
+        #[case] expected: [i64; 7],
+        #[case] expected_in_progress: bool,
+    ) {
+        let start = local_datetime(2024, 1, 30, 9);
+        mock_datetime::set_mock_time(start + TimeDelta::minutes(30));
+        let entries = vec![
+            dummy_entry(1, local_datetime(2024, 1, 29, 9), 3600, "project 1"),
+            TimeEntry {
+                stop: None,
+                // Toggl APIでは実行中のタイムエントリーは負の値となる
+                duration: -start.timestamp(),
+                ..dummy_entry(2, start, 0, "project 1")
+            },
+        ];
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_read_time_entries()
+            .times(1)
+            .returning(move |_, _| Ok(entries.clone()));
+        let args = WeeklyArgs {
+            week: Some("2024-W05".parse().unwrap()),
+            include_running,
+            ..default_args()
+        };
+
+        let reports = MockReportsRepository::new();
+        let result = WeeklyCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        let grid = result.unwrap();
+        assert_eq!(expected.to_vec(), grid.rows[0].durations);
+        assert_eq!(expected_in_progress, grid.rows[0].in_progress);
+        assert_eq!(expected_in_progress, grid.in_progress);
+    }
+
+    /// Reports APIは実行中のタイムエントリーを返さないため、`--include-running`と同時に指定するとエラーになることを確認する。
+    #[tokio::test]
+    async fn test_run_reports_include_running() {
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_read_time_entries().never();
+        toggl.expect_read_workspace_ids().never();
+        let mut reports = MockReportsRepository::new();
+        reports.expect_read_detailed_time_entries().never();
+        let args = WeeklyArgs {
+            week: Some("2024-W05".parse().unwrap()),
+            include_running: true,
+            source: Source::Reports,
+            ..default_args()
+        };
+
+        let result = WeeklyCommand::new(&toggl, &reports).run(args).await;
+
+        assert!(format!("{:#}", result.unwrap_err())
+            .contains("Running time entries cannot be counted with --source reports"));
+    }
+
+    /// テスト用にデフォルトの引数を作成する。
+    fn default_args() -> WeeklyArgs {
+        WeeklyArgs {
+            week: None,
+            date: None,
+            week_start: WeekStart::Mon,
+            include_running: false,
+            no_clip: false,
+            source: Source::TimeEntries,
+        }
+    }
+
+    /// テスト用にLocalタイムゾーンの日時を作成する。
+    fn local_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にダミーのTimeEntryを作成する。
+    fn dummy_entry(id: i64, start: DateTime<Utc>, duration: i64, project: &str) -> TimeEntry {
+        TimeEntry {
+            id,
+            start,
+            stop: Some(start + TimeDelta::seconds(duration)),
+            duration,
+            description: format!("entry{}", id),
+            project: Some(project.to_string()),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use anyhow::Result;
+
+use crate::aggregation::MonthlyDurations;
+use crate::datetime::{now, Zone};
+use crate::range::{fiscal_year_of, DateRange};
+use crate::summary_args::{summarize_by_month, SummaryArgs};
+use crate::toggl::{ReportsRepository, TogglRepository};
+
+/// 年度毎の情報を出力するためのサブコマンド。
+#[derive(Debug, clap::Args)]
+pub struct YearlyArgs {
+    #[clap(
+        short = 'y',
+        long = "year",
+        help = "Sets a custom fiscal year, named after the calendar year in which it starts"
+    )]
+    year: Option<i32>,
+
+    #[clap(flatten)]
+    pub summary: SummaryArgs,
+}
+
+pub struct YearlyCommand<'a, T: TogglRepository, R: ReportsRepository> {
+    toggl_client: &'a T,
+    reports_client: &'a R,
+    clip_to_range: bool,
+    fiscal_year_start_month: u32,
+    zone: Zone,
+}
+
+impl<'a, T: TogglRepository, R: ReportsRepository> YearlyCommand<'a, T, R> {
+    /// 新しい`YearlyCommand`を返す。
+    ///
+    /// # Arguments
+    /// * `toggl_client` - Toggl APIと通信するためのリポジトリ
+    /// * `reports_client` - Toggl Reports APIと通信するためのリポジトリ
+    pub fn new(toggl_client: &'a T, reports_client: &'a R) -> Self {
+        Self {
+            toggl_client,
+            reports_client,
+            clip_to_range: true,
+            fiscal_year_start_month: 1,
+            zone: Zone::default(),
+        }
+    }
+
+    /// タイムエントリーを月の範囲で切り詰めて集計するかどうかを設定する。
+    ///
+    /// `--no-clip`を指定した場合は、この設定によらず切り詰めない。
+    pub fn clip_to_range(mut self, clip_to_range: bool) -> Self {
+        self.clip_to_range = clip_to_range;
+        self
+    }
+
+    /// 年度が始まる月(1から12)を設定する。
+    pub fn fiscal_year_start_month(mut self, fiscal_year_start_month: u32) -> Self {
+        self.fiscal_year_start_month = fiscal_year_start_month;
+        self
+    }
+
+    /// 月の境界とするタイムゾーンを設定する。
+    pub fn time_zone(mut self, zone: Zone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// `yearly`サブコマンドの処理を行う。
+    ///
+    /// 設定したタイムゾーンで指定された年度のtime entryを、月ごとに指定した単位とタグごとに集計する。
+    /// 年度が指定されていない場合は、設定したタイムゾーンで現在の日付を含む年度を利用する。
+    pub async fn run(&self, yearly: YearlyArgs) -> Result<MonthlyDurations> {
+        let year = yearly.year.unwrap_or_else(|| {
+            let today = now().with_timezone(&self.zone).date_naive();
+            fiscal_year_of(&today, self.fiscal_year_start_month)
+        });
+        let range = DateRange::fiscal_year(year, self.fiscal_year_start_month)?;
+
+        summarize_by_month(
+            self.toggl_client,
+            self.reports_client,
+            &range,
+            &yearly.summary,
+            self.clip_to_range,
+            &self.zone,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Local, NaiveDate, TimeDelta, TimeZone, Utc};
+    use mockall::predicate;
+    use rstest::rstest;
+
+    use super::YearlyArgs;
+    use super::YearlyCommand;
+    use crate::aggregation::CLIP_LOOKBACK_HOURS;
+    use crate::datetime::{mock_datetime, Zone};
+    use crate::toggl::{MockReportsRepository, MockTogglRepository};
+
+    /// 指定した年度や現在の日付を含む年度の月ごとに、タイムエントリーを1ヶ月ずつ取得することを確認する。
+    ///
+    /// 現在は2026-05-15として、現在より後に始まる月は取得しない。
+    #[tokio::test]
+    #[rstest]
+    #[case::calendar_year(Some(2024), 1, (2024, 1), 12)]
+    #[case::fiscal_year(Some(2024), 4, (2024, 4), 12)]
+    #[case::current_calendar_year(None, 1, (2026, 1), 5)]
+    #[case::current_fiscal_year(None, 4, (2026, 4), 2)]
+    #[case::current_fiscal_year_before_start(None, 10, (2025, 10), 8)]
+    async fn test_run_months(
+        #[case] year: Option<i32>,
+        #[case] start_month: u32,
+        #[case] expected_first_month: (i32, u32),
+        #[case] expected_fetched_months: u32,
+    ) {
+        mock_datetime::set_mock_time(local_datetime(2026, 5, 15, 12));
+        let first_month =
+            NaiveDate::from_ymd_opt(expected_first_month.0, expected_first_month.1, 1).unwrap();
+        let expected_months: Vec<NaiveDate> = (0..12)
+            .map(|index| first_month + chrono::Months::new(index))
+            .collect();
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_history_start().returning(|| None);
+        for month in expected_months
+            .iter()
+            .take(expected_fetched_months as usize)
+        {
+            let start_at = local_date(month);
+            let end_at = local_date(&(*month + chrono::Months::new(1)));
+            toggl
+                .expect_read_time_entries()
+                .with(
+                    predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                    predicate::eq(end_at),
+                )
+                .times(1)
+                .returning(|_, _| Ok(vec![]));
+        }
+        let args = YearlyArgs {
+            year,
+            summary: Default::default(),
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = YearlyCommand::new(&toggl, &reports)
+            .fiscal_year_start_month(start_month)
+            .run(args)
+            .await;
+        mock_datetime::clear_mock_time();
+
+        let monthly_durations = result.unwrap();
+        let months: Vec<NaiveDate> = monthly_durations
+            .months
+            .iter()
+            .map(|(month, _)| *month)
+            .collect();
+        assert_eq!(expected_months, months);
+        assert!(monthly_durations.total.is_empty());
+    }
+
+    /// タイムエントリーの一覧を取得できる過去の期間より前に終わる月は、取得せずに集計しなかった月とすることを確認する。
+    ///
+    /// 現在は2026-10-16として、取得できる過去の期間は90日前の2026-07-18からとする。
+    #[tokio::test]
+    async fn test_run_months_before_history() {
+        let now = local_datetime(2026, 10, 16, 12);
+        mock_datetime::set_mock_time(now);
+        let mut toggl = MockTogglRepository::new();
+        toggl
+            .expect_history_start()
+            .returning(move || Some(now - TimeDelta::days(90)));
+        for month in 7..=10 {
+            let start_at = local_date(&NaiveDate::from_ymd_opt(2026, month, 1).unwrap());
+            let end_at = local_date(&NaiveDate::from_ymd_opt(2026, month + 1, 1).unwrap());
+            toggl
+                .expect_read_time_entries()
+                .with(
+                    predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                    predicate::eq(end_at),
+                )
+                .times(1)
+                .returning(|_, _| Ok(vec![]));
+        }
+        let args = YearlyArgs {
+            year: None,
+            summary: Default::default(),
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = YearlyCommand::new(&toggl, &reports).run(args).await;
+        mock_datetime::clear_mock_time();
+
+        let monthly_durations = result.unwrap();
+        assert_eq!(12, monthly_durations.months.len());
+        assert!(monthly_durations
+            .months
+            .iter()
+            .all(|(_, durations)| durations.is_empty()));
+        // 取得しなかった月は、集計結果が空の月と区別できるよう記録する
+        let expected_skipped: Vec<NaiveDate> = (1..=6)
+            .map(|month| NaiveDate::from_ymd_opt(2026, month, 1).unwrap())
+            .collect();
+        assert_eq!(expected_skipped, monthly_durations.skipped);
+    }
+
+    /// 指定したタイムゾーンの月の境界で、ホストのタイムゾーンによらず取得することを確認する。
+    ///
+    /// 現在はUTCで2024-01-15とし、東京の2024-01はUTCで2023-12-31 15:00から始まる。
+    #[tokio::test]
+    async fn test_run_time_zone() {
+        mock_datetime::set_mock_time(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap());
+        let start_at = Utc.with_ymd_and_hms(2023, 12, 31, 15, 0, 0).unwrap();
+        let end_at = Utc.with_ymd_and_hms(2024, 1, 31, 15, 0, 0).unwrap();
+        let mut toggl = MockTogglRepository::new();
+        toggl.expect_history_start().returning(|| None);
+        toggl
+            .expect_read_time_entries()
+            .with(
+                predicate::eq(start_at - TimeDelta::hours(CLIP_LOOKBACK_HOURS)),
+                predicate::eq(end_at),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        let args = YearlyArgs {
+            year: None,
+            summary: Default::default(),
+        };
+        let reports = MockReportsRepository::new();
+
+        let result = YearlyCommand::new(&toggl, &reports)
+            .time_zone(Zone::Named(chrono_tz::Asia::Tokyo))
+            .run(args)
+            .await;
+        mock_datetime::clear_mock_time();
+
+        let monthly_durations = result.unwrap();
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            monthly_durations.months[0].0
+        );
+    }
+
+    /// テスト用にLocalタイムゾーンの日時を作成する。
+    fn local_datetime(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .to_utc()
+    }
+
+    /// テスト用にLocalタイムゾーンの日付の始まりの日時を作成する。
+    fn local_date(date: &NaiveDate) -> DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            .unwrap()
+            .to_utc()
+    }
+}